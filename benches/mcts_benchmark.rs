@@ -3,7 +3,7 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use port_game::domain::aggregates::Port;
 use port_game::domain::value_objects::PlayerId;
-use port_game::mcts::{MCTSConfig, MCTSEngine};
+use port_game::mcts::{ExplorationSchedule, MCTSConfig, MCTSEngine, RewardConfig};
 
 fn benchmark_mcts_search(c: &mut Criterion) {
     let mut group = c.benchmark_group("mcts_search");
@@ -19,6 +19,14 @@ fn benchmark_mcts_search(c: &mut Criterion) {
                     exploration_constant: 1.41,
                     max_depth: 20,
                     max_actions_per_turn: 3,
+                    reward: RewardConfig::default(),
+                    adversarial: false,
+                    max_nodes: 10_000,
+                    use_rave: false,
+                    rave_equivalence: 300.0,
+                    exploration_schedule: ExplorationSchedule::Constant,
+                    rollout_noise: 5.0,
+                    use_priors: false,
                 };
                 let mut engine = MCTSEngine::new(config);
                 let port = create_test_port();
@@ -45,6 +53,14 @@ fn benchmark_mcts_with_ships(c: &mut Criterion) {
                     exploration_constant: 1.41,
                     max_depth: 20,
                     max_actions_per_turn: 3,
+                    reward: RewardConfig::default(),
+                    adversarial: false,
+                    max_nodes: 10_000,
+                    use_rave: false,
+                    rave_equivalence: 300.0,
+                    exploration_schedule: ExplorationSchedule::Constant,
+                    rollout_noise: 5.0,
+                    use_priors: false,
                 };
                 let mut engine = MCTSEngine::new(config);
                 let port = create_port_with_ships(num_ships);
@@ -63,7 +79,7 @@ fn benchmark_tree_expansion(c: &mut Criterion) {
         let port = create_test_port();
         tree.init_root(port);
 
-        b.iter(|| tree.expand(black_box(0), 20));
+        b.iter(|| tree.expand(black_box(0), 20, usize::MAX));
     });
 }
 
@@ -72,7 +88,7 @@ fn benchmark_ucb1_calculation(c: &mut Criterion) {
         let mut tree = port_game::mcts::MCTSTree::new();
         let port = create_test_port();
         tree.init_root(port);
-        tree.expand(0, 20);
+        tree.expand(0, 20, usize::MAX);
 
         b.iter(|| tree.select_ucb1(black_box(1.41)));
     });
@@ -30,12 +30,25 @@ pub enum Command {
         ship_id: ShipId,
     },
 
+    /// Move an assigned crane straight to another docked ship
+    ReassignCrane {
+        player_id: PlayerId,
+        crane_id: CraneId,
+        new_ship_id: ShipId,
+    },
+
     /// Force undock a ship (emergency, penalty applied)
     ForceUndock {
         player_id: PlayerId,
         ship_id: ShipId,
     },
 
+    /// Unload docked ships with their assigned cranes
+    ProcessContainers { player_id: PlayerId },
+
+    /// Spend an action without changing the port (the turn goes on)
+    Pass { player_id: PlayerId },
+
     /// End player's turn (turn-based mode)
     EndTurn { player_id: PlayerId },
 
@@ -52,18 +65,37 @@ impl Command {
             Command::DockShip { player_id, .. } => *player_id,
             Command::AssignCrane { player_id, .. } => *player_id,
             Command::UnassignCrane { player_id, .. } => *player_id,
+            Command::ReassignCrane { player_id, .. } => *player_id,
             Command::ForceUndock { player_id, .. } => *player_id,
+            Command::ProcessContainers { player_id } => *player_id,
+            Command::Pass { player_id } => *player_id,
             Command::EndTurn { player_id } => *player_id,
             Command::AITakeTurn { player_id, .. } => *player_id,
         }
     }
 
+    /// Whether the command spends one of its side's action points
+    /// (dock, assign, unassign, reassign and pass)
+    pub fn is_action(&self) -> bool {
+        matches!(
+            self,
+            Command::DockShip { .. }
+                | Command::AssignCrane { .. }
+                | Command::UnassignCrane { .. }
+                | Command::ReassignCrane { .. }
+                | Command::Pass { .. }
+        )
+    }
+
     pub fn command_type(&self) -> &str {
         match self {
             Command::DockShip { .. } => "DockShip",
             Command::AssignCrane { .. } => "AssignCrane",
             Command::UnassignCrane { .. } => "UnassignCrane",
+            Command::ReassignCrane { .. } => "ReassignCrane",
             Command::ForceUndock { .. } => "ForceUndock",
+            Command::ProcessContainers { .. } => "ProcessContainers",
+            Command::Pass { .. } => "Pass",
             Command::EndTurn { .. } => "EndTurn",
             Command::AITakeTurn { .. } => "AITakeTurn",
         }
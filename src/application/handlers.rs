@@ -5,8 +5,10 @@ use uuid::Uuid;
 
 use crate::domain::aggregates::Port;
 use crate::domain::events::{DomainEvent, EventMetadata};
+use crate::domain::scoring::{DefaultScoring, ScoringStrategy};
+use crate::domain::value_objects::{BerthId, CraneId, PlayerId, ProcessingPolicy, ShipId};
 
-use super::queries::{BerthView, CraneView, PortStateView, ShipView};
+use super::queries::{BerthView, ComparisonStats, CraneView, PortStateView, ShipView};
 
 pub struct CommandHandler {
     // Will be implemented when we have full game session
@@ -24,19 +26,7 @@ pub fn handle_dock_ship_command(
     berth_id: crate::domain::value_objects::BerthId,
     player_id: crate::domain::value_objects::PlayerId,
 ) -> Result<Vec<DomainEvent>, String> {
-    // Validation
-    if port.ships.get(&ship_id).is_none() {
-        return Err(format!("Ship {} not found", ship_id));
-    }
-
-    if !port
-        .berths
-        .get(&berth_id)
-        .ok_or("Berth not found")?
-        .is_free()
-    {
-        return Err(format!("Berth {} is occupied", berth_id));
-    }
+    validate_dock_ship(port, ship_id, berth_id)?;
 
     // Generate event
     let event = DomainEvent::ShipDocked {
@@ -57,19 +47,7 @@ pub fn handle_assign_crane_command(
     ship_id: crate::domain::value_objects::ShipId,
     player_id: crate::domain::value_objects::PlayerId,
 ) -> Result<Vec<DomainEvent>, String> {
-    // Validation
-    if port.ships.get(&ship_id).is_none() {
-        return Err(format!("Ship {} not found", ship_id));
-    }
-
-    if !port
-        .cranes
-        .get(&crane_id)
-        .ok_or("Crane not found")?
-        .is_free()
-    {
-        return Err(format!("Crane {} is already assigned", crane_id));
-    }
+    validate_assign_crane(port, crane_id, ship_id)?;
 
     // Generate event
     let event = DomainEvent::CraneAssigned {
@@ -83,13 +61,383 @@ pub fn handle_assign_crane_command(
     Ok(vec![event])
 }
 
+/// Take a crane off the ship it works on: one `CraneUnassigned`
+pub fn handle_unassign_crane_command(
+    port: &Port,
+    aggregate_id: Uuid,
+    crane_id: CraneId,
+    ship_id: ShipId,
+    player_id: PlayerId,
+) -> Result<Vec<DomainEvent>, String> {
+    validate_unassign_crane(port, crane_id, ship_id)?;
+
+    Ok(vec![DomainEvent::CraneUnassigned {
+        metadata: EventMetadata::new(aggregate_id, port.version() + 1),
+        crane_id,
+        ship_id,
+        unassignment_time: port.current_time,
+        player: Some(player_id),
+    }])
+}
+
+/// Move a busy crane to another docked ship: `CraneUnassigned` + `CraneAssigned`,
+/// validated together so a failure leaves the current assignment untouched.
+pub fn handle_reassign_crane_command(
+    port: &Port,
+    aggregate_id: Uuid,
+    crane_id: CraneId,
+    new_ship_id: ShipId,
+    player_id: PlayerId,
+) -> Result<Vec<DomainEvent>, String> {
+    let current_ship = validate_reassign_crane(port, crane_id, new_ship_id)?;
+
+    let unassign = DomainEvent::CraneUnassigned {
+        metadata: EventMetadata::new(aggregate_id, port.version() + 1),
+        crane_id,
+        ship_id: current_ship,
+        unassignment_time: port.current_time,
+        player: Some(player_id),
+    };
+
+    // The assignment is versioned after the unassignment
+    let mut after_unassign = port.clone();
+    after_unassign.apply_event(&unassign);
+    let assign = handle_assign_crane_command(
+        &after_unassign,
+        aggregate_id,
+        crane_id,
+        new_ship_id,
+        player_id,
+    )?;
+
+    Ok(std::iter::once(unassign).chain(assign).collect())
+}
+
+/// Put several cranes on one docked ship: one `CraneAssigned` per crane, validated
+/// in order on a scratch port so any failure means no crane is assigned.
+pub fn handle_assign_cranes_command(
+    port: &Port,
+    aggregate_id: Uuid,
+    ship_id: ShipId,
+    crane_ids: &[CraneId],
+    player_id: PlayerId,
+) -> Result<Vec<DomainEvent>, String> {
+    if crane_ids.is_empty() {
+        return Err("No crane selected".to_string());
+    }
+
+    let mut scratch = port.clone();
+    let mut events = Vec::with_capacity(crane_ids.len());
+    for &crane_id in crane_ids {
+        let assign =
+            handle_assign_crane_command(&scratch, aggregate_id, crane_id, ship_id, player_id)
+                .map_err(|e| format!("{} (no crane was assigned)", e))?;
+        for event in assign {
+            scratch.apply_event(&event);
+            events.push(event);
+        }
+    }
+
+    Ok(events)
+}
+
+/// A deliberate no-op: one `PlayerPassed` so the intent shows in the history
+pub fn handle_pass_command(
+    port: &Port,
+    aggregate_id: Uuid,
+    player_id: PlayerId,
+) -> Vec<DomainEvent> {
+    vec![DomainEvent::PlayerPassed {
+        metadata: EventMetadata::new(aggregate_id, port.version() + 1),
+        player: player_id,
+        pass_time: port.current_time,
+    }]
+}
+
+/// Checks of `handle_dock_ship_command`, without producing events
+pub fn validate_dock_ship(port: &Port, ship_id: ShipId, berth_id: BerthId) -> Result<(), String> {
+    let ship = port
+        .ships
+        .get(&ship_id)
+        .ok_or_else(|| format!("Ship {} not found", ship_id))?;
+
+    if let Some(current_berth) = ship.docked_at {
+        return Err(format!(
+            "Ship {} is already docked at berth {}",
+            ship_id, current_berth
+        ));
+    }
+
+    if !port.tide_allows(ship) {
+        return Err(format!(
+            "Tide too low: {} ({} containers) can only dock at high tide",
+            ship_id, ship.containers
+        ));
+    }
+
+    if !port
+        .berths
+        .get(&berth_id)
+        .ok_or("Berth not found")?
+        .is_free()
+    {
+        return Err(format!("Berth {} is occupied", berth_id));
+    }
+
+    Ok(())
+}
+
+/// Checks of `handle_unassign_crane_command`, without producing events
+pub fn validate_unassign_crane(
+    port: &Port,
+    crane_id: CraneId,
+    ship_id: ShipId,
+) -> Result<(), String> {
+    let crane = port.cranes.get(&crane_id).ok_or("Crane not found")?;
+    if crane.assigned_to != Some(ship_id) {
+        return Err(format!("Crane {} is not assigned to {}", crane_id, ship_id));
+    }
+    Ok(())
+}
+
+/// Checks of `handle_assign_crane_command`, without producing events
+pub fn validate_assign_crane(
+    port: &Port,
+    crane_id: CraneId,
+    ship_id: ShipId,
+) -> Result<(), String> {
+    let ship = port
+        .ships
+        .get(&ship_id)
+        .ok_or_else(|| format!("Ship {} not found", ship_id))?;
+
+    if !ship.is_docked() {
+        return Err(format!("Ship {} is not docked", ship_id));
+    }
+
+    let crane = port.cranes.get(&crane_id).ok_or("Crane not found")?;
+    if !crane.is_free() {
+        return Err(format!("Crane {} is already assigned", crane_id));
+    }
+
+    if let Some(berth_id) = ship.docked_at.filter(|&berth| !crane.can_serve(berth)) {
+        return Err(format!(
+            "Crane {} is incompatible with {}",
+            crane_id, berth_id
+        ));
+    }
+
+    if !crane.can_unload(ship) {
+        return Err(format!(
+            "Crane {} cannot handle the cargo left on {}",
+            crane_id, ship_id
+        ));
+    }
+
+    if ship.assigned_cranes.len() >= port.max_cranes_per_ship {
+        return Err(format!(
+            "Crane limit reached: {} already has {} cranes",
+            ship_id,
+            ship.assigned_cranes.len()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks of `handle_reassign_crane_command`, without producing events.
+/// Returns the ship the crane currently works on.
+pub fn validate_reassign_crane(
+    port: &Port,
+    crane_id: CraneId,
+    new_ship_id: ShipId,
+) -> Result<ShipId, String> {
+    let current_ship = port
+        .cranes
+        .get(&crane_id)
+        .ok_or("Crane not found")?
+        .assigned_to
+        .ok_or_else(|| format!("Crane {} is not assigned", crane_id))?;
+
+    if current_ship == new_ship_id {
+        return Err(format!(
+            "Crane {} is already assigned to {}",
+            crane_id, new_ship_id
+        ));
+    }
+
+    // The new assignment is checked against the port once the crane is free
+    let mut after_unassign = port.clone();
+    if let Some(crane) = after_unassign.cranes.get_mut(&crane_id) {
+        crane.unassign();
+    }
+    if let Some(ship) = after_unassign.ships.get_mut(&current_ship) {
+        ship.unassign_crane(crane_id);
+    }
+    validate_assign_crane(&after_unassign, crane_id, new_ship_id)?;
+
+    Ok(current_ship)
+}
+
+/// One `ContainerProcessed` per ship and container type handled this turn.
+/// Never fails: ships without cranes are simply skipped.
+pub fn handle_process_containers_command(
+    port: &Port,
+    aggregate_id: Uuid,
+    efficiency: f64,
+) -> Vec<DomainEvent> {
+    handle_process_containers_in_batches(port, aggregate_id, efficiency, None)
+}
+
+/// Same as `handle_process_containers_command`, split into one event per
+/// `batch_size` containers (the last batch may be smaller) so a UI can animate
+/// the unloading. `None` or 0 keeps one event per ship and container type.
+pub fn handle_process_containers_in_batches(
+    port: &Port,
+    aggregate_id: Uuid,
+    efficiency: f64,
+    batch_size: Option<u32>,
+) -> Vec<DomainEvent> {
+    handle_process_containers_with_policy(
+        port,
+        aggregate_id,
+        efficiency,
+        batch_size,
+        ProcessingPolicy::Fifo,
+    )
+}
+
+/// Same as `handle_process_containers_in_batches`, ships taken in the order
+/// of `policy` (ties by ship id). With `Fair` the ships' batches alternate.
+/// The policy only orders the work: each ship is still unloaded by its own
+/// cranes, as in MCTS rollouts and `Port::estimated_completion`.
+pub fn handle_process_containers_with_policy(
+    port: &Port,
+    aggregate_id: Uuid,
+    efficiency: f64,
+    batch_size: Option<u32>,
+    policy: ProcessingPolicy,
+) -> Vec<DomainEvent> {
+    let mut ships: Vec<_> = port
+        .docked_ships()
+        .into_iter()
+        .filter(|ship| !ship.assigned_cranes.is_empty())
+        .collect();
+    ships.sort_by_key(|ship| ship.id);
+    match policy {
+        ProcessingPolicy::Fifo => ships.sort_by(|a, b| a.arrival_time.total_cmp(&b.arrival_time)),
+        ProcessingPolicy::MostFull => {
+            ships.sort_by_key(|ship| std::cmp::Reverse(ship.containers_remaining))
+        }
+        ProcessingPolicy::Fair => {}
+    }
+
+    // Batches of each ship: (crane, ship, container type, containers left after it)
+    let mut queues: Vec<Vec<_>> = Vec::new();
+    for ship in ships {
+        // Worn cranes unload less
+        let process_amount = (port.crane_capacity(ship) * efficiency) as u32;
+
+        let mut queue = Vec::new();
+        let mut remaining = ship.containers_remaining;
+        for (container_type, processed) in ship.plan_processing(process_amount as f64) {
+            let batch = batch_size.filter(|&size| size > 0).unwrap_or(processed);
+            let mut left_in_plan = processed;
+            while left_in_plan > 0 {
+                let unloaded = left_in_plan.min(batch);
+                left_in_plan -= unloaded;
+                remaining -= unloaded;
+                // First assigned crane stands for the whole team
+                queue.push((ship.assigned_cranes[0], ship.id, container_type, remaining));
+            }
+        }
+        queues.push(queue);
+    }
+
+    let batches: Vec<_> = if policy == ProcessingPolicy::Fair {
+        let rounds = queues.iter().map(Vec::len).max().unwrap_or(0);
+        (0..rounds)
+            .flat_map(|round| queues.iter().filter_map(move |queue| queue.get(round)))
+            .copied()
+            .collect()
+    } else {
+        queues.into_iter().flatten().collect()
+    };
+
+    batches
+        .into_iter()
+        .enumerate()
+        .map(
+            |(index, (crane_id, ship_id, container_type, containers_remaining))| {
+                DomainEvent::ContainerProcessed {
+                    metadata: EventMetadata::new(aggregate_id, port.version() + 1 + index as u64),
+                    crane_id,
+                    ship_id,
+                    player: port.player_id,
+                    containers_remaining,
+                    container_type,
+                }
+            },
+        )
+        .collect()
+}
+
+/// Ships of `player_id` undocked in the event stream (the ports drop
+/// completed ships, so their maps cannot tell)
+pub fn count_ships_completed(events: &[DomainEvent], player_id: PlayerId) -> u32 {
+    events
+        .iter()
+        .filter(|event| {
+            matches!(event, DomainEvent::ShipUndocked { player, .. } if *player == player_id)
+        })
+        .count() as u32
+}
+
+/// Head-to-head stats of the two ports, ship counts read from `events`
+pub fn query_comparison_stats(
+    player_port: &Port,
+    ai_port: &Port,
+    events: &[DomainEvent],
+) -> ComparisonStats {
+    query_comparison_stats_with_scoring(player_port, ai_port, events, &DefaultScoring)
+}
+
+/// Same as `query_comparison_stats`, scores given by `scoring`
+pub fn query_comparison_stats_with_scoring(
+    player_port: &Port,
+    ai_port: &Port,
+    events: &[DomainEvent],
+    scoring: &dyn ScoringStrategy,
+) -> ComparisonStats {
+    ComparisonStats {
+        player_score: scoring.score(player_port),
+        ai_score: scoring.score(ai_port),
+        player_ships_processed: count_ships_completed(events, player_port.player_id),
+        ai_ships_processed: count_ships_completed(events, ai_port.player_id),
+        player_avg_wait_time: player_port.average_wait_time(player_port.current_time),
+        ai_avg_wait_time: ai_port.average_wait_time(ai_port.current_time),
+    }
+}
+
 pub fn query_port_state(port: &Port) -> PortStateView {
+    query_port_state_with_scoring(port, &DefaultScoring)
+}
+
+/// Same as `query_port_state`, score given by `scoring`
+pub fn query_port_state_with_scoring(port: &Port, scoring: &dyn ScoringStrategy) -> PortStateView {
     PortStateView {
         player_id: port.player_id,
-        ships: port.ships.values().map(ShipView::from).collect(),
+        ships: port
+            .ships
+            .values()
+            .map(|ship| ShipView::from_port(port, ship))
+            .collect(),
         berths: port.berths.values().map(BerthView::from).collect(),
         cranes: port.cranes.values().map(CraneView::from).collect(),
-        score: port.calculate_score(),
+        score: scoring.score(port),
         current_time: port.current_time,
+        tide: port.tide,
+        berth_utilization: port.berth_utilization(),
+        crane_utilization: port.crane_utilization(),
     }
 }
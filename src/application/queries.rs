@@ -3,8 +3,9 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::domain::entities::{Berth, Crane, Ship};
-use crate::domain::value_objects::PlayerId;
+use crate::domain::aggregates::Port;
+use crate::domain::entities::{Berth, CargoMix, Crane, Ship};
+use crate::domain::value_objects::{PlayerId, Tide};
 
 /// All queries for reading game state
 #[derive(Debug, Clone)]
@@ -43,6 +44,9 @@ pub struct PortStateView {
     pub cranes: Vec<CraneView>,
     pub score: i32,
     pub current_time: f64,
+    pub tide: Tide,
+    pub berth_utilization: f64, // Occupied berths / total
+    pub crane_utilization: f64, // Assigned cranes / total
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +57,10 @@ pub struct ShipView {
     pub is_docked: bool,
     pub docked_at: Option<usize>,
     pub assigned_cranes: Vec<usize>,
+    pub priority: bool,
+    pub cargo: CargoMix,
+    pub eta_turns: Option<u32>, // None when no crane is working on the ship
+    pub progress: f64,          // Share of the cargo unloaded, 0.0 to 1.0
 }
 
 impl From<&Ship> for ShipView {
@@ -64,6 +72,20 @@ impl From<&Ship> for ShipView {
             is_docked: ship.is_docked(),
             docked_at: ship.docked_at.map(|b| b.0),
             assigned_cranes: ship.assigned_cranes.iter().map(|c| c.0).collect(),
+            priority: ship.priority,
+            cargo: ship.cargo,
+            eta_turns: None,
+            progress: ship.progress(),
+        }
+    }
+}
+
+impl ShipView {
+    /// Build the view with the completion estimate from the ship's port
+    pub fn from_port(port: &Port, ship: &Ship) -> Self {
+        Self {
+            eta_turns: port.estimated_completion(ship.id),
+            ..Self::from(ship)
         }
     }
 }
@@ -73,6 +95,7 @@ pub struct BerthView {
     pub id: usize,
     pub is_free: bool,
     pub occupied_by: Option<usize>,
+    pub docking_cost: i32,
 }
 
 impl From<&Berth> for BerthView {
@@ -81,6 +104,7 @@ impl From<&Berth> for BerthView {
             id: berth.id.0,
             is_free: berth.is_free(),
             occupied_by: berth.occupied_by.map(|s| s.0),
+            docking_cost: berth.docking_cost,
         }
     }
 }
@@ -91,6 +115,7 @@ pub struct CraneView {
     pub is_free: bool,
     pub assigned_to: Option<usize>,
     pub processing_speed: f64,
+    pub wear: f64,
 }
 
 impl From<&Crane> for CraneView {
@@ -100,6 +125,7 @@ impl From<&Crane> for CraneView {
             is_free: crane.is_free(),
             assigned_to: crane.assigned_to.map(|s| s.0),
             processing_speed: crane.processing_speed,
+            wear: crane.wear,
         }
     }
 }
@@ -128,5 +154,6 @@ mod tests {
         assert_eq!(view.id, 1);
         assert_eq!(view.containers, 50);
         assert!(!view.is_docked);
+        assert_eq!(view.progress, 0.0);
     }
 }
@@ -1,7 +1,18 @@
 // Display utilities for CLI
 
 use crate::domain::aggregates::Port;
-use crate::game::GameSession;
+use crate::domain::entities::Ship;
+use crate::domain::events::DomainEvent;
+use crate::game::{GameResult, GameSession};
+
+/// Marker appended to ship listings for priority cargo
+pub fn priority_tag(ship: &Ship) -> &'static str {
+    if ship.priority {
+        " ⭐ PRIORITY"
+    } else {
+        ""
+    }
+}
 
 /// Display port status with nice formatting
 pub fn display_port_status(port: &Port, title: &str) {
@@ -27,6 +38,10 @@ pub fn display_port_status(port: &Port, title: &str) {
         "│ 🎯 Score: {}                                               ",
         port.calculate_score()
     );
+    println!(
+        "│ 🌊 Tide: {}                                                ",
+        port.tide
+    );
     println!("└────────────────────────────────────────────────────────────┘");
 
     // Show waiting ships
@@ -34,10 +49,11 @@ pub fn display_port_status(port: &Port, title: &str) {
         println!("\n📦 WAITING SHIPS:");
         for ship in port.waiting_ships() {
             println!(
-                "  • Ship #{}: {} containers (waiting {:.1}s)",
+                "  • Ship #{}: {} containers (waiting {:.1}s){}",
                 ship.id.0,
                 ship.containers,
-                ship.waiting_time(port.current_time)
+                ship.waiting_time(port.current_time),
+                priority_tag(ship)
             );
         }
     }
@@ -49,16 +65,18 @@ pub fn display_port_status(port: &Port, title: &str) {
             let berth_id = ship.docked_at.unwrap().0;
             let assigned_cranes: Vec<_> = ship.assigned_cranes.iter().map(|c| c.0).collect();
             println!(
-                "  • Ship #{} at Berth #{}: {}/{} containers | Cranes: {:?}",
+                "  • Ship #{} at Berth #{}: {}/{} containers ({:.0}% done) | Cranes: {:?}{}",
                 ship.id.0,
                 berth_id,
                 ship.containers_remaining,
                 ship.containers,
+                ship.progress() * 100.0,
                 if assigned_cranes.is_empty() {
                     vec![]
                 } else {
                     assigned_cranes
-                }
+                },
+                priority_tag(ship)
             );
         }
     }
@@ -66,21 +84,11 @@ pub fn display_port_status(port: &Port, title: &str) {
 
 /// Display comparison between player and AI
 pub fn display_comparison(session: &GameSession) {
-    let player_score = session.player_port.calculate_score();
-    let ai_score = session.ai_port.calculate_score();
-
-    let player_ships_done = session
-        .player_port
-        .ships
-        .values()
-        .filter(|s| s.is_completed())
-        .count();
-    let ai_ships_done = session
-        .ai_port
-        .ships
-        .values()
-        .filter(|s| s.is_completed())
-        .count();
+    let player_score = session.score(&session.player_port);
+    let ai_score = session.score(&session.ai_port);
+
+    let player_ships_done = session.ships_completed(session.player_port.player_id);
+    let ai_ships_done = session.ships_completed(session.ai_port.player_id);
 
     println!("\n╔════════════════════════════════════════════════════════════╗");
     println!("║               PLAYER vs AI COMPARISON                      ║");
@@ -105,6 +113,16 @@ pub fn display_comparison(session: &GameSession) {
         session.player_port.docked_ships().len(),
         session.ai_port.docked_ships().len()
     );
+    println!(
+        "║ Berth usage:        {:5.0}%   │  {:5.0}%                     ║",
+        session.player_port.berth_utilization() * 100.0,
+        session.ai_port.berth_utilization() * 100.0
+    );
+    println!(
+        "║ Crane usage:        {:5.0}%   │  {:5.0}%                     ║",
+        session.player_port.crane_utilization() * 100.0,
+        session.ai_port.crane_utilization() * 100.0
+    );
     println!("╚════════════════════════════════════════════════════════════╝");
 
     if player_score > ai_score {
@@ -116,16 +134,88 @@ pub fn display_comparison(session: &GameSession) {
     }
 }
 
-/// Display game header
-pub fn display_header(turn: u32) {
+/// Cumulative statistics of both ports, as the text of the statistics screen
+pub fn display_statistics(session: &GameSession) -> String {
+    let stats = session.comparison_stats();
+    let (player_containers, ai_containers) =
+        session
+            .turn_log()
+            .iter()
+            .fold((0, 0), |(player, ai), turn| {
+                (
+                    player + turn.containers_processed,
+                    ai + turn.ai_containers_processed,
+                )
+            });
+
+    let rows = [
+        (
+            "Containers done:",
+            format!("{:6}", player_containers),
+            format!("{:6}", ai_containers),
+        ),
+        (
+            "Ships completed:",
+            format!("{:6}", stats.player_ships_processed),
+            format!("{:6}", stats.ai_ships_processed),
+        ),
+        (
+            "Avg wait (turns):",
+            format!("{:6.1}", stats.player_avg_wait_time),
+            format!("{:6.1}", stats.ai_avg_wait_time),
+        ),
+        (
+            "Berth usage:",
+            format!("{:5.0}%", session.player_port.berth_utilization() * 100.0),
+            format!("{:5.0}%", session.ai_port.berth_utilization() * 100.0),
+        ),
+        (
+            "Crane usage:",
+            format!("{:5.0}%", session.player_port.crane_utilization() * 100.0),
+            format!("{:5.0}%", session.ai_port.crane_utilization() * 100.0),
+        ),
+        (
+            "Crane efficiency:",
+            format!("{:5.0}%", session.crane_efficiency_modifier * 100.0),
+            format!("{:5.0}%", session.ai_crane_efficiency_modifier * 100.0),
+        ),
+    ];
+
+    let mut out = String::new();
+    out.push_str("╔════════════════════════════════════════════════════════════╗\n");
+    out.push_str("║                  STATISTICS                                ║\n");
+    out.push_str("╠════════════════════════════════════════════════════════════╣\n");
+    out.push_str("║                    PLAYER    │    AI                       ║\n");
+    out.push_str("╟────────────────────────────────────────────────────────────╢\n");
+    for (label, player, ai) in rows {
+        out.push_str(&format!(
+            "║ {:<19} {}   │  {}                     ║\n",
+            label, player, ai
+        ));
+    }
+    out.push_str("╚════════════════════════════════════════════════════════════╝");
+    out
+}
+
+/// Numbered timeline of a game's events, as the text of the replay viewer
+pub fn display_event_log(events: &[DomainEvent]) -> String {
+    let mut out = format!("📜 Game timeline ({} events)\n", events.len());
+    for (index, event) in events.iter().enumerate() {
+        out.push_str(&format!("{:4}. {}\n", index + 1, event.describe()));
+    }
+    out
+}
+
+/// Display game header with the turns left before the limit
+pub fn display_header(turn: u32, turns_remaining: u32) {
     println!("\n");
     println!("╔════════════════════════════════════════════════════════════╗");
     println!("║         🚢 PORT TERMINAL MANAGER 🚢                         ║");
     println!("║              MCTS Strategy Game                            ║");
     println!("╠════════════════════════════════════════════════════════════╣");
     println!(
-        "║ Turn: {}                                                    ║",
-        turn
+        "║ Turn: {:<3}                               Turns left: {:<3}   ║",
+        turn, turns_remaining
     );
     println!("╚════════════════════════════════════════════════════════════╝");
 }
@@ -140,14 +230,14 @@ pub fn display_action_result(success: bool, message: &str) {
 }
 
 /// Display game end
-pub fn display_game_end(session: &GameSession, winner: Option<&str>) {
+pub fn display_game_end(session: &GameSession, result: GameResult) {
     println!("\n");
     println!("╔════════════════════════════════════════════════════════════╗");
     println!("║                    GAME OVER                               ║");
     println!("╠════════════════════════════════════════════════════════════╣");
 
-    let player_score = session.player_port.calculate_score();
-    let ai_score = session.ai_port.calculate_score();
+    let player_score = session.score(&session.player_port);
+    let ai_score = session.score(&session.ai_port);
 
     println!("║ Final Score:                                               ║");
     println!(
@@ -160,17 +250,63 @@ pub fn display_game_end(session: &GameSession, winner: Option<&str>) {
     );
     println!("╠════════════════════════════════════════════════════════════╣");
 
-    match winner {
-        Some("player") => {
+    match result {
+        GameResult::PlayerWins => {
             println!("║            🏆 YOU WIN! 🏆                                  ║");
         }
-        Some("ai") => {
+        GameResult::AiWins => {
             println!("║            🤖 AI WINS! 🤖                                  ║");
         }
-        _ => {
+        GameResult::Tie | GameResult::InProgress => {
             println!("║            🤝 IT'S A TIE! 🤝                               ║");
         }
     }
 
     println!("╚════════════════════════════════════════════════════════════╝");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::{BerthId, CraneId, PlayerId, ShipId};
+    use crate::game::{GameMode, SpawnConfig};
+
+    #[test]
+    fn test_statistics_reflect_processed_turn() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 30,
+                max_containers: 30,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.set_events_enabled(false);
+        session.spawn_ships(1);
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ShipId::new(0))
+            .unwrap();
+        session.process_containers();
+
+        let stats = display_statistics(&session);
+        let processed = 30 - session.player_port.ships[&ShipId::new(0)].containers_remaining;
+        assert!(processed > 0);
+
+        let row = |label: &str| {
+            stats
+                .lines()
+                .find(|line| line.contains(label))
+                .unwrap()
+                .to_string()
+        };
+        assert!(row("Containers done:").contains(&format!("{:6}   │       0", processed)));
+        assert!(row("Ships completed:").contains("     0   │       0"));
+        assert!(row("Berth usage:").contains(&format!(
+            "{:5.0}%   │      0%",
+            100.0 / session.player_port.berths.len() as f64
+        )));
+        assert!(row("Crane efficiency:").contains("  100%   │    100%"));
+    }
+}
@@ -13,6 +13,55 @@ pub fn confirm(prompt: &str) -> bool {
     matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
 }
 
+/// Parse a 1-based menu selection into a 0-based index within `len` items
+pub fn parse_index(input: &str, len: usize) -> Result<usize, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Please enter a number".to_string());
+    }
+
+    let number = input
+        .parse::<usize>()
+        .map_err(|_| "Please enter a valid number".to_string())?;
+
+    if number == 0 || number > len {
+        return Err(format!("Please choose a number between 1 and {}", len));
+    }
+
+    Ok(number - 1)
+}
+
+/// Parse a selection that may be cancelled with "c": None means cancelled
+pub fn parse_selection(input: &str, len: usize) -> Result<Option<usize>, String> {
+    if matches!(input.trim().to_lowercase().as_str(), "c" | "cancel") {
+        return Ok(None);
+    }
+
+    parse_index(input, len).map(Some)
+}
+
+/// Parse one or more selections separated by commas or spaces ("1,3"),
+/// returned as distinct 0-based indexes in the order given. None means cancelled.
+pub fn parse_multi_selection(input: &str, len: usize) -> Result<Option<Vec<usize>>, String> {
+    if matches!(input.trim().to_lowercase().as_str(), "c" | "cancel") {
+        return Ok(None);
+    }
+
+    let mut indexes = Vec::new();
+    for part in input.split([',', ' ']).filter(|p| !p.trim().is_empty()) {
+        let index = parse_index(part, len)?;
+        if indexes.contains(&index) {
+            return Err(format!("{} was selected twice", index + 1));
+        }
+        indexes.push(index);
+    }
+
+    if indexes.is_empty() {
+        return Err("Please enter a number".to_string());
+    }
+    Ok(Some(indexes))
+}
+
 /// Wait for user to press Enter
 pub fn wait_for_enter() {
     println!("\nPress Enter to continue...");
@@ -25,3 +74,43 @@ pub fn clear_screen() {
     print!("\x1B[2J\x1B[1;1H");
     io::stdout().flush().unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_index_valid() {
+        assert_eq!(parse_index("1", 3), Ok(0));
+        assert_eq!(parse_index(" 3\n", 3), Ok(2));
+    }
+
+    #[test]
+    fn test_parse_index_rejects_bad_input() {
+        assert!(parse_index("0", 3).is_err());
+        assert!(parse_index("", 3).is_err());
+        assert!(parse_index("abc", 3).is_err());
+        assert!(parse_index("4", 3).is_err());
+        assert!(parse_index("-1", 3).is_err());
+    }
+
+    #[test]
+    fn test_parse_selection_cancel() {
+        assert_eq!(parse_selection("c", 3), Ok(None));
+        assert_eq!(parse_selection(" Cancel\n", 3), Ok(None));
+        assert_eq!(parse_selection("2", 3), Ok(Some(1)));
+        assert!(parse_selection("x", 3).is_err());
+        assert!(parse_selection("0", 3).is_err());
+    }
+
+    #[test]
+    fn test_parse_multi_selection() {
+        assert_eq!(parse_multi_selection("2", 3), Ok(Some(vec![1])));
+        assert_eq!(parse_multi_selection("3, 1\n", 3), Ok(Some(vec![2, 0])));
+        assert_eq!(parse_multi_selection("1 2", 3), Ok(Some(vec![0, 1])));
+        assert_eq!(parse_multi_selection("c", 3), Ok(None));
+        assert!(parse_multi_selection("1,1", 3).is_err());
+        assert!(parse_multi_selection("1,4", 3).is_err());
+        assert!(parse_multi_selection(" , ", 3).is_err());
+    }
+}
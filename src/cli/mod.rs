@@ -15,27 +15,43 @@ pub use input::*;
 /// Player action menu
 #[derive(Debug, Clone, PartialEq)]
 pub enum PlayerAction {
-    DockShip { ship_id: ShipId, berth_id: BerthId },
-    AssignCrane { crane_id: CraneId, ship_id: ShipId },
+    DockShip {
+        ship_id: ShipId,
+        berth_id: BerthId,
+    },
+    AssignCrane {
+        crane_id: CraneId,
+        ship_id: ShipId,
+    },
+    AssignCranes {
+        ship_id: ShipId,
+        crane_ids: Vec<CraneId>,
+    }, // Several at once, one action
     ViewState,
     ViewComparison,
+    ViewStatistics,
+    Pass,
     EndTurn,
     Quit,
+    Cancel, // Player backed out of a sub-menu
 }
 
 /// Display main menu and get player choice
-pub fn display_menu() {
+pub fn display_menu(action_points: u32) {
     println!("\n┌────────────────────────────────────┐");
     println!("│       AVAILABLE ACTIONS            │");
+    println!("│ Actions left this turn: {:<11}│", action_points);
     println!("├────────────────────────────────────┤");
     println!("│ 1. Dock a ship                     │");
     println!("│ 2. Assign crane to ship            │");
     println!("│ 3. View port state                 │");
     println!("│ 4. View player vs AI comparison    │");
-    println!("│ 5. End turn                        │");
-    println!("│ 6. Quit game                       │");
+    println!("│ 5. View statistics                 │");
+    println!("│ 6. Pass (skip one action)          │");
+    println!("│ 7. End turn                        │");
+    println!("│ 8. Quit game                       │");
     println!("└────────────────────────────────────┘");
-    print!("Choose action (1-6): ");
+    print!("Choose action (1-8): ");
     io::stdout().flush().unwrap();
 }
 
@@ -65,20 +81,19 @@ pub fn handle_dock_ship_input(port: &Port) -> Result<PlayerAction, String> {
     println!("\nWaiting ships:");
     for (i, ship) in waiting_ships.iter().enumerate() {
         println!(
-            "  {}. Ship #{} - {} containers",
+            "  {}. Ship #{} - {} containers{}",
             i + 1,
             ship.id.0,
-            ship.containers
+            ship.containers,
+            priority_tag(ship)
         );
     }
 
-    print!("\nSelect ship number: ");
+    print!("\nSelect ship number (c to cancel): ");
     io::stdout().flush().unwrap();
-    let ship_idx = get_user_index()? - 1;
-
-    if ship_idx >= waiting_ships.len() {
-        return Err("Invalid ship number".to_string());
-    }
+    let Some(ship_idx) = get_user_index(waiting_ships.len())? else {
+        return Ok(PlayerAction::Cancel);
+    };
 
     let ship_id = waiting_ships[ship_idx].id;
 
@@ -90,16 +105,23 @@ pub fn handle_dock_ship_input(port: &Port) -> Result<PlayerAction, String> {
 
     println!("\nAvailable berths:");
     for (i, berth) in free_berths.iter().enumerate() {
-        println!("  {}. Berth #{}", i + 1, berth.id.0);
+        if berth.docking_cost > 0 {
+            println!(
+                "  {}. Berth #{} (fee: {} points)",
+                i + 1,
+                berth.id.0,
+                berth.docking_cost
+            );
+        } else {
+            println!("  {}. Berth #{}", i + 1, berth.id.0);
+        }
     }
 
-    print!("\nSelect berth number: ");
+    print!("\nSelect berth number (c to cancel): ");
     io::stdout().flush().unwrap();
-    let berth_idx = get_user_index()? - 1;
-
-    if berth_idx >= free_berths.len() {
-        return Err("Invalid berth number".to_string());
-    }
+    let Some(berth_idx) = get_user_index(free_berths.len())? else {
+        return Ok(PlayerAction::Cancel);
+    };
 
     let berth_id = free_berths[berth_idx].id;
 
@@ -119,22 +141,21 @@ pub fn handle_assign_crane_input(port: &Port) -> Result<PlayerAction, String> {
     println!("\nDocked ships:");
     for (i, ship) in docked_ships.iter().enumerate() {
         println!(
-            "  {}. Ship #{} at Berth #{} - {}/{} containers remaining",
+            "  {}. Ship #{} at Berth #{} - {}/{} containers remaining{}",
             i + 1,
             ship.id.0,
             ship.docked_at.unwrap().0,
             ship.containers_remaining,
-            ship.containers
+            ship.containers,
+            priority_tag(ship)
         );
     }
 
-    print!("\nSelect ship number: ");
+    print!("\nSelect ship number (c to cancel): ");
     io::stdout().flush().unwrap();
-    let ship_idx = get_user_index()? - 1;
-
-    if ship_idx >= docked_ships.len() {
-        return Err("Invalid ship number".to_string());
-    }
+    let Some(ship_idx) = get_user_index(docked_ships.len())? else {
+        return Ok(PlayerAction::Cancel);
+    };
 
     let ship_id = docked_ships[ship_idx].id;
 
@@ -154,29 +175,36 @@ pub fn handle_assign_crane_input(port: &Port) -> Result<PlayerAction, String> {
         );
     }
 
-    print!("\nSelect crane number: ");
+    print!("\nSelect crane number(s), e.g. 1,3 (c to cancel): ");
     io::stdout().flush().unwrap();
-    let crane_idx = get_user_index()? - 1;
-
-    if crane_idx >= free_cranes.len() {
-        return Err("Invalid crane number".to_string());
-    }
-
-    let crane_id = free_cranes[crane_idx].id;
-
-    Ok(PlayerAction::AssignCrane { crane_id, ship_id })
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| format!("Failed to read input: {}", e))?;
+    let Some(crane_idxs) = parse_multi_selection(&input, free_cranes.len())? else {
+        return Ok(PlayerAction::Cancel);
+    };
+
+    let crane_ids: Vec<CraneId> = crane_idxs.iter().map(|&i| free_cranes[i].id).collect();
+
+    Ok(match crane_ids.as_slice() {
+        [crane_id] => PlayerAction::AssignCrane {
+            crane_id: *crane_id,
+            ship_id,
+        },
+        _ => PlayerAction::AssignCranes { ship_id, crane_ids },
+    })
 }
 
-fn get_user_index() -> Result<usize, String> {
+/// Read a 1-based selection among `len` items, returned as a 0-based index
+/// (None if the player cancelled)
+fn get_user_index(len: usize) -> Result<Option<usize>, String> {
     let mut input = String::new();
     io::stdin()
         .read_line(&mut input)
         .map_err(|e| format!("Failed to read input: {}", e))?;
 
-    input
-        .trim()
-        .parse::<usize>()
-        .map_err(|_| "Please enter a valid number".to_string())
+    parse_selection(&input, len)
 }
 
 /// Process player menu choice
@@ -186,8 +214,10 @@ pub fn process_player_choice(choice: u32, session: &GameSession) -> Result<Playe
         2 => handle_assign_crane_input(&session.player_port),
         3 => Ok(PlayerAction::ViewState),
         4 => Ok(PlayerAction::ViewComparison),
-        5 => Ok(PlayerAction::EndTurn),
-        6 => Ok(PlayerAction::Quit),
-        _ => Err("Invalid choice. Please select 1-6.".to_string()),
+        5 => Ok(PlayerAction::ViewStatistics),
+        6 => Ok(PlayerAction::Pass),
+        7 => Ok(PlayerAction::EndTurn),
+        8 => Ok(PlayerAction::Quit),
+        _ => Err("Invalid choice. Please select 1-8.".to_string()),
     }
 }
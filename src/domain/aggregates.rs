@@ -1,65 +1,210 @@
 // Aggregates - DDD pattern for consistency boundaries
 // Port is the main aggregate root
 
-use super::entities::{Berth, Crane, Ship};
+use super::entities::{Berth, CargoMix, Crane, Ship};
 use super::events::{DomainEvent, EventMetadata};
-use super::value_objects::{BerthId, CraneId, PlayerId, ShipId};
-use std::collections::HashMap;
+use super::value_objects::{BerthId, ContainerType, CraneId, PlayerId, ShipId, Tide};
+use crate::utils::random;
+use std::collections::BTreeMap;
+use std::hash::Hasher;
 use uuid::Uuid;
 
+/// Default number of cranes that can work on the same ship
+pub const DEFAULT_MAX_CRANES_PER_SHIP: usize = 2;
+
+/// Processing speed of cranes built by `Port::new`
+pub const DEFAULT_CRANE_SPEED: f64 = 2.0;
+
+/// Default containers a fresh crane unloads per turn
+pub const DEFAULT_CONTAINERS_PER_CRANE: u32 = 10;
+
+/// Default points awarded per processed container
+pub const DEFAULT_POINTS_PER_CONTAINER: u32 = 10;
+
+/// Default score lost per idle crane each turn
+pub const DEFAULT_IDLE_CRANE_PENALTY: u32 = 1;
+
+/// Score multiplier applied to a priority ship once fully unloaded
+pub const PRIORITY_SCORE_MULTIPLIER: u32 = 2;
+
+/// Waiting penalty per time unit for normal and priority ships
+const WAITING_PENALTY: f64 = 5.0;
+const PRIORITY_WAITING_PENALTY: f64 = 10.0;
+
+fn ratio(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
 /// Port aggregate - Manages ships, berths, and cranes
 /// This is the consistency boundary and event source
 #[derive(Debug, Clone)]
 pub struct Port {
     pub player_id: PlayerId,
-    pub ships: HashMap<ShipId, Ship>,
-    pub berths: HashMap<BerthId, Berth>,
-    pub cranes: HashMap<CraneId, Crane>,
+    // Keyed in id order so listings are stable (the CLI picks entries by index)
+    pub ships: BTreeMap<ShipId, Ship>,
+    pub berths: BTreeMap<BerthId, Berth>,
+    pub cranes: BTreeMap<CraneId, Crane>,
     pub current_time: f64,
     pub score: i32,
+    pub max_cranes_per_ship: usize,
+    pub containers_per_crane: u32, // Per turn, before wear and efficiency modifiers
+    pub points_per_container: u32, // Score of each processed container
+    pub tide: Tide,
+    pub waiting_cost: i32, // Waiting penalty charged into `score` so far
+    pub waiting_charged_until: f64, // Time up to which waiting has been charged
+    pub idle_crane_penalty: u32, // Score lost per idle crane each turn
+    pub idle_crane_cost: i32, // Idle crane penalty charged into `score` so far
 
     // Event sourcing
+    pub aggregate_id: Uuid, // Stream the port's own events belong to (the game session)
     version: u64,
     uncommitted_events: Vec<DomainEvent>,
 }
 
+/// Inclusive ranges a random port layout is drawn from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutConstraints {
+    pub min_berths: usize,
+    pub max_berths: usize,
+    pub min_cranes: usize,
+    pub max_cranes: usize,
+    pub min_crane_speed: f64,
+    pub max_crane_speed: f64,
+}
+
+impl Default for LayoutConstraints {
+    fn default() -> Self {
+        Self {
+            min_berths: 2,
+            max_berths: 4,
+            min_cranes: 2,
+            max_cranes: 4,
+            min_crane_speed: 1.0,
+            max_crane_speed: 3.0,
+        }
+    }
+}
+
 impl Port {
+    /// Port with a berth count, crane count and crane speeds drawn from
+    /// `constraints`. The same seed always gives the same layout; speeds are
+    /// rounded to half units.
+    pub fn random_layout(player_id: PlayerId, seed: u64, constraints: LayoutConstraints) -> Self {
+        random::with_seed(seed, || {
+            let num_berths =
+                random::range_usize_inclusive(constraints.min_berths, constraints.max_berths);
+            let num_cranes =
+                random::range_usize_inclusive(constraints.min_cranes, constraints.max_cranes);
+            let speeds: Vec<f64> = (0..num_cranes)
+                .map(|_| {
+                    let speed = random::range_f64_inclusive(
+                        constraints.min_crane_speed,
+                        constraints.max_crane_speed,
+                    );
+                    ((speed * 2.0).round() / 2.0)
+                        .clamp(constraints.min_crane_speed, constraints.max_crane_speed)
+                })
+                .collect();
+            Self::new_with_crane_speeds(player_id, num_berths, &speeds)
+        })
+    }
+
     pub fn new(player_id: PlayerId, num_berths: usize, num_cranes: usize) -> Self {
-        let mut berths = HashMap::new();
+        Self::new_with_crane_speeds(
+            player_id,
+            num_berths,
+            &vec![DEFAULT_CRANE_SPEED; num_cranes],
+        )
+    }
+
+    /// One crane per speed, numbered in order
+    pub fn new_with_crane_speeds(player_id: PlayerId, num_berths: usize, speeds: &[f64]) -> Self {
+        let mut berths = BTreeMap::new();
         for i in 0..num_berths {
             berths.insert(BerthId::new(i), Berth::new(BerthId::new(i)));
         }
 
-        let mut cranes = HashMap::new();
-        for i in 0..num_cranes {
-            cranes.insert(
-                CraneId::new(i),
-                Crane::new(CraneId::new(i), 2.0), // Default speed
-            );
-        }
+        let cranes = speeds
+            .iter()
+            .enumerate()
+            .map(|(i, &speed)| (CraneId::new(i), Crane::new(CraneId::new(i), speed)))
+            .collect();
 
         Self {
             player_id,
-            ships: HashMap::new(),
+            ships: BTreeMap::new(),
             berths,
             cranes,
             current_time: 0.0,
             score: 0,
+            max_cranes_per_ship: DEFAULT_MAX_CRANES_PER_SHIP,
+            containers_per_crane: DEFAULT_CONTAINERS_PER_CRANE,
+            points_per_container: DEFAULT_POINTS_PER_CONTAINER,
+            tide: Tide::default(),
+            waiting_cost: 0,
+            waiting_charged_until: 0.0,
+            idle_crane_penalty: DEFAULT_IDLE_CRANE_PENALTY,
+            idle_crane_cost: 0,
+            aggregate_id: Uuid::new_v4(),
             version: 0,
             uncommitted_events: Vec::new(),
         }
     }
 
+    /// Rebuild a port from a session stream shared with other ports: the
+    /// events of `layout`'s player and the turn starts (clock and tide) are
+    /// replayed on top of it
+    pub fn from_events(layout: Port, events: &[DomainEvent]) -> Self {
+        let player_id = layout.player_id;
+        let mut port = layout;
+        let replayed = |event: &&DomainEvent| {
+            event.concerns_port(player_id) || matches!(event, DomainEvent::TurnStarted { .. })
+        };
+        for event in events.iter().filter(replayed) {
+            port.apply_event(event);
+        }
+        port
+    }
+
+    /// Make the last berth the premium deep-water one: docking there costs
+    /// `fee`, the other berths become free
+    pub fn set_premium_berth_fee(&mut self, fee: i32) {
+        let premium = self.berths.keys().last().copied();
+        for berth in self.berths.values_mut() {
+            berth.docking_cost = if Some(berth.id) == premium { fee } else { 0 };
+        }
+    }
+
+    /// Stamp the events the port builds itself with `aggregate_id`
+    pub fn with_aggregate_id(mut self, aggregate_id: Uuid) -> Self {
+        self.aggregate_id = aggregate_id;
+        self
+    }
+
     /// Apply an event to update state (Event Sourcing pattern)
     pub fn apply_event(&mut self, event: &DomainEvent) {
         match event {
+            DomainEvent::TurnStarted { turn_number, .. } => {
+                // Port clocks follow the turn counter
+                self.current_time = *turn_number as f64;
+                self.tide = Tide::for_turn(*turn_number);
+            }
+
             DomainEvent::ShipArrived {
                 ship_id,
                 container_count,
                 arrival_time,
+                priority,
+                cargo,
                 ..
             } => {
-                let ship = Ship::new(*ship_id, *container_count, *arrival_time);
+                let cargo = cargo.unwrap_or(CargoMix::standard(*container_count));
+                let mut ship = Ship::with_cargo(*ship_id, cargo, *arrival_time);
+                ship.priority = *priority;
                 self.ships.insert(*ship_id, ship);
             }
 
@@ -71,6 +216,7 @@ impl Port {
                 }
                 if let Some(berth) = self.berths.get_mut(berth_id) {
                     berth.occupy(*ship_id);
+                    self.score -= berth.docking_cost;
                 }
             }
 
@@ -90,11 +236,20 @@ impl Port {
             DomainEvent::CraneAssigned {
                 crane_id, ship_id, ..
             } => {
-                if let Some(crane) = self.cranes.get_mut(crane_id) {
-                    crane.assign(*ship_id);
-                }
-                if let Some(ship) = self.ships.get_mut(ship_id) {
-                    ship.assign_crane(*crane_id);
+                // Malformed streams may reassign a busy crane: keep the existing
+                // assignment instead of leaving the previous ship pointing at it
+                let busy_elsewhere = self
+                    .cranes
+                    .get(crane_id)
+                    .and_then(|crane| crane.assigned_to)
+                    .is_some_and(|assigned| assigned != *ship_id);
+                if !busy_elsewhere {
+                    if let Some(crane) = self.cranes.get_mut(crane_id) {
+                        crane.assign(*ship_id);
+                    }
+                    if let Some(ship) = self.ships.get_mut(ship_id) {
+                        ship.assign_crane(*crane_id);
+                    }
                 }
             }
 
@@ -112,13 +267,70 @@ impl Port {
             DomainEvent::ContainerProcessed {
                 ship_id,
                 containers_remaining,
+                container_type,
                 ..
             } => {
-                if let Some(ship) = self.ships.get_mut(ship_id) {
+                // Out-of-order streams may report more containers than are left:
+                // the event is stale, it neither changes the ship nor scores
+                let ship = self
+                    .ships
+                    .get_mut(ship_id)
+                    .filter(|ship| ship.containers_remaining >= *containers_remaining);
+                if let Some(ship) = ship {
                     let containers_processed = ship.containers_remaining - *containers_remaining;
                     ship.containers_remaining = *containers_remaining;
-                    // Mise à jour du score : 10 points par conteneur traité
-                    self.score += (containers_processed * 10) as i32;
+                    ship.cargo.remove(*container_type, containers_processed);
+                    // Mise à jour du score : points par conteneur traité
+                    self.score += (containers_processed * self.points_per_container) as i32;
+
+                    // Bonus des navires prioritaires une fois entièrement déchargés
+                    if ship.priority && containers_processed > 0 && ship.is_completed() {
+                        let bonus = ship.containers
+                            * self.points_per_container
+                            * (PRIORITY_SCORE_MULTIPLIER - 1);
+                        self.score += bonus as i32;
+                    }
+                }
+            }
+
+            DomainEvent::EmergencyCraneDeployed {
+                crane_id,
+                ship_id,
+                processing_speed,
+                ..
+            } => {
+                let mut crane = Crane::new(*crane_id, *processing_speed);
+                crane.assign(*ship_id);
+                self.cranes.insert(*crane_id, crane);
+                if let Some(ship) = self.ships.get_mut(ship_id) {
+                    ship.assign_crane(*crane_id);
+                }
+            }
+
+            DomainEvent::EmergencyCraneRemoved { crane_id, .. } => {
+                self.remove_crane(*crane_id);
+            }
+
+            DomainEvent::WaitingPenaltyCharged {
+                amount,
+                charged_until,
+                ..
+            } => {
+                self.score -= amount;
+                self.waiting_cost += amount;
+                self.waiting_charged_until = *charged_until;
+            }
+
+            DomainEvent::IdleCranesCharged { amount, .. } => {
+                self.score -= amount;
+                self.idle_crane_cost += amount;
+            }
+
+            DomainEvent::CraneWearChanged { wear, .. } => {
+                for (crane_id, crane_wear) in wear {
+                    if let Some(crane) = self.cranes.get_mut(crane_id) {
+                        crane.wear = *crane_wear;
+                    }
                 }
             }
 
@@ -128,35 +340,298 @@ impl Port {
         self.version += 1;
     }
 
-    /// Get waiting ships (not docked yet)
+    /// Get waiting ships (not docked yet), sorted by id
     pub fn waiting_ships(&self) -> Vec<&Ship> {
         self.ships.values().filter(|s| !s.is_docked()).collect()
     }
 
-    /// Get docked ships
+    /// Waiting ship that has been in the harbor the longest, with its wait
+    /// (ties go to the smallest id)
+    pub fn slowest_waiting_ship(&self, current_time: f64) -> Option<(ShipId, f64)> {
+        self.waiting_ships()
+            .into_iter()
+            .map(|ship| (ship.id, ship.waiting_time(current_time)))
+            .max_by(|(id_a, wait_a), (id_b, wait_b)| {
+                wait_a.total_cmp(wait_b).then_with(|| id_b.cmp(id_a))
+            })
+    }
+
+    /// Mean wait of the ships currently waiting (0.0 for an empty harbor)
+    pub fn average_wait_time(&self, current_time: f64) -> f64 {
+        let waiting = self.waiting_ships();
+        if waiting.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = waiting
+            .iter()
+            .map(|ship| ship.waiting_time(current_time))
+            .sum();
+        total / waiting.len() as f64
+    }
+
+    /// Get docked ships, sorted by id
     pub fn docked_ships(&self) -> Vec<&Ship> {
         self.ships.values().filter(|s| s.is_docked()).collect()
     }
 
-    /// Get free berths
+    /// Docked ships still holding containers but with no crane working on them
+    pub fn idle_docked_ships(&self) -> Vec<&Ship> {
+        self.docked_ships()
+            .into_iter()
+            .filter(|s| s.assigned_cranes.is_empty() && !s.is_completed())
+            .collect()
+    }
+
+    /// Turns until a ship is unloaded by its currently assigned cranes,
+    /// planned turn by turn like real processing (handling times, cranes
+    /// limited to some cargo types). None if the ship is unknown or its
+    /// cranes cannot unload anything.
+    pub fn estimated_completion(&self, ship_id: ShipId) -> Option<u32> {
+        let mut ship = self.ships.get(&ship_id)?.clone();
+        let mut turns = 0;
+
+        while ship.containers_remaining > 0 {
+            let plan = ship.plan_processing(self.crane_capacity(&ship));
+            if plan.is_empty() {
+                return None;
+            }
+            for (container_type, count) in plan {
+                ship.process_container_type(container_type, count);
+            }
+            turns += 1;
+        }
+        Some(turns)
+    }
+
+    /// Containers the cranes assigned to a ship can unload this turn
+    /// (shared by real processing and MCTS rollouts). A specialized crane
+    /// adds nothing once the ship has no cargo of its types left.
+    pub fn crane_capacity(&self, ship: &Ship) -> f64 {
+        ship.assigned_cranes
+            .iter()
+            .filter_map(|crane_id| self.cranes.get(crane_id))
+            .filter(|crane| crane.can_unload(ship))
+            .map(|crane| crane.containers_per_turn(self.containers_per_crane))
+            .sum()
+    }
+
+    /// Advance crane wear by one turn (working cranes tire, idle ones rest)
+    /// by recording a `CraneWearChanged` for the cranes whose wear moves
+    pub fn tick_crane_wear(&mut self) {
+        let wear: Vec<(CraneId, f64)> = self
+            .cranes
+            .values()
+            .filter_map(|crane| {
+                let mut ticked = crane.clone();
+                ticked.tick_wear();
+                (ticked.wear != crane.wear).then_some((crane.id, ticked.wear))
+            })
+            .collect();
+        if wear.is_empty() {
+            return;
+        }
+        let event = DomainEvent::CraneWearChanged {
+            metadata: EventMetadata::new(self.aggregate_id, self.version + 1),
+            player: self.player_id,
+            wear,
+        };
+        self.record_event(event);
+    }
+
+    /// Whether the tide currently lets this ship dock
+    pub fn tide_allows(&self, ship: &Ship) -> bool {
+        self.tide == Tide::High || !ship.is_large()
+    }
+
+    /// Get free berths, sorted by id
     pub fn free_berths(&self) -> Vec<&Berth> {
         self.berths.values().filter(|b| b.is_free()).collect()
     }
 
-    /// Get free cranes
+    /// Get free cranes, sorted by id
     pub fn free_cranes(&self) -> Vec<&Crane> {
         self.cranes.values().filter(|c| c.is_free()).collect()
     }
 
-    /// Calculate current score (simple heuristic)
+    /// Share of berths occupied by a ship (0.0 for a port without berths)
+    pub fn berth_utilization(&self) -> f64 {
+        let occupied = self.berths.len() - self.free_berths().len();
+        ratio(occupied, self.berths.len())
+    }
+
+    /// Share of cranes assigned to a ship (0.0 for a port without cranes)
+    pub fn crane_utilization(&self) -> f64 {
+        let assigned = self.cranes.len() - self.free_cranes().len();
+        ratio(assigned, self.cranes.len())
+    }
+
+    /// Calculate current score (simple heuristic): the recorded score minus
+    /// the waiting penalty accrued since it was last charged
     pub fn calculate_score(&self) -> i32 {
-        let waiting_penalty: i32 = self
-            .waiting_ships()
+        self.score - self.uncharged_waiting_penalty()
+    }
+
+    /// Penalty of the ships currently waiting, from their arrival or the last
+    /// charge (whichever is later) up to `current_time`
+    fn uncharged_waiting_penalty(&self) -> i32 {
+        self.waiting_ships()
             .into_iter()
-            .map(|ship| (ship.waiting_time(self.current_time) * 5.0) as i32)
+            .map(|ship| {
+                let rate = if ship.priority {
+                    PRIORITY_WAITING_PENALTY
+                } else {
+                    WAITING_PENALTY
+                };
+                let since = ship.arrival_time.max(self.waiting_charged_until);
+                ((self.current_time - since).max(0.0) * rate) as i32
+            })
+            .sum()
+    }
+
+    /// Move the waiting penalty accrued so far into `score` by recording a
+    /// `WaitingPenaltyCharged`, so docking a ship no longer wipes out what its
+    /// wait has cost. Called once per turn; nothing is recorded when nothing is owed.
+    pub fn charge_waiting_penalty(&mut self) {
+        let amount = self.uncharged_waiting_penalty();
+        if amount == 0 {
+            return;
+        }
+        let event = DomainEvent::WaitingPenaltyCharged {
+            metadata: EventMetadata::new(self.aggregate_id, self.version + 1),
+            player: self.player_id,
+            amount,
+            charged_until: self.current_time,
+        };
+        self.record_event(event);
+    }
+
+    /// Free cranes that docked, unfinished ships could still take (capped by
+    /// their open crane slots): the cranes the idle penalty charges
+    pub fn idle_crane_count(&self) -> usize {
+        let open_slots: usize = self
+            .docked_ships()
+            .into_iter()
+            .filter(|ship| !ship.is_completed())
+            .map(|ship| {
+                self.max_cranes_per_ship
+                    .saturating_sub(ship.assigned_cranes.len())
+            })
             .sum();
+        self.free_cranes().len().min(open_slots)
+    }
+
+    /// Charge `idle_crane_penalty` into `score` for each idle crane (see
+    /// `idle_crane_count`) by recording an `IdleCranesCharged`. Called once
+    /// per turn, when containers are processed after the player's moves.
+    pub fn charge_idle_cranes(&mut self) {
+        let idle_cranes = self.idle_crane_count() as u32;
+        let amount = (idle_cranes * self.idle_crane_penalty) as i32;
+        if amount == 0 {
+            return;
+        }
+        let event = DomainEvent::IdleCranesCharged {
+            metadata: EventMetadata::new(self.aggregate_id, self.version + 1),
+            player: self.player_id,
+            idle_cranes,
+            amount,
+        };
+        self.record_event(event);
+    }
 
-        self.score - waiting_penalty
+    /// Logical equality of two ports: ships, berths, cranes, score and clock.
+    /// Event sourcing bookkeeping (version, uncommitted events) is ignored,
+    /// and so is the order in which cranes were assigned to a ship.
+    pub fn state_eq(&self, other: &Port) -> bool {
+        fn normalized(ship: &Ship) -> Ship {
+            let mut ship = ship.clone();
+            ship.assigned_cranes.sort();
+            ship
+        }
+
+        self.ships.len() == other.ships.len()
+            && self
+                .ships
+                .values()
+                .zip(other.ships.values())
+                .all(|(a, b)| normalized(a) == normalized(b))
+            && self.berths == other.berths
+            && self.cranes == other.cranes
+            && self.score == other.score
+            && self.current_time == other.current_time
+    }
+
+    /// Feed the state compared by `state_eq` (plus tide and charges) to
+    /// `hasher` in a canonical order. Ids and counts are written as u64 and
+    /// floats by their bits, so native and WASM builds feed the same values;
+    /// the hash itself only matches across builds with a fully specified
+    /// hasher such as `utils::hash::Fnv1a`. Event sourcing bookkeeping is left out.
+    pub fn hash_state<H: Hasher>(&self, hasher: &mut H) {
+        fn write_option(hasher: &mut impl Hasher, value: Option<usize>) {
+            hasher.write_u64(value.map_or(0, |v| v as u64 + 1));
+        }
+        fn write_ids(hasher: &mut impl Hasher, mut ids: Vec<u64>) {
+            ids.sort_unstable();
+            hasher.write_u64(ids.len() as u64);
+            ids.into_iter().for_each(|id| hasher.write_u64(id));
+        }
+        let type_index = |container_type: &ContainerType| {
+            ContainerType::ALL
+                .iter()
+                .position(|t| t == container_type)
+                .unwrap_or_default() as u64
+        };
+
+        hasher.write(self.player_id.0.as_bytes());
+
+        hasher.write_u64(self.ships.len() as u64);
+        for ship in self.ships.values() {
+            hasher.write_u64(ship.id.0 as u64);
+            hasher.write_u32(ship.containers);
+            hasher.write_u32(ship.containers_remaining);
+            hasher.write_u64(ship.arrival_time.to_bits());
+            write_option(hasher, ship.docked_at.map(|berth| berth.0));
+            write_ids(
+                hasher,
+                ship.assigned_cranes.iter().map(|c| c.0 as u64).collect(),
+            );
+            hasher.write_u8(ship.priority as u8);
+            hasher.write_u32(ship.cargo.standard);
+            hasher.write_u32(ship.cargo.reefer);
+            hasher.write_u32(ship.cargo.hazmat);
+        }
+
+        hasher.write_u64(self.berths.len() as u64);
+        for berth in self.berths.values() {
+            hasher.write_u64(berth.id.0 as u64);
+            write_option(hasher, berth.occupied_by.map(|ship| ship.0));
+            hasher.write_i32(berth.docking_cost);
+        }
+
+        hasher.write_u64(self.cranes.len() as u64);
+        for crane in self.cranes.values() {
+            hasher.write_u64(crane.id.0 as u64);
+            write_option(hasher, crane.assigned_to.map(|ship| ship.0));
+            hasher.write_u64(crane.processing_speed.to_bits());
+            hasher.write_u64(crane.wear.to_bits());
+            hasher.write_u8(crane.compatible_berths.is_some() as u8);
+            if let Some(berths) = &crane.compatible_berths {
+                write_ids(hasher, berths.iter().map(|b| b.0 as u64).collect());
+            }
+            hasher.write_u8(crane.handled_types.is_some() as u8);
+            if let Some(types) = &crane.handled_types {
+                write_ids(hasher, types.iter().map(type_index).collect());
+            }
+        }
+
+        hasher.write_u64(self.current_time.to_bits());
+        hasher.write_i32(self.score);
+        hasher.write_u8(match self.tide {
+            Tide::Low => 0,
+            Tide::High => 1,
+        });
+        hasher.write_i32(self.waiting_cost);
+        hasher.write_u64(self.waiting_charged_until.to_bits());
+        hasher.write_i32(self.idle_crane_cost);
     }
 
     pub fn version(&self) -> u64 {
@@ -171,17 +646,157 @@ impl Port {
         self.uncommitted_events.clear();
     }
 
+    /// Apply an event and keep it as uncommitted until the session persists it
+    pub fn record_event(&mut self, event: DomainEvent) {
+        self.apply_event(&event);
+        self.uncommitted_events.push(event);
+    }
+
+    /// Drain uncommitted events so they can be appended to the event store
+    pub fn take_uncommitted_events(&mut self) -> Vec<DomainEvent> {
+        std::mem::take(&mut self.uncommitted_events)
+    }
+
+    /// Revert the most recent uncommitted dock, crane (un)assignment, pass or
+    /// emergency crane. Committed events (previous turns) are final.
+    pub fn undo_last_event(&mut self) -> Result<DomainEvent, String> {
+        let mut undone = self.undo_last_events(1)?;
+        Ok(undone.remove(0))
+    }
+
+    /// Revert the `count` most recent uncommitted events (newest first) as
+    /// one action: nothing changes unless every one of them can be undone.
+    pub fn undo_last_events(&mut self, count: usize) -> Result<Vec<DomainEvent>, String> {
+        let len = self.uncommitted_events.len();
+        if count == 0 || count > len {
+            return Err("Nothing to undo in the current turn".to_string());
+        }
+        if let Some(event) = self.uncommitted_events[len - count..]
+            .iter()
+            .find(|event| !Self::can_undo(event))
+        {
+            return Err(format!("{} cannot be undone", event.event_type()));
+        }
+
+        let mut undone = Vec::with_capacity(count);
+        for _ in 0..count {
+            let event = self.uncommitted_events.pop().expect("checked above");
+            self.revert_event(&event);
+            self.version -= 1;
+            undone.push(event);
+        }
+        Ok(undone)
+    }
+
+    fn can_undo(event: &DomainEvent) -> bool {
+        matches!(
+            event,
+            DomainEvent::ShipDocked { .. }
+                | DomainEvent::CraneAssigned { .. }
+                | DomainEvent::CraneUnassigned { .. }
+                | DomainEvent::PlayerPassed { .. }
+                | DomainEvent::EmergencyCraneDeployed { .. }
+        )
+    }
+
+    /// Inverse of `apply_event` for the events `can_undo` accepts
+    fn revert_event(&mut self, event: &DomainEvent) {
+        match event {
+            DomainEvent::ShipDocked {
+                ship_id, berth_id, ..
+            } => {
+                if let Some(ship) = self.ships.get_mut(ship_id) {
+                    ship.undock();
+                }
+                if let Some(berth) = self.berths.get_mut(berth_id) {
+                    berth.free();
+                    self.score += berth.docking_cost;
+                }
+            }
+            DomainEvent::CraneAssigned {
+                crane_id, ship_id, ..
+            } => {
+                if let Some(crane) = self.cranes.get_mut(crane_id) {
+                    crane.unassign();
+                }
+                if let Some(ship) = self.ships.get_mut(ship_id) {
+                    ship.unassign_crane(*crane_id);
+                }
+            }
+            DomainEvent::CraneUnassigned {
+                crane_id, ship_id, ..
+            } => {
+                if let Some(crane) = self.cranes.get_mut(crane_id) {
+                    crane.assign(*ship_id);
+                }
+                if let Some(ship) = self.ships.get_mut(ship_id) {
+                    ship.assign_crane(*crane_id);
+                }
+            }
+            DomainEvent::EmergencyCraneDeployed { crane_id, .. } => self.remove_crane(*crane_id),
+            _ => {} // Nothing changed on the port (pass)
+        }
+    }
+
+    /// Put an extra crane of `processing_speed` on a docked ship, under the
+    /// next free crane id
+    pub fn deploy_emergency_crane(
+        &mut self,
+        ship_id: ShipId,
+        processing_speed: f64,
+    ) -> Result<CraneId, String> {
+        self.ships
+            .get(&ship_id)
+            .filter(|ship| ship.is_docked() && !ship.is_completed())
+            .ok_or_else(|| format!("Ship {} is not docked", ship_id.0))?;
+
+        let crane_id = CraneId::new(self.cranes.keys().last().map_or(0, |id| id.0 + 1));
+        let event = DomainEvent::EmergencyCraneDeployed {
+            metadata: EventMetadata::new(self.aggregate_id, self.version + 1),
+            crane_id,
+            ship_id,
+            player: self.player_id,
+            processing_speed,
+        };
+        self.record_event(event);
+        Ok(crane_id)
+    }
+
+    /// Send an emergency crane back, releasing the ship it worked on
+    pub fn withdraw_emergency_crane(&mut self, crane_id: CraneId) {
+        if self.cranes.contains_key(&crane_id) {
+            let event = DomainEvent::EmergencyCraneRemoved {
+                metadata: EventMetadata::new(self.aggregate_id, self.version + 1),
+                crane_id,
+                player: self.player_id,
+            };
+            self.record_event(event);
+        }
+    }
+
+    fn remove_crane(&mut self, crane_id: CraneId) {
+        let Some(crane) = self.cranes.remove(&crane_id) else {
+            return;
+        };
+        if let Some(ship) = crane
+            .assigned_to
+            .and_then(|ship_id| self.ships.get_mut(&ship_id))
+        {
+            ship.unassign_crane(crane_id);
+        }
+    }
+
     pub fn free_crane(&mut self, crane_id: CraneId) {
         if let Some(crane) = self.cranes.get_mut(&crane_id) {
             if let Some(ship_id) = crane.assigned_to {
                 let event = DomainEvent::CraneUnassigned {
-                    metadata: EventMetadata::new(Uuid::new_v4(), self.version + 1),
+                    metadata: EventMetadata::new(self.aggregate_id, self.version + 1),
                     crane_id,
                     ship_id,
                     unassignment_time: self.current_time,
+                    player: Some(self.player_id),
                 };
-                self.apply_event(&event);
-                self.uncommitted_events.push(event);
+                self.record_event(event);
             }
         }
     }
@@ -191,14 +806,14 @@ impl Port {
             if ship.docked_at == Some(berth_id) {
                 let containers_processed = ship.containers - ship.containers_remaining;
                 let event = DomainEvent::ShipUndocked {
-                    metadata: EventMetadata::new(Uuid::new_v4(), self.version + 1),
+                    metadata: EventMetadata::new(self.aggregate_id, self.version + 1),
                     ship_id,
                     berth_id,
+                    player: self.player_id,
                     completion_time: self.current_time,
                     containers_processed,
                 };
-                self.apply_event(&event);
-                self.uncommitted_events.push(event);
+                self.record_event(event);
             }
         }
     }
@@ -215,6 +830,7 @@ impl Port {
 mod tests {
     use super::*;
     use crate::domain::events::EventMetadata;
+    use crate::domain::value_objects::ContainerType;
     use uuid::Uuid;
 
     #[test]
@@ -228,6 +844,25 @@ mod tests {
         assert_eq!(port.version, 0);
     }
 
+    #[test]
+    fn test_port_with_crane_speeds() {
+        let mut port = Port::new_with_crane_speeds(PlayerId::new(), 2, &[1.0, 2.0, 3.0]);
+
+        let speeds: Vec<f64> = port.cranes.values().map(|c| c.processing_speed).collect();
+        assert_eq!(speeds, vec![1.0, 2.0, 3.0]);
+
+        port.cranes
+            .get_mut(&CraneId::new(1))
+            .unwrap()
+            .assign(ShipId::new(0));
+        let free: Vec<(CraneId, f64)> = port
+            .free_cranes()
+            .iter()
+            .map(|crane| (crane.id, crane.processing_speed))
+            .collect();
+        assert_eq!(free, vec![(CraneId::new(0), 1.0), (CraneId::new(2), 3.0)]);
+    }
+
     #[test]
     fn test_ship_arrival_event() {
         let player_id = PlayerId::new();
@@ -238,6 +873,9 @@ mod tests {
             ship_id: ShipId::new(1),
             container_count: 50,
             arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
         };
 
         port.apply_event(&event);
@@ -257,6 +895,9 @@ mod tests {
             ship_id: ShipId::new(1),
             container_count: 50,
             arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
         };
         port.apply_event(&arrival_event);
 
@@ -285,6 +926,9 @@ mod tests {
             ship_id: ShipId::new(1),
             container_count: 50,
             arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
         };
         port.apply_event(&arrival_event);
 
@@ -312,6 +956,234 @@ mod tests {
         assert_eq!(ship.assigned_cranes.len(), 1);
     }
 
+    fn processed_score(priority: bool) -> i32 {
+        let player_id = PlayerId::new();
+        let mut port = Port::new(player_id, 2, 2);
+
+        port.apply_event(&DomainEvent::ShipArrived {
+            metadata: EventMetadata::new(Uuid::new_v4(), 1),
+            ship_id: ShipId::new(1),
+            container_count: 20,
+            arrival_time: 0.0,
+            priority,
+            cargo: None,
+            player: None,
+        });
+        port.apply_event(&DomainEvent::ContainerProcessed {
+            metadata: EventMetadata::new(Uuid::new_v4(), 2),
+            crane_id: CraneId::new(0),
+            ship_id: ShipId::new(1),
+            player: port.player_id,
+            containers_remaining: 0,
+            container_type: ContainerType::Standard,
+        });
+
+        port.score
+    }
+
+    #[test]
+    fn test_conflicting_crane_assignment_is_skipped() {
+        let player_id = PlayerId::new();
+        let mut port = Port::new(player_id, 2, 1);
+        let aggregate_id = Uuid::new_v4();
+        let crane_id = CraneId::new(0);
+        let (first, second) = (ShipId::new(1), ShipId::new(2));
+
+        let mut version = 0;
+        let mut next_metadata = || {
+            version += 1;
+            EventMetadata::new(aggregate_id, version)
+        };
+        let mut stream = Vec::new();
+        for (ship_id, berth) in [(first, 0), (second, 1)] {
+            stream.push(DomainEvent::ShipArrived {
+                metadata: next_metadata(),
+                ship_id,
+                container_count: 20,
+                arrival_time: 0.0,
+                priority: false,
+                cargo: None,
+                player: None,
+            });
+            stream.push(DomainEvent::ShipDocked {
+                metadata: next_metadata(),
+                ship_id,
+                berth_id: BerthId::new(berth),
+                player: player_id,
+                docking_time: 0.0,
+            });
+        }
+        // The second assignment is bogus: the crane is already busy
+        for ship_id in [first, second] {
+            stream.push(DomainEvent::CraneAssigned {
+                metadata: next_metadata(),
+                crane_id,
+                ship_id,
+                player: player_id,
+                assignment_time: 1.0,
+            });
+        }
+
+        for event in &stream {
+            port.apply_event(event);
+        }
+
+        assert_eq!(port.cranes[&crane_id].assigned_to, Some(first));
+        assert_eq!(port.ships[&first].assigned_cranes, vec![crane_id]);
+        assert!(port.ships[&second].assigned_cranes.is_empty());
+    }
+
+    #[test]
+    fn test_waiting_time_stats() {
+        let mut port = Port::new(PlayerId::new(), 1, 1);
+        assert_eq!(port.slowest_waiting_ship(10.0), None);
+        assert_eq!(port.average_wait_time(10.0), 0.0);
+
+        for (id, arrival) in [(1, 4.0), (2, 1.0), (3, 7.0), (4, 1.0)] {
+            port.ships
+                .insert(ShipId::new(id), Ship::new(ShipId::new(id), 20, arrival));
+        }
+        // Docked ships no longer count as waiting
+        let mut docked = Ship::new(ShipId::new(5), 20, 0.0);
+        docked.dock(BerthId::new(0));
+        port.ships.insert(docked.id, docked);
+
+        assert_eq!(port.slowest_waiting_ship(10.0), Some((ShipId::new(2), 9.0)));
+        assert_eq!(port.average_wait_time(10.0), (6.0 + 9.0 + 3.0 + 9.0) / 4.0);
+    }
+
+    #[test]
+    fn test_estimated_completion() {
+        let player_id = PlayerId::new();
+        let mut port = Port::new(player_id, 1, 1);
+        port.cranes
+            .get_mut(&CraneId::new(0))
+            .unwrap()
+            .processing_speed = 1.0; // Half the default speed: 5/turn
+
+        port.apply_event(&DomainEvent::ShipArrived {
+            metadata: EventMetadata::new(Uuid::new_v4(), 1),
+            ship_id: ShipId::new(1),
+            container_count: 30,
+            arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
+        });
+        port.apply_event(&DomainEvent::ShipDocked {
+            metadata: EventMetadata::new(Uuid::new_v4(), 2),
+            ship_id: ShipId::new(1),
+            berth_id: BerthId::new(0),
+            player: player_id,
+            docking_time: 0.0,
+        });
+
+        assert_eq!(port.estimated_completion(ShipId::new(1)), None);
+
+        port.apply_event(&DomainEvent::CraneAssigned {
+            metadata: EventMetadata::new(Uuid::new_v4(), 3),
+            crane_id: CraneId::new(0),
+            ship_id: ShipId::new(1),
+            player: player_id,
+            assignment_time: 0.0,
+        });
+
+        assert_eq!(port.estimated_completion(ShipId::new(1)), Some(6));
+        assert_eq!(port.estimated_completion(ShipId::new(99)), None);
+    }
+
+    #[test]
+    fn test_estimated_completion_counts_handling_times() {
+        let player_id = PlayerId::new();
+        let mut port = Port::new(player_id, 1, 1);
+        port.cranes
+            .get_mut(&CraneId::new(0))
+            .unwrap()
+            .processing_speed = DEFAULT_CRANE_SPEED; // 10/turn
+
+        port.apply_event(&DomainEvent::ShipArrived {
+            metadata: EventMetadata::new(Uuid::new_v4(), 1),
+            ship_id: ShipId::new(1),
+            container_count: 20,
+            arrival_time: 0.0,
+            priority: false,
+            cargo: Some(CargoMix::new(10, 0, 10)),
+            player: None,
+        });
+        for event in [
+            DomainEvent::ShipDocked {
+                metadata: EventMetadata::new(Uuid::new_v4(), 2),
+                ship_id: ShipId::new(1),
+                berth_id: BerthId::new(0),
+                player: player_id,
+                docking_time: 0.0,
+            },
+            DomainEvent::CraneAssigned {
+                metadata: EventMetadata::new(Uuid::new_v4(), 3),
+                crane_id: CraneId::new(0),
+                ship_id: ShipId::new(1),
+                player: player_id,
+                assignment_time: 0.0,
+            },
+        ] {
+            port.apply_event(&event);
+        }
+
+        // 10 standard containers, then 5 hazmat a turn (twice as slow)
+        assert_eq!(port.estimated_completion(ShipId::new(1)), Some(3));
+    }
+
+    #[test]
+    fn test_priority_ship_awards_bonus() {
+        assert_eq!(processed_score(false), 200);
+        assert!(processed_score(true) > processed_score(false));
+    }
+
+    #[test]
+    fn test_container_processed_tracks_cargo_type() {
+        let player_id = PlayerId::new();
+        let mut port = Port::new(player_id, 2, 2);
+
+        port.apply_event(&DomainEvent::ShipArrived {
+            metadata: EventMetadata::new(Uuid::new_v4(), 1),
+            ship_id: ShipId::new(1),
+            container_count: 30,
+            arrival_time: 0.0,
+            priority: false,
+            cargo: Some(CargoMix::new(10, 0, 20)),
+            player: None,
+        });
+        port.apply_event(&DomainEvent::ContainerProcessed {
+            metadata: EventMetadata::new(Uuid::new_v4(), 2),
+            crane_id: CraneId::new(0),
+            ship_id: ShipId::new(1),
+            player: player_id,
+            containers_remaining: 25,
+            container_type: ContainerType::Hazmat,
+        });
+
+        let ship = port.ships.get(&ShipId::new(1)).unwrap();
+        assert_eq!(ship.cargo, CargoMix::new(10, 0, 15));
+        assert_eq!(ship.containers_remaining, 25);
+    }
+
+    #[test]
+    fn test_priority_ship_waiting_penalty() {
+        let player_id = PlayerId::new();
+        let mut port = Port::new(player_id, 2, 2);
+        port.ships
+            .insert(ShipId::new(1), Ship::new(ShipId::new(1), 20, 0.0));
+        port.current_time = 2.0;
+        let normal_penalty = -port.calculate_score();
+
+        port.ships.clear();
+        port.ships
+            .insert(ShipId::new(1), Ship::new_priority(ShipId::new(1), 20, 0.0));
+        let priority_penalty = -port.calculate_score();
+
+        assert!(priority_penalty > normal_penalty);
+    }
+
     #[test]
     fn test_free_crane() {
         let player_id = PlayerId::new();
@@ -322,6 +1194,9 @@ mod tests {
             ship_id: ShipId::new(1),
             container_count: 50,
             arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
         };
         port.apply_event(&arrival_event);
 
@@ -360,6 +1235,9 @@ mod tests {
             ship_id: ShipId::new(1),
             container_count: 50,
             arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
         };
         port.apply_event(&arrival_event);
 
@@ -377,4 +1255,306 @@ mod tests {
         assert_eq!(port.ships.len(), 0);
         assert_eq!(port.free_berths().len(), 2);
     }
+
+    fn arrival(ship: usize) -> DomainEvent {
+        DomainEvent::ShipArrived {
+            metadata: EventMetadata::new(Uuid::new_v4(), 0),
+            ship_id: ShipId::new(ship),
+            container_count: 30,
+            arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
+        }
+    }
+
+    fn crane_assigned(player_id: PlayerId, crane: usize, ship: usize) -> DomainEvent {
+        DomainEvent::CraneAssigned {
+            metadata: EventMetadata::new(Uuid::new_v4(), 0),
+            crane_id: CraneId::new(crane),
+            ship_id: ShipId::new(ship),
+            player: player_id,
+            assignment_time: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_docking_fee_is_charged_and_refunded_on_undo() {
+        let player_id = PlayerId::new();
+        let mut port = Port::new(player_id, 2, 1);
+        port.berths.get_mut(&BerthId::new(1)).unwrap().docking_cost = 25;
+        port.apply_event(&arrival(1));
+
+        port.record_event(DomainEvent::ShipDocked {
+            metadata: EventMetadata::new(Uuid::new_v4(), 1),
+            ship_id: ShipId::new(1),
+            berth_id: BerthId::new(1),
+            player: player_id,
+            docking_time: 0.0,
+        });
+        assert_eq!(port.score, -25);
+        assert_eq!(port.calculate_score(), -25);
+
+        port.undo_last_event().unwrap();
+        assert_eq!(port.score, 0);
+    }
+
+    #[test]
+    fn test_idle_crane_scores_lower() {
+        let player_id = PlayerId::new();
+        let mut port = Port::new(player_id, 1, 2);
+        port.apply_event(&arrival(1));
+        port.apply_event(&DomainEvent::ShipDocked {
+            metadata: EventMetadata::new(Uuid::new_v4(), 0),
+            ship_id: ShipId::new(1),
+            berth_id: BerthId::new(0),
+            player: player_id,
+            docking_time: 0.0,
+        });
+        port.apply_event(&crane_assigned(player_id, 0, 1));
+
+        let mut busy = port.clone();
+        busy.apply_event(&crane_assigned(player_id, 1, 1));
+        let mut idle = port;
+
+        for _ in 0..3 {
+            busy.charge_idle_cranes();
+            idle.charge_idle_cranes();
+        }
+
+        assert_eq!(busy.calculate_score(), 0);
+        assert_eq!(
+            idle.calculate_score(),
+            -3 * DEFAULT_IDLE_CRANE_PENALTY as i32
+        );
+        assert_eq!(idle.idle_crane_cost, 3 * DEFAULT_IDLE_CRANE_PENALTY as i32);
+    }
+
+    #[test]
+    fn test_random_layout_respects_constraints() {
+        let constraints = LayoutConstraints {
+            min_berths: 3,
+            max_berths: 5,
+            min_cranes: 1,
+            max_cranes: 6,
+            min_crane_speed: 1.0,
+            max_crane_speed: 2.0,
+        };
+
+        for seed in 0..20 {
+            let port = Port::random_layout(PlayerId::new(), seed, constraints);
+            assert!((3..=5).contains(&port.berths.len()));
+            assert!((1..=6).contains(&port.cranes.len()));
+            assert!(port
+                .cranes
+                .values()
+                .all(|crane| (1.0..=2.0).contains(&crane.processing_speed)));
+        }
+    }
+
+    #[test]
+    fn test_state_eq_ignores_event_order_and_bookkeeping() {
+        let player_id = PlayerId::new();
+        let dock = DomainEvent::ShipDocked {
+            metadata: EventMetadata::new(Uuid::new_v4(), 0),
+            ship_id: ShipId::new(1),
+            berth_id: BerthId::new(0),
+            player: player_id,
+            docking_time: 1.0,
+        };
+
+        let mut port_a = Port::new(player_id, 2, 2);
+        for event in [
+            arrival(1),
+            arrival(2),
+            dock.clone(),
+            crane_assigned(player_id, 0, 1),
+            crane_assigned(player_id, 1, 1),
+        ] {
+            port_a.apply_event(&event);
+        }
+
+        // Same state reached in another order, with uncommitted events left
+        let mut port_b = Port::new(player_id, 2, 2);
+        for event in [
+            arrival(2),
+            arrival(1),
+            dock,
+            crane_assigned(player_id, 1, 1),
+            crane_assigned(player_id, 0, 1),
+        ] {
+            port_b.record_event(event);
+        }
+        port_b.free_crane(CraneId::new(1));
+        port_b.record_event(crane_assigned(player_id, 1, 1));
+
+        assert_ne!(port_a.version(), port_b.version());
+        assert!(port_a.state_eq(&port_b));
+        assert!(port_b.state_eq(&port_a));
+
+        port_b.apply_event(&DomainEvent::ContainerProcessed {
+            metadata: EventMetadata::new(Uuid::new_v4(), 0),
+            crane_id: CraneId::new(0),
+            ship_id: ShipId::new(1),
+            player: player_id,
+            containers_remaining: 20,
+            container_type: ContainerType::Standard,
+        });
+        assert!(!port_a.state_eq(&port_b));
+    }
+
+    #[test]
+    fn test_state_eq_detects_different_state() {
+        let player_id = PlayerId::new();
+        let port = Port::new(player_id, 2, 2);
+
+        let mut other = port.clone();
+        other.apply_event(&arrival(1));
+        assert!(!port.state_eq(&other));
+
+        let mut other = port.clone();
+        other.current_time = 3.0;
+        assert!(!port.state_eq(&other));
+
+        let mut other = port.clone();
+        other.cranes.get_mut(&CraneId::new(0)).unwrap().wear = 0.1;
+        assert!(!port.state_eq(&other));
+
+        assert!(port.state_eq(&port.clone()));
+    }
+
+    #[test]
+    fn test_idle_docked_ships() {
+        let player_id = PlayerId::new();
+        let mut port = Port::new(player_id, 2, 2);
+
+        for (ship, berth) in [(1, 0), (2, 1)] {
+            port.apply_event(&arrival(ship));
+            port.apply_event(&DomainEvent::ShipDocked {
+                metadata: EventMetadata::new(Uuid::new_v4(), 0),
+                ship_id: ShipId::new(ship),
+                berth_id: BerthId::new(berth),
+                player: player_id,
+                docking_time: 1.0,
+            });
+        }
+        port.apply_event(&crane_assigned(player_id, 0, 1));
+
+        let idle: Vec<ShipId> = port.idle_docked_ships().iter().map(|s| s.id).collect();
+        assert_eq!(idle, vec![ShipId::new(2)]);
+    }
+
+    #[test]
+    fn test_berth_and_crane_utilization() {
+        let player_id = PlayerId::new();
+        let mut port = Port::new(player_id, 4, 3);
+        assert_eq!(port.berth_utilization(), 0.0);
+        assert_eq!(port.crane_utilization(), 0.0);
+
+        port.apply_event(&arrival(1));
+        port.apply_event(&DomainEvent::ShipDocked {
+            metadata: EventMetadata::new(Uuid::new_v4(), 0),
+            ship_id: ShipId::new(1),
+            berth_id: BerthId::new(2),
+            player: player_id,
+            docking_time: 1.0,
+        });
+        port.apply_event(&crane_assigned(player_id, 0, 1));
+        port.apply_event(&crane_assigned(player_id, 2, 1));
+
+        assert_eq!(port.berth_utilization(), 0.25);
+        assert_eq!(port.crane_utilization(), 2.0 / 3.0);
+
+        let empty = Port::new(player_id, 0, 0);
+        assert_eq!(empty.berth_utilization(), 0.0);
+        assert_eq!(empty.crane_utilization(), 0.0);
+    }
+
+    #[test]
+    fn test_out_of_order_container_processed_is_ignored() {
+        let player_id = PlayerId::new();
+        let mut port = Port::new(player_id, 2, 2);
+        port.apply_event(&arrival(1));
+
+        let processed = |remaining| DomainEvent::ContainerProcessed {
+            metadata: EventMetadata::new(Uuid::new_v4(), 0),
+            crane_id: CraneId::new(0),
+            ship_id: ShipId::new(1),
+            player: player_id,
+            containers_remaining: remaining,
+            container_type: ContainerType::Standard,
+        };
+        port.apply_event(&processed(10));
+        assert_eq!(port.score, 200);
+
+        // Arrives after the later one: 25 left is more than the ship has
+        port.apply_event(&processed(25));
+
+        assert_eq!(port.score, 200);
+        assert_eq!(port.ships[&ShipId::new(1)].containers_remaining, 10);
+    }
+
+    #[test]
+    fn test_listings_are_sorted_by_id_and_stable() {
+        let mut port = Port::new(PlayerId::new(), 4, 4);
+        for id in [7, 2, 9, 4, 1] {
+            port.ships
+                .insert(ShipId::new(id), Ship::new(ShipId::new(id), 20, 0.0));
+        }
+        for (ship, berth) in [(9, 3), (4, 0)] {
+            port.ships
+                .get_mut(&ShipId::new(ship))
+                .unwrap()
+                .dock(BerthId::new(berth));
+            port.berths
+                .get_mut(&BerthId::new(berth))
+                .unwrap()
+                .occupy(ShipId::new(ship));
+        }
+        port.cranes
+            .get_mut(&CraneId::new(2))
+            .unwrap()
+            .assign(ShipId::new(9));
+
+        let waiting: Vec<usize> = port.waiting_ships().iter().map(|s| s.id.0).collect();
+        let docked: Vec<usize> = port.docked_ships().iter().map(|s| s.id.0).collect();
+        let berths: Vec<usize> = port.free_berths().iter().map(|b| b.id.0).collect();
+        let cranes: Vec<usize> = port.free_cranes().iter().map(|c| c.id.0).collect();
+        assert_eq!(waiting, vec![1, 2, 7]);
+        assert_eq!(docked, vec![4, 9]);
+        assert_eq!(berths, vec![1, 2]);
+        assert_eq!(cranes, vec![0, 1, 3]);
+
+        // Same order on every call
+        for _ in 0..3 {
+            assert_eq!(
+                port.waiting_ships()
+                    .iter()
+                    .map(|s| s.id.0)
+                    .collect::<Vec<_>>(),
+                waiting
+            );
+            assert_eq!(
+                port.docked_ships()
+                    .iter()
+                    .map(|s| s.id.0)
+                    .collect::<Vec<_>>(),
+                docked
+            );
+            assert_eq!(
+                port.free_berths()
+                    .iter()
+                    .map(|b| b.id.0)
+                    .collect::<Vec<_>>(),
+                berths
+            );
+            assert_eq!(
+                port.free_cranes()
+                    .iter()
+                    .map(|c| c.id.0)
+                    .collect::<Vec<_>>(),
+                cranes
+            );
+        }
+    }
 }
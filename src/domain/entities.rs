@@ -2,11 +2,63 @@
 // Following DDD principles
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
-use super::value_objects::{BerthId, CraneId, ShipId};
+use super::aggregates::DEFAULT_CRANE_SPEED;
+use super::value_objects::{BerthId, ContainerType, CraneId, ShipId};
+
+/// Ships carrying more containers than this only dock at high tide
+pub const LARGE_SHIP_CONTAINERS: u32 = 40;
+
+/// Remaining containers on a ship, per container type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CargoMix {
+    pub standard: u32,
+    pub reefer: u32,
+    pub hazmat: u32,
+}
+
+impl CargoMix {
+    pub fn new(standard: u32, reefer: u32, hazmat: u32) -> Self {
+        Self {
+            standard,
+            reefer,
+            hazmat,
+        }
+    }
+
+    /// Cargo made only of standard containers
+    pub fn standard(count: u32) -> Self {
+        Self::new(count, 0, 0)
+    }
+
+    pub fn total(&self) -> u32 {
+        self.standard + self.reefer + self.hazmat
+    }
+
+    pub fn count(&self, container_type: ContainerType) -> u32 {
+        match container_type {
+            ContainerType::Standard => self.standard,
+            ContainerType::Reefer => self.reefer,
+            ContainerType::Hazmat => self.hazmat,
+        }
+    }
+
+    /// Remove up to `count` containers of the given type, returns how many were removed
+    pub fn remove(&mut self, container_type: ContainerType, count: u32) -> u32 {
+        let slot = match container_type {
+            ContainerType::Standard => &mut self.standard,
+            ContainerType::Reefer => &mut self.reefer,
+            ContainerType::Hazmat => &mut self.hazmat,
+        };
+        let removed = count.min(*slot);
+        *slot -= removed;
+        removed
+    }
+}
 
 /// Ship entity - Represents a cargo ship waiting to dock
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Ship {
     pub id: ShipId,
     pub containers: u32,
@@ -14,6 +66,8 @@ pub struct Ship {
     pub arrival_time: f64,
     pub docked_at: Option<BerthId>,
     pub assigned_cranes: Vec<CraneId>,
+    pub priority: bool, // Perishable cargo: bonus when unloaded, steeper waiting penalty
+    pub cargo: CargoMix,
 }
 
 impl Ship {
@@ -25,6 +79,22 @@ impl Ship {
             arrival_time,
             docked_at: None,
             assigned_cranes: Vec::new(),
+            priority: false,
+            cargo: CargoMix::standard(containers),
+        }
+    }
+
+    pub fn with_cargo(id: ShipId, cargo: CargoMix, arrival_time: f64) -> Self {
+        Self {
+            cargo,
+            ..Self::new(id, cargo.total(), arrival_time)
+        }
+    }
+
+    pub fn new_priority(id: ShipId, containers: u32, arrival_time: f64) -> Self {
+        Self {
+            priority: true,
+            ..Self::new(id, containers, arrival_time)
         }
     }
 
@@ -32,10 +102,37 @@ impl Ship {
         self.docked_at.is_some()
     }
 
+    /// Large ships need high tide to dock
+    pub fn is_large(&self) -> bool {
+        self.containers > LARGE_SHIP_CONTAINERS
+    }
+
     pub fn is_completed(&self) -> bool {
         self.containers_remaining == 0
     }
 
+    /// Share of the cargo already unloaded, in `[0, 1]` (an empty ship is done)
+    pub fn progress(&self) -> f64 {
+        if self.containers == 0 {
+            return 1.0;
+        }
+        let unloaded = self.containers.saturating_sub(self.containers_remaining);
+        unloaded as f64 / self.containers as f64
+    }
+
+    /// Turns needed to unload the remaining containers at the given throughput
+    /// (`u32::MAX` when nothing is processing the ship)
+    pub fn turns_to_complete(&self, crane_throughput_per_turn: u32) -> u32 {
+        if self.containers_remaining == 0 {
+            return 0;
+        }
+        if crane_throughput_per_turn == 0 {
+            return u32::MAX;
+        }
+        self.containers_remaining
+            .div_ceil(crane_throughput_per_turn)
+    }
+
     pub fn dock(&mut self, berth_id: BerthId) {
         self.docked_at = Some(berth_id);
     }
@@ -56,19 +153,60 @@ impl Ship {
     }
 
     pub fn process_containers(&mut self, count: u32) {
+        let mut left = count.min(self.containers_remaining);
+        for container_type in ContainerType::ALL {
+            left -= self.cargo.remove(container_type, left);
+        }
         self.containers_remaining = self.containers_remaining.saturating_sub(count);
     }
 
+    /// Unload containers of a single type
+    pub fn process_container_type(&mut self, container_type: ContainerType, count: u32) {
+        let removed = self.cargo.remove(container_type, count);
+        self.containers_remaining = self.containers_remaining.saturating_sub(removed);
+    }
+
+    /// Split a handling capacity (in standard-container units) across the
+    /// remaining cargo, in `ContainerType::ALL` order
+    pub fn plan_processing(&self, capacity: f64) -> Vec<(ContainerType, u32)> {
+        let mut plan = Vec::new();
+        let mut capacity = capacity;
+        let mut budget = self.containers_remaining;
+
+        for container_type in ContainerType::ALL {
+            let available = self.cargo.count(container_type).min(budget);
+            if available == 0 {
+                continue;
+            }
+
+            let affordable = (capacity / container_type.handling_time()).floor() as u32;
+            let count = available.min(affordable);
+            if count > 0 {
+                plan.push((container_type, count));
+                capacity -= count as f64 * container_type.handling_time();
+                budget -= count;
+            }
+
+            if count < available {
+                break;
+            }
+        }
+
+        plan
+    }
+
     pub fn waiting_time(&self, current_time: f64) -> f64 {
         current_time - self.arrival_time
     }
 }
 
 /// Berth entity - Docking position for ships
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Berth {
     pub id: BerthId,
     pub occupied_by: Option<ShipId>,
+    #[serde(default)]
+    pub docking_cost: i32, // Score paid by each ship docking here
 }
 
 impl Berth {
@@ -76,6 +214,7 @@ impl Berth {
         Self {
             id,
             occupied_by: None,
+            docking_cost: 0,
         }
     }
 
@@ -92,12 +231,22 @@ impl Berth {
     }
 }
 
+/// Wear added each turn a crane works, and the maximum it can reach
+const CRANE_WEAR_PER_TURN: f64 = 0.1;
+const MAX_CRANE_WEAR: f64 = 0.5;
+
 /// Crane entity - Equipment for unloading containers
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Crane {
     pub id: CraneId,
     pub assigned_to: Option<ShipId>,
     pub processing_speed: f64, // containers per time unit
+    #[serde(default)]
+    pub wear: f64, // 0.0 = fresh, reduces throughput until rested
+    #[serde(default)]
+    pub compatible_berths: Option<HashSet<BerthId>>, // None = can reach any berth
+    #[serde(default)]
+    pub handled_types: Option<HashSet<ContainerType>>, // None = handles any cargo
 }
 
 impl Crane {
@@ -106,6 +255,9 @@ impl Crane {
             id,
             assigned_to: None,
             processing_speed,
+            wear: 0.0,
+            compatible_berths: None,
+            handled_types: None,
         }
     }
 
@@ -113,6 +265,28 @@ impl Crane {
         self.assigned_to.is_none()
     }
 
+    /// Whether the crane can work on a ship docked at this berth
+    /// (rail-mounted cranes only reach the berths next to them)
+    pub fn can_serve(&self, berth_id: BerthId) -> bool {
+        self.compatible_berths
+            .as_ref()
+            .is_none_or(|berths| berths.contains(&berth_id))
+    }
+
+    /// Whether the crane is rigged for this kind of container
+    pub fn can_handle(&self, container_type: ContainerType) -> bool {
+        self.handled_types
+            .as_ref()
+            .is_none_or(|types| types.contains(&container_type))
+    }
+
+    /// Whether any container still on the ship is one the crane can handle
+    pub fn can_unload(&self, ship: &Ship) -> bool {
+        ContainerType::ALL.into_iter().any(|container_type| {
+            ship.cargo.count(container_type) > 0 && self.can_handle(container_type)
+        })
+    }
+
     pub fn assign(&mut self, ship_id: ShipId) {
         self.assigned_to = Some(ship_id);
     }
@@ -121,8 +295,25 @@ impl Crane {
         self.assigned_to = None;
     }
 
-    pub fn containers_per_turn(&self) -> u32 {
-        (self.processing_speed * 10.0) as u32 // Simple formula
+    /// Containers unloaded per turn: `containers_per_crane` at the default
+    /// speed, scaled by this crane's speed and worn down by use
+    pub fn containers_per_turn(&self, containers_per_crane: u32) -> f64 {
+        containers_per_crane as f64 * self.processing_speed / DEFAULT_CRANE_SPEED
+            * self.efficiency()
+    }
+
+    /// Share of nominal throughput left after wear (1.0 = fresh)
+    pub fn efficiency(&self) -> f64 {
+        1.0 - self.wear
+    }
+
+    /// End of turn: a working crane wears down, an idle one is fully rested
+    pub fn tick_wear(&mut self) {
+        if self.is_free() {
+            self.wear = 0.0;
+        } else {
+            self.wear = (self.wear + CRANE_WEAR_PER_TURN).min(MAX_CRANE_WEAR);
+        }
     }
 }
 
@@ -178,6 +369,48 @@ mod tests {
         assert!(ship.is_completed());
     }
 
+    #[test]
+    fn test_hazmat_cargo_takes_longer() {
+        fn turns_to_unload(mut ship: Ship) -> u32 {
+            let mut turns = 0;
+            while !ship.is_completed() {
+                for (container_type, count) in ship.plan_processing(10.0) {
+                    ship.process_container_type(container_type, count);
+                }
+                turns += 1;
+            }
+            turns
+        }
+
+        let standard = Ship::with_cargo(ShipId::new(1), CargoMix::standard(30), 0.0);
+        let hazmat = Ship::with_cargo(ShipId::new(2), CargoMix::new(0, 0, 30), 0.0);
+
+        assert_eq!(turns_to_unload(standard), 3);
+        assert!(turns_to_unload(hazmat) > 3);
+    }
+
+    #[test]
+    fn test_turns_to_complete() {
+        let ship = Ship::new(ShipId::new(1), 30, 0.0);
+
+        assert_eq!(ship.turns_to_complete(10), 3);
+        assert_eq!(ship.turns_to_complete(20), 2);
+        assert_eq!(ship.turns_to_complete(0), u32::MAX);
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut ship = Ship::new(ShipId::new(1), 50, 0.0);
+        assert_eq!(ship.progress(), 0.0);
+
+        ship.process_containers(30);
+        assert_eq!(ship.containers_remaining, 20);
+        assert!((ship.progress() - 0.6).abs() < 1e-9);
+
+        let empty = Ship::new(ShipId::new(2), 0, 0.0);
+        assert_eq!(empty.progress(), 1.0);
+    }
+
     #[test]
     fn test_berth_occupation() {
         let mut berth = Berth::new(BerthId::new(1));
@@ -195,6 +428,35 @@ mod tests {
         assert!(berth.is_free());
     }
 
+    #[test]
+    fn test_crane_wear_and_rest() {
+        let mut crane = Crane::new(CraneId::new(1), DEFAULT_CRANE_SPEED);
+        crane.assign(ShipId::new(1));
+
+        let mut throughput = vec![crane.containers_per_turn(10) as u32];
+        for _ in 0..3 {
+            crane.tick_wear();
+            throughput.push(crane.containers_per_turn(10) as u32);
+        }
+        assert_eq!(throughput, vec![10, 9, 8, 7]);
+
+        // One idle turn is enough to recover
+        crane.unassign();
+        crane.tick_wear();
+        assert_eq!(crane.containers_per_turn(10) as u32, 10);
+    }
+
+    #[test]
+    fn test_faster_crane_unloads_more() {
+        let slow = Crane::new(CraneId::new(1), 1.0);
+        let default = Crane::new(CraneId::new(2), DEFAULT_CRANE_SPEED);
+        let fast = Crane::new(CraneId::new(3), 3.0);
+
+        assert_eq!(slow.containers_per_turn(10), 5.0);
+        assert_eq!(default.containers_per_turn(10), 10.0);
+        assert_eq!(fast.containers_per_turn(10), 15.0);
+    }
+
     #[test]
     fn test_crane_availability() {
         let mut crane = Crane::new(CraneId::new(1), 2.0);
@@ -5,7 +5,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::value_objects::{BerthId, CraneId, PlayerId, ShipId};
+use super::entities::CargoMix;
+use super::value_objects::{BerthId, ContainerType, CraneId, GameMode, PlayerId, ShipId};
+use crate::mcts::MCTSAction;
 
 /// Event metadata for event sourcing
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +40,12 @@ pub enum DomainEvent {
         ai_player_id: PlayerId,
         num_berths: usize,
         num_cranes: usize,
+        #[serde(default)]
+        crane_speeds: Vec<f64>, // One per crane, empty in replays that predate it
+        #[serde(default)]
+        mode: GameMode,
+        #[serde(default)]
+        extra_players: Vec<PlayerId>, // Seats added with `with_extra_port`
     },
 
     TurnStarted {
@@ -65,6 +73,20 @@ pub enum DomainEvent {
         ship_id: ShipId,
         container_count: u32,
         arrival_time: f64,
+        #[serde(default)]
+        priority: bool,
+        #[serde(default)]
+        cargo: Option<CargoMix>, // None = all standard containers
+        #[serde(default)]
+        player: Option<PlayerId>, // Port the ship arrives at, None = both (mirror match)
+    },
+
+    // Arrival refused because a harbor was full
+    ArrivalRejected {
+        metadata: EventMetadata,
+        ship_id: ShipId,
+        container_count: u32,
+        player: PlayerId, // Port whose harbor was at capacity
     },
 
     ShipDocked {
@@ -79,6 +101,7 @@ pub enum DomainEvent {
         metadata: EventMetadata,
         ship_id: ShipId,
         berth_id: BerthId,
+        player: PlayerId,
         completion_time: f64,
         containers_processed: u32,
     },
@@ -97,13 +120,72 @@ pub enum DomainEvent {
         crane_id: CraneId,
         ship_id: ShipId,
         unassignment_time: f64,
+        #[serde(default)]
+        player: Option<PlayerId>, // None in replays recorded before the field existed
     },
 
     ContainerProcessed {
         metadata: EventMetadata,
         crane_id: CraneId,
         ship_id: ShipId,
+        player: PlayerId,
         containers_remaining: u32,
+        #[serde(default)]
+        container_type: ContainerType,
+    },
+
+    // Player spent an action on purpose without touching the port
+    PlayerPassed {
+        metadata: EventMetadata,
+        player: PlayerId,
+        pass_time: f64,
+    },
+
+    // One-turn extra crane put to work on a docked ship
+    EmergencyCraneDeployed {
+        metadata: EventMetadata,
+        crane_id: CraneId,
+        ship_id: ShipId,
+        player: PlayerId,
+        processing_speed: f64,
+    },
+
+    // Emergency crane sent back at the end of its turn
+    EmergencyCraneRemoved {
+        metadata: EventMetadata,
+        crane_id: CraneId,
+        player: PlayerId,
+    },
+
+    // Waiting penalty moved into a port's score at a turn start
+    WaitingPenaltyCharged {
+        metadata: EventMetadata,
+        player: PlayerId,
+        amount: i32,
+        charged_until: f64, // Waiting up to this time is paid for
+    },
+
+    // Cranes left free while docked ships could have used them
+    IdleCranesCharged {
+        metadata: EventMetadata,
+        player: PlayerId,
+        idle_cranes: u32,
+        amount: i32,
+    },
+
+    // Crane wear after a turn's work: working cranes tire, idle ones rest
+    CraneWearChanged {
+        metadata: EventMetadata,
+        player: PlayerId,
+        wear: Vec<(CraneId, f64)>, // New wear of each crane whose wear moved
+    },
+
+    // Random events (storms, rush hour, ...)
+    RandomEventTriggered {
+        metadata: EventMetadata,
+        description: String,
+        #[serde(default)]
+        player: Option<PlayerId>, // Port hit by the event, None = both
     },
 
     // MCTS AI events (for transparency)
@@ -130,16 +212,166 @@ impl DomainEvent {
             DomainEvent::TurnEnded { metadata, .. } => metadata,
             DomainEvent::GameEnded { metadata, .. } => metadata,
             DomainEvent::ShipArrived { metadata, .. } => metadata,
+            DomainEvent::ArrivalRejected { metadata, .. } => metadata,
             DomainEvent::ShipDocked { metadata, .. } => metadata,
             DomainEvent::ShipUndocked { metadata, .. } => metadata,
             DomainEvent::CraneAssigned { metadata, .. } => metadata,
             DomainEvent::CraneUnassigned { metadata, .. } => metadata,
             DomainEvent::ContainerProcessed { metadata, .. } => metadata,
+            DomainEvent::PlayerPassed { metadata, .. } => metadata,
+            DomainEvent::EmergencyCraneDeployed { metadata, .. } => metadata,
+            DomainEvent::EmergencyCraneRemoved { metadata, .. } => metadata,
+            DomainEvent::WaitingPenaltyCharged { metadata, .. } => metadata,
+            DomainEvent::IdleCranesCharged { metadata, .. } => metadata,
+            DomainEvent::CraneWearChanged { metadata, .. } => metadata,
+            DomainEvent::RandomEventTriggered { metadata, .. } => metadata,
             DomainEvent::MCTSSearchStarted { metadata, .. } => metadata,
             DomainEvent::MCTSSearchCompleted { metadata, .. } => metadata,
         }
     }
 
+    /// Whether the event changes the port of `player_id`. Arrivals and random
+    /// events without a player go to every port; session-level events concern
+    /// no port.
+    pub fn concerns_port(&self, player_id: PlayerId) -> bool {
+        match self {
+            DomainEvent::ShipArrived { player, .. }
+            | DomainEvent::RandomEventTriggered { player, .. } => {
+                player.is_none_or(|p| p == player_id)
+            }
+            DomainEvent::CraneUnassigned { player, .. } => *player == Some(player_id),
+            DomainEvent::ArrivalRejected { player, .. }
+            | DomainEvent::ShipDocked { player, .. }
+            | DomainEvent::ShipUndocked { player, .. }
+            | DomainEvent::CraneAssigned { player, .. }
+            | DomainEvent::ContainerProcessed { player, .. }
+            | DomainEvent::PlayerPassed { player, .. }
+            | DomainEvent::EmergencyCraneDeployed { player, .. }
+            | DomainEvent::EmergencyCraneRemoved { player, .. }
+            | DomainEvent::WaitingPenaltyCharged { player, .. }
+            | DomainEvent::IdleCranesCharged { player, .. }
+            | DomainEvent::CraneWearChanged { player, .. }
+            | DomainEvent::MCTSSearchStarted { player, .. }
+            | DomainEvent::MCTSSearchCompleted { player, .. } => *player == player_id,
+            _ => false,
+        }
+    }
+
+    /// One-line human-readable account of the event, for timelines
+    pub fn describe(&self) -> String {
+        match self {
+            DomainEvent::GameStarted {
+                num_berths,
+                num_cranes,
+                ..
+            } => format!(
+                "Game started with {} berths and {} cranes per port",
+                num_berths, num_cranes
+            ),
+            DomainEvent::TurnStarted { turn_number, .. } => {
+                format!("Turn {} started", turn_number)
+            }
+            DomainEvent::TurnEnded { turn_number, .. } => format!("Turn {} ended", turn_number),
+            DomainEvent::GameEnded {
+                winner,
+                player_score,
+                ai_score,
+                ..
+            } => format!(
+                "Game over ({}): player {} vs AI {}",
+                if winner.is_some() { "won" } else { "tie" },
+                player_score,
+                ai_score
+            ),
+            DomainEvent::ShipArrived {
+                ship_id,
+                container_count,
+                priority,
+                ..
+            } => format!(
+                "Ship #{} arrived with {} containers{}",
+                ship_id.0,
+                container_count,
+                if *priority { " (priority)" } else { "" }
+            ),
+            DomainEvent::ArrivalRejected {
+                ship_id,
+                container_count,
+                ..
+            } => format!(
+                "Ship #{} with {} containers turned away, harbor full",
+                ship_id.0, container_count
+            ),
+            DomainEvent::ShipDocked {
+                ship_id, berth_id, ..
+            } => format!("Ship #{} docked at Berth #{}", ship_id.0, berth_id.0),
+            DomainEvent::ShipUndocked {
+                ship_id,
+                berth_id,
+                containers_processed,
+                ..
+            } => format!(
+                "Ship #{} left Berth #{} after unloading {} containers",
+                ship_id.0, berth_id.0, containers_processed
+            ),
+            DomainEvent::CraneAssigned {
+                crane_id, ship_id, ..
+            } => format!("Crane #{} assigned to Ship #{}", crane_id.0, ship_id.0),
+            DomainEvent::CraneUnassigned {
+                crane_id, ship_id, ..
+            } => format!("Crane #{} released from Ship #{}", crane_id.0, ship_id.0),
+            DomainEvent::ContainerProcessed {
+                crane_id,
+                ship_id,
+                containers_remaining,
+                ..
+            } => format!(
+                "Crane #{} unloaded Ship #{}, {} containers left",
+                crane_id.0, ship_id.0, containers_remaining
+            ),
+            DomainEvent::PlayerPassed { .. } => "Player passed an action".to_string(),
+            DomainEvent::EmergencyCraneDeployed {
+                crane_id, ship_id, ..
+            } => format!(
+                "Emergency Crane #{} deployed on Ship #{}",
+                crane_id.0, ship_id.0
+            ),
+            DomainEvent::EmergencyCraneRemoved { crane_id, .. } => {
+                format!("Emergency Crane #{} withdrawn", crane_id.0)
+            }
+            DomainEvent::WaitingPenaltyCharged { amount, .. } => {
+                format!("Waiting ships cost {} points", amount)
+            }
+            DomainEvent::IdleCranesCharged {
+                idle_cranes,
+                amount,
+                ..
+            } => format!("{} idle cranes cost {} points", idle_cranes, amount),
+            DomainEvent::CraneWearChanged { wear, .. } => {
+                format!("Wear changed on {} cranes", wear.len())
+            }
+            DomainEvent::RandomEventTriggered { description, .. } => description.clone(),
+            DomainEvent::MCTSSearchStarted {
+                num_simulations, ..
+            } => format!("AI started a search of {} simulations", num_simulations),
+            DomainEvent::MCTSSearchCompleted {
+                best_action,
+                confidence,
+                ..
+            } => {
+                // best_action holds the action as JSON; fall back to the raw text
+                let action = serde_json::from_str::<MCTSAction>(best_action)
+                    .map(|action| action.to_string())
+                    .unwrap_or_else(|_| best_action.clone());
+                format!(
+                    "AI chose {} ({:.0}% confidence)",
+                    action,
+                    confidence * 100.0
+                )
+            }
+        }
+    }
+
     pub fn event_type(&self) -> &str {
         match self {
             DomainEvent::GameStarted { .. } => "GameStarted",
@@ -147,11 +379,19 @@ impl DomainEvent {
             DomainEvent::TurnEnded { .. } => "TurnEnded",
             DomainEvent::GameEnded { .. } => "GameEnded",
             DomainEvent::ShipArrived { .. } => "ShipArrived",
+            DomainEvent::ArrivalRejected { .. } => "ArrivalRejected",
             DomainEvent::ShipDocked { .. } => "ShipDocked",
             DomainEvent::ShipUndocked { .. } => "ShipUndocked",
             DomainEvent::CraneAssigned { .. } => "CraneAssigned",
             DomainEvent::CraneUnassigned { .. } => "CraneUnassigned",
             DomainEvent::ContainerProcessed { .. } => "ContainerProcessed",
+            DomainEvent::PlayerPassed { .. } => "PlayerPassed",
+            DomainEvent::EmergencyCraneDeployed { .. } => "EmergencyCraneDeployed",
+            DomainEvent::EmergencyCraneRemoved { .. } => "EmergencyCraneRemoved",
+            DomainEvent::WaitingPenaltyCharged { .. } => "WaitingPenaltyCharged",
+            DomainEvent::IdleCranesCharged { .. } => "IdleCranesCharged",
+            DomainEvent::CraneWearChanged { .. } => "CraneWearChanged",
+            DomainEvent::RandomEventTriggered { .. } => "RandomEventTriggered",
             DomainEvent::MCTSSearchStarted { .. } => "MCTSSearchStarted",
             DomainEvent::MCTSSearchCompleted { .. } => "MCTSSearchCompleted",
         }
@@ -180,6 +420,9 @@ mod tests {
             ship_id: ShipId::new(1),
             container_count: 50,
             arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
         };
 
         let json = serde_json::to_string(&event).unwrap();
@@ -187,4 +430,219 @@ mod tests {
 
         assert_eq!(event.event_type(), deserialized.event_type());
     }
+
+    #[test]
+    fn test_every_event_describes_its_ids() {
+        let metadata = EventMetadata::new(Uuid::new_v4(), 1);
+        let player = PlayerId::new();
+        let (ship_id, berth_id, crane_id) = (ShipId::new(3), BerthId::new(1), CraneId::new(2));
+        let cases = vec![
+            (
+                DomainEvent::GameStarted {
+                    metadata: metadata.clone(),
+                    player_id: player,
+                    ai_player_id: PlayerId::new(),
+                    num_berths: 4,
+                    num_cranes: 5,
+                    crane_speeds: vec![2.0; 5],
+                    mode: GameMode::VersusAI,
+                    extra_players: Vec::new(),
+                },
+                vec!["4 berths", "5 cranes"],
+            ),
+            (
+                DomainEvent::TurnStarted {
+                    metadata: metadata.clone(),
+                    turn_number: 7,
+                    current_player: player,
+                },
+                vec!["Turn 7"],
+            ),
+            (
+                DomainEvent::TurnEnded {
+                    metadata: metadata.clone(),
+                    turn_number: 7,
+                    player,
+                },
+                vec!["Turn 7"],
+            ),
+            (
+                DomainEvent::GameEnded {
+                    metadata: metadata.clone(),
+                    winner: Some(player),
+                    player_score: 120,
+                    ai_score: 95,
+                },
+                vec!["120", "95"],
+            ),
+            (
+                DomainEvent::ShipArrived {
+                    metadata: metadata.clone(),
+                    ship_id,
+                    container_count: 40,
+                    arrival_time: 0.0,
+                    priority: true,
+                    cargo: None,
+                    player: None,
+                },
+                vec!["Ship #3", "40"],
+            ),
+            (
+                DomainEvent::ArrivalRejected {
+                    metadata: metadata.clone(),
+                    ship_id,
+                    container_count: 40,
+                    player,
+                },
+                vec!["Ship #3", "40"],
+            ),
+            (
+                DomainEvent::ShipDocked {
+                    metadata: metadata.clone(),
+                    ship_id,
+                    berth_id,
+                    player,
+                    docking_time: 1.0,
+                },
+                vec!["Ship #3", "Berth #1"],
+            ),
+            (
+                DomainEvent::ShipUndocked {
+                    metadata: metadata.clone(),
+                    ship_id,
+                    berth_id,
+                    player,
+                    completion_time: 2.0,
+                    containers_processed: 40,
+                },
+                vec!["Ship #3", "Berth #1", "40"],
+            ),
+            (
+                DomainEvent::CraneAssigned {
+                    metadata: metadata.clone(),
+                    crane_id,
+                    ship_id,
+                    player,
+                    assignment_time: 1.0,
+                },
+                vec!["Crane #2", "Ship #3"],
+            ),
+            (
+                DomainEvent::CraneUnassigned {
+                    metadata: metadata.clone(),
+                    crane_id,
+                    ship_id,
+                    unassignment_time: 2.0,
+                    player: Some(player),
+                },
+                vec!["Crane #2", "Ship #3"],
+            ),
+            (
+                DomainEvent::ContainerProcessed {
+                    metadata: metadata.clone(),
+                    crane_id,
+                    ship_id,
+                    player,
+                    containers_remaining: 12,
+                    container_type: ContainerType::default(),
+                },
+                vec!["Crane #2", "Ship #3", "12"],
+            ),
+            (
+                DomainEvent::PlayerPassed {
+                    metadata: metadata.clone(),
+                    player,
+                    pass_time: 1.0,
+                },
+                vec!["passed"],
+            ),
+            (
+                DomainEvent::EmergencyCraneDeployed {
+                    metadata: metadata.clone(),
+                    crane_id,
+                    ship_id,
+                    player,
+                    processing_speed: 2.0,
+                },
+                vec!["Emergency Crane #2", "Ship #3"],
+            ),
+            (
+                DomainEvent::EmergencyCraneRemoved {
+                    metadata: metadata.clone(),
+                    crane_id,
+                    player,
+                },
+                vec!["Emergency Crane #2", "withdrawn"],
+            ),
+            (
+                DomainEvent::WaitingPenaltyCharged {
+                    metadata: metadata.clone(),
+                    player,
+                    amount: 15,
+                    charged_until: 3.0,
+                },
+                vec!["15"],
+            ),
+            (
+                DomainEvent::IdleCranesCharged {
+                    metadata: metadata.clone(),
+                    player,
+                    idle_cranes: 2,
+                    amount: 4,
+                },
+                vec!["2 idle cranes", "4"],
+            ),
+            (
+                DomainEvent::CraneWearChanged {
+                    metadata: metadata.clone(),
+                    player,
+                    wear: vec![(CraneId::new(0), 0.1), (CraneId::new(1), 0.0)],
+                },
+                vec!["2 cranes"],
+            ),
+            (
+                DomainEvent::RandomEventTriggered {
+                    metadata: metadata.clone(),
+                    description: "Storm".to_string(),
+                    player: None,
+                },
+                vec!["Storm"],
+            ),
+            (
+                DomainEvent::MCTSSearchStarted {
+                    metadata: metadata.clone(),
+                    player,
+                    num_simulations: 100,
+                },
+                vec!["100"],
+            ),
+            (
+                DomainEvent::MCTSSearchCompleted {
+                    metadata,
+                    player,
+                    best_action: serde_json::to_string(&MCTSAction::DockShip {
+                        ship_id: ShipId::new(3),
+                        berth_id: BerthId::new(1),
+                    })
+                    .unwrap(),
+                    confidence: 0.5,
+                    simulations_performed: 100,
+                },
+                vec!["AI chose Dock Ship#3 at Berth#1 (50% confidence)"],
+            ),
+        ];
+
+        for (event, expected) in cases {
+            let description = event.describe();
+            for part in expected {
+                assert!(
+                    description.contains(part),
+                    "{}: {:?} lacks {:?}",
+                    event.event_type(),
+                    description,
+                    part
+                );
+            }
+        }
+    }
 }
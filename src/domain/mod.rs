@@ -3,8 +3,10 @@
 pub mod aggregates;
 pub mod entities;
 pub mod events;
+pub mod scoring;
 pub mod value_objects;
 
 pub use aggregates::Port;
 pub use entities::{Berth, Crane, Ship};
+pub use scoring::{DefaultScoring, ScoringStrategy};
 pub use value_objects::{BerthId, CraneId, PlayerId, ShipId};
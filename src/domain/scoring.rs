@@ -0,0 +1,19 @@
+// Scoring strategies - How a port's performance is turned into a score
+
+use super::aggregates::Port;
+
+/// Objective used to score a port (game result, read models and MCTS rollouts)
+pub trait ScoringStrategy: Send + Sync {
+    fn score(&self, port: &Port) -> i32;
+}
+
+/// Points per processed container minus the waiting penalty
+/// (same as `Port::calculate_score`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultScoring;
+
+impl ScoringStrategy for DefaultScoring {
+    fn score(&self, port: &Port) -> i32 {
+        port.calculate_score()
+    }
+}
@@ -6,7 +6,7 @@ use std::fmt;
 use uuid::Uuid;
 
 /// Type-safe ship identifier
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ShipId(pub usize);
 
 impl ShipId {
@@ -22,7 +22,7 @@ impl fmt::Display for ShipId {
 }
 
 /// Type-safe berth identifier
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct BerthId(pub usize);
 
 impl BerthId {
@@ -38,7 +38,7 @@ impl fmt::Display for BerthId {
 }
 
 /// Type-safe crane identifier
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct CraneId(pub usize);
 
 impl CraneId {
@@ -79,10 +79,112 @@ impl fmt::Display for PlayerId {
     }
 }
 
+/// Tide level: large ships can only dock at high tide
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Tide {
+    #[default]
+    High,
+    Low,
+}
+
+impl Tide {
+    /// High tide comes back every `CYCLE_TURNS` turns (turn 0 included)
+    pub const CYCLE_TURNS: u32 = 3;
+
+    pub fn for_turn(turn: u32) -> Self {
+        if turn.is_multiple_of(Self::CYCLE_TURNS) {
+            Tide::High
+        } else {
+            Tide::Low
+        }
+    }
+}
+
+impl fmt::Display for Tide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tide::High => write!(f, "High"),
+            Tide::Low => write!(f, "Low"),
+        }
+    }
+}
+
+/// Game mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    VersusAI, // Player vs AI MCTS
+    Tutorial, // Learning mode
+    Sandbox,  // Free play
+    Coop,     // Player and AI share the player port and chase a target score
+}
+
+/// Order in which docked ships are unloaded, and their events recorded, each turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ProcessingPolicy {
+    #[default]
+    Fifo, // Earliest arrival first
+    MostFull, // Most containers remaining first
+    Fair,     // Round-robin: one batch of each ship in turn
+}
+
+/// Kind of container carried by a ship
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ContainerType {
+    #[default]
+    Standard,
+    Reefer, // Refrigerated, needs power hook-up
+    Hazmat, // Dangerous goods, slow handling
+}
+
+impl ContainerType {
+    /// Handling order used when unloading a ship
+    pub const ALL: [ContainerType; 3] = [
+        ContainerType::Standard,
+        ContainerType::Reefer,
+        ContainerType::Hazmat,
+    ];
+
+    /// Handling time relative to a standard container
+    pub fn handling_time(&self) -> f64 {
+        match self {
+            ContainerType::Standard => 1.0,
+            ContainerType::Reefer => 1.25,
+            ContainerType::Hazmat => 2.0,
+        }
+    }
+}
+
+impl fmt::Display for ContainerType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerType::Standard => write!(f, "Standard"),
+            ContainerType::Reefer => write!(f, "Reefer"),
+            ContainerType::Hazmat => write!(f, "Hazmat"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_tide_cycle() {
+        let tides: Vec<_> = (0..6).map(Tide::for_turn).collect();
+        assert_eq!(
+            tides,
+            vec![
+                Tide::High,
+                Tide::Low,
+                Tide::Low,
+                Tide::High,
+                Tide::Low,
+                Tide::Low
+            ]
+        );
+    }
+
     #[test]
     fn test_ship_id_creation() {
         let ship_id = ShipId::new(42);
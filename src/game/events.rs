@@ -1,11 +1,17 @@
 // Random game events system
 // Adds unpredictability and challenge to the game
 
+use std::cell::RefCell;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
 use crate::domain::value_objects::CraneId;
 use crate::utils::random;
 
 /// Random events that can occur during gameplay
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum RandomEvent {
     /// Storm reduces crane efficiency
     Storm {
@@ -77,48 +83,108 @@ impl RandomEvent {
     }
 }
 
+/// Kinds of random events `generate` picks from, equally likely
+const EVENT_KINDS: usize = 5;
+/// Kind number of `RandomEvent::RushHour`
+const RUSH_HOUR: usize = 3;
+
 /// Event generator with configurable probability
 pub struct EventGenerator {
-    probability: f64, // 0.0 to 1.0
+    probability: f64,             // 0.0 to 1.0
+    rng: Option<RefCell<StdRng>>, // None = global `random` module
 }
 
 impl EventGenerator {
     pub fn new(probability: f64) -> Self {
         Self {
             probability: probability.clamp(0.0, 1.0),
+            rng: None,
+        }
+    }
+
+    /// Generator with its own seeded RNG: the event sequence only depends
+    /// on `seed`, whatever else draws random numbers meanwhile
+    pub fn with_seed(probability: f64, seed: u64) -> Self {
+        Self {
+            rng: Some(RefCell::new(StdRng::seed_from_u64(seed))),
+            ..Self::new(probability)
         }
     }
 
     /// Generate a random event (or None)
     pub fn generate(&self) -> Option<RandomEvent> {
+        self.draw(|| self.roll())
+    }
+
+    /// Generate an event drawing every random value from `rng`
+    pub fn generate_with_rng(&self, rng: &mut StdRng) -> Option<RandomEvent> {
+        random::with_rng(rng, || self.roll())
+    }
+
+    /// Harbor-wide event (rush hour), rolled once per turn for all ports
+    /// with the share rush hour has in `generate`
+    pub fn generate_harbor_event(&self) -> Option<RandomEvent> {
+        self.draw(|| {
+            let share = 1.0 / EVENT_KINDS as f64;
+            random::hit(self.probability * share).then(|| Self::event_of_kind(RUSH_HOUR))
+        })
+    }
+
+    /// Event hitting a single port (weather, breakdown, inspection), rolled
+    /// once per port and turn with the full probability
+    pub fn generate_port_event(&self) -> Option<RandomEvent> {
+        self.draw(|| {
+            if !random::hit(self.probability) {
+                return None;
+            }
+            // Every kind but rush hour
+            let kind = random::range_usize(0, EVENT_KINDS - 1);
+            Some(Self::event_of_kind(if kind >= RUSH_HOUR {
+                kind + 1
+            } else {
+                kind
+            }))
+        })
+    }
+
+    /// Run `roll` on this generator's own RNG when it has one
+    fn draw(&self, roll: impl FnOnce() -> Option<RandomEvent>) -> Option<RandomEvent> {
+        match &self.rng {
+            Some(rng) => random::with_rng(&mut rng.borrow_mut(), roll),
+            None => roll(),
+        }
+    }
+
+    fn roll(&self) -> Option<RandomEvent> {
         // Check if event should occur
         if !random::hit(self.probability) {
             return None;
         }
 
         // Choose event type
-        let event_type = random::range_usize(0, 5);
+        Some(Self::event_of_kind(random::range_usize(0, EVENT_KINDS)))
+    }
 
-        match event_type {
-            0 => Some(RandomEvent::Storm {
+    fn event_of_kind(kind: usize) -> RandomEvent {
+        match kind {
+            0 => RandomEvent::Storm {
                 duration_turns: random::range_u32_inclusive(1, 3),
                 efficiency_penalty: random::range_f64_inclusive(0.3, 0.6),
-            }),
-            1 => Some(RandomEvent::CraneBreakdown {
+            },
+            1 => RandomEvent::CraneBreakdown {
                 crane_id: CraneId::new(random::range_usize(0, 2)), // Assume 2 cranes
                 duration_turns: random::range_u32_inclusive(1, 2),
-            }),
-            2 => Some(RandomEvent::CustomsInspection {
+            },
+            2 => RandomEvent::CustomsInspection {
                 delay_turns: random::range_u32_inclusive(1, 2),
-            }),
-            3 => Some(RandomEvent::RushHour {
+            },
+            RUSH_HOUR => RandomEvent::RushHour {
                 extra_ships: random::range_usize_inclusive(1, 3),
-            }),
-            4 => Some(RandomEvent::GoodWeather {
+            },
+            _ => RandomEvent::GoodWeather {
                 duration_turns: random::range_u32_inclusive(1, 2),
                 efficiency_bonus: random::range_f64_inclusive(0.2, 0.4),
-            }),
-            _ => None,
+            },
         }
     }
 }
@@ -130,7 +196,7 @@ impl Default for EventGenerator {
 }
 
 /// Active event tracker
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActiveEvent {
     pub event: RandomEvent,
     pub turns_remaining: u32,
@@ -186,6 +252,36 @@ mod tests {
         assert!(event.is_some());
     }
 
+    #[test]
+    fn test_seeded_generator_produces_known_breakdown() {
+        let expected = RandomEvent::CraneBreakdown {
+            crane_id: CraneId::new(1),
+            duration_turns: 2,
+        };
+
+        let generator = EventGenerator::with_seed(1.0, 13);
+        assert_eq!(generator.generate(), Some(expected.clone()));
+
+        let mut rng = StdRng::seed_from_u64(13);
+        assert_eq!(
+            EventGenerator::new(1.0).generate_with_rng(&mut rng),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn test_harbor_and_port_events_split_the_kinds() {
+        let generator = EventGenerator::with_seed(1.0, 5);
+        for _ in 0..200 {
+            if let Some(event) = generator.generate_harbor_event() {
+                assert!(matches!(event, RandomEvent::RushHour { .. }));
+            }
+            let event = generator.generate_port_event();
+            assert!(event.is_some());
+            assert!(!matches!(event, Some(RandomEvent::RushHour { .. })));
+        }
+    }
+
     #[test]
     fn test_active_event_tick() {
         let event = RandomEvent::Storm {
@@ -0,0 +1,129 @@
+// Turn history - Human-readable summaries rebuilt from the event stream
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::aggregates::Port;
+use crate::domain::events::DomainEvent;
+use crate::domain::value_objects::PlayerId;
+
+/// What happened during one turn
+/// Score deltas only count container points (waiting penalties excluded)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnSummary {
+    pub turn: u32,
+    pub player_actions: Vec<String>,
+    pub containers_processed: u32,
+    pub ai_containers_processed: u32,
+    pub score_delta: i32,
+    pub random_events: Vec<String>,
+}
+
+impl TurnSummary {
+    fn new(turn: u32) -> Self {
+        Self {
+            turn,
+            player_actions: Vec::new(),
+            containers_processed: 0,
+            ai_containers_processed: 0,
+            score_delta: 0,
+            random_events: Vec::new(),
+        }
+    }
+}
+
+/// Group events between `TurnStarted` markers (turn 0 = before the first one)
+pub fn turn_log(
+    events: &[DomainEvent],
+    player_id: PlayerId,
+    ai_id: PlayerId,
+    points_per_container: u32,
+) -> Vec<TurnSummary> {
+    // Shadow ports replay arrivals and processing to measure each batch
+    let mut player_shadow = Port::new(player_id, 0, 0);
+    let mut ai_shadow = Port::new(ai_id, 0, 0);
+    player_shadow.points_per_container = points_per_container;
+    ai_shadow.points_per_container = points_per_container;
+    let mut summaries = vec![TurnSummary::new(0)];
+
+    for event in events {
+        let summary = summaries.last_mut().expect("turn 0 summary always exists");
+
+        match event {
+            DomainEvent::TurnStarted { turn_number, .. } => {
+                summaries.push(TurnSummary::new(*turn_number));
+            }
+            DomainEvent::ShipArrived { .. } => {
+                for shadow in [&mut player_shadow, &mut ai_shadow] {
+                    if event.concerns_port(shadow.player_id) {
+                        shadow.apply_event(event);
+                    }
+                }
+            }
+            DomainEvent::ShipDocked {
+                ship_id,
+                berth_id,
+                player,
+                ..
+            } if *player == player_id => {
+                summary
+                    .player_actions
+                    .push(format!("Docked {} at {}", ship_id, berth_id));
+            }
+            DomainEvent::CraneAssigned {
+                crane_id,
+                ship_id,
+                player,
+                ..
+            } if *player == player_id => {
+                summary
+                    .player_actions
+                    .push(format!("Assigned {} to {}", crane_id, ship_id));
+            }
+            DomainEvent::EmergencyCraneDeployed {
+                crane_id,
+                ship_id,
+                player,
+                ..
+            } if *player == player_id => {
+                summary
+                    .player_actions
+                    .push(format!("Deployed emergency {} on {}", crane_id, ship_id));
+            }
+            DomainEvent::ContainerProcessed {
+                ship_id, player, ..
+            } => {
+                let is_player = *player == player_id;
+                let shadow = if is_player {
+                    &mut player_shadow
+                } else {
+                    &mut ai_shadow
+                };
+                let remaining = |port: &Port| {
+                    port.ships
+                        .get(ship_id)
+                        .map_or(0, |ship| ship.containers_remaining)
+                };
+                let (before, score_before) = (remaining(shadow), shadow.score);
+                shadow.apply_event(event);
+                let processed = before - remaining(shadow);
+
+                if is_player {
+                    summary.containers_processed += processed;
+                    summary.score_delta += shadow.score - score_before;
+                } else {
+                    summary.ai_containers_processed += processed;
+                }
+            }
+            DomainEvent::RandomEventTriggered {
+                description,
+                player,
+                ..
+            } if player.is_none_or(|player| player == player_id) => {
+                summary.random_events.push(description.clone());
+            }
+            _ => {}
+        }
+    }
+
+    summaries
+}
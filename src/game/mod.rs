@@ -1,76 +1,479 @@
 // Game orchestration layer - High-level game logic
 
 pub mod events;
+pub mod history;
+pub mod save;
+pub mod shared;
+pub mod sim;
+pub mod snapshot;
+pub mod tournament;
 
+use std::collections::HashMap;
+use std::hash::Hasher;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::application::handlers::{handle_assign_crane_command, handle_dock_ship_command};
-use crate::domain::aggregates::Port;
+use crate::application::commands::Command;
+use crate::application::handlers::{
+    count_ships_completed, handle_assign_crane_command, handle_assign_cranes_command,
+    handle_dock_ship_command, handle_pass_command, handle_process_containers_with_policy,
+    handle_reassign_crane_command, handle_unassign_crane_command,
+    query_comparison_stats_with_scoring, query_port_state_with_scoring, validate_assign_crane,
+    validate_dock_ship, validate_reassign_crane, validate_unassign_crane,
+};
+use crate::application::queries::{ComparisonStats, PortStateView};
+use crate::domain::aggregates::{
+    LayoutConstraints, Port, DEFAULT_CONTAINERS_PER_CRANE, DEFAULT_CRANE_SPEED,
+    DEFAULT_IDLE_CRANE_PENALTY, DEFAULT_POINTS_PER_CONTAINER,
+};
+use crate::domain::entities::CargoMix;
 use crate::domain::events::{DomainEvent, EventMetadata};
-use crate::domain::value_objects::{BerthId, CraneId, PlayerId, ShipId};
-use crate::infrastructure::{EventStore, InMemoryEventStore};
-use crate::mcts::{MCTSConfig, MCTSEngine};
+use crate::domain::scoring::{DefaultScoring, ScoringStrategy};
+use crate::domain::value_objects::{BerthId, CraneId, PlayerId, ProcessingPolicy, ShipId, Tide};
+use crate::infrastructure::{EventStore, InMemoryEventStore, ReplayEnvelope};
+use crate::mcts::{
+    ExplorationSchedule, MCTSAction, MCTSConfig, MCTSEngine, MCTSTree, RewardConfig,
+};
+use crate::utils::hash::Fnv1a;
+use crate::utils::random;
 
+pub use crate::domain::value_objects::GameMode;
 pub use events::{ActiveEvent, EventGenerator, RandomEvent};
+pub use history::TurnSummary;
+pub use save::{SaveGame, AUTOSAVE_FILE};
+pub use shared::SharedSession;
+pub use sim::{run_ai_vs_ai, run_match, SimulationResult, SimulationRules};
+pub use snapshot::GameSnapshot;
+pub use tournament::{Standing, Tournament, TournamentResult};
+
+/// Chance that a spawned ship carries priority (perishable) cargo
+const PRIORITY_SHIP_PROBABILITY: f64 = 0.2;
+
+/// Default share of reefer containers on spawned ships
+pub const DEFAULT_REEFER_PERCENT: u32 = 20;
+
+/// Default share of hazmat containers on spawned ships
+pub const DEFAULT_HAZMAT_PERCENT: u32 = 10;
+
+/// How the container count of a new ship is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ShipSizeDistribution {
+    /// Any size in `min_containers..=max_containers`, equally likely
+    #[default]
+    Uniform,
+    /// Mostly `Uniform` ships, but `percent` of the arrivals are mega ships
+    /// sized uniformly in `min..=max`
+    MegaShips { percent: u32, min: u32, max: u32 },
+}
+
+/// Ship spawning parameters: container counts are drawn from `distribution`,
+/// regular ships in `min_containers..=max_containers`, then split by type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpawnConfig {
+    pub min_containers: u32,
+    pub max_containers: u32,
+    pub distribution: ShipSizeDistribution,
+    #[serde(default = "default_reefer_percent")]
+    pub reefer_percent: u32, // Share of each ship's containers that are reefers
+    #[serde(default = "default_hazmat_percent")]
+    pub hazmat_percent: u32, // Share of each ship's containers that are hazmat
+}
+
+fn default_reefer_percent() -> u32 {
+    DEFAULT_REEFER_PERCENT
+}
+
+fn default_hazmat_percent() -> u32 {
+    DEFAULT_HAZMAT_PERCENT
+}
+
+impl SpawnConfig {
+    /// Check that every size range is ordered and within
+    /// `1..=MAX_SHIP_CONTAINERS`, so each ship can arrive and be completed
+    pub fn validate(&self) -> Result<(), String> {
+        check_size_range("Ship", self.min_containers, self.max_containers)?;
+        if let ShipSizeDistribution::MegaShips { percent, min, max } = self.distribution {
+            if percent > 100 {
+                return Err(format!(
+                    "Mega ship share must be at most 100%, got {}%",
+                    percent
+                ));
+            }
+            check_size_range("Mega ship", min, max)?;
+        }
+        if self.reefer_percent + self.hazmat_percent > 100 {
+            return Err(format!(
+                "Reefer and hazmat shares must add up to at most 100%, got {}% + {}%",
+                self.reefer_percent, self.hazmat_percent
+            ));
+        }
+        Ok(())
+    }
+
+    /// Split a ship's containers by type following the configured shares
+    /// (rounded down, standard containers take the rest)
+    pub fn cargo_mix(&self, containers: u32) -> CargoMix {
+        let reefer = containers * self.reefer_percent / 100;
+        let hazmat = containers * self.hazmat_percent / 100;
+        CargoMix::new(containers - reefer - hazmat, reefer, hazmat)
+    }
+
+    /// Container count of the next ship, drawn from the shared RNG
+    pub fn sample_containers(&self) -> u32 {
+        match self.distribution {
+            ShipSizeDistribution::MegaShips { percent, min, max }
+                if random::hit(percent as f64 / 100.0) =>
+            {
+                random::range_u32_inclusive(min, max)
+            }
+            _ => random::range_u32_inclusive(self.min_containers, self.max_containers),
+        }
+    }
+}
+
+fn check_size_range(kind: &str, min: u32, max: u32) -> Result<(), String> {
+    if min > max {
+        return Err(format!(
+            "{} sizes: minimum {} is above maximum {}",
+            kind, min, max
+        ));
+    }
+    if min == 0 || max > MAX_SHIP_CONTAINERS {
+        return Err(format!(
+            "{} sizes must be within 1..={} containers, got {}..={}",
+            kind, MAX_SHIP_CONTAINERS, min, max
+        ));
+    }
+    Ok(())
+}
+
+impl Default for SpawnConfig {
+    fn default() -> Self {
+        Self {
+            min_containers: 20,
+            max_containers: 50,
+            distribution: ShipSizeDistribution::Uniform,
+            reefer_percent: DEFAULT_REEFER_PERCENT,
+            hazmat_percent: DEFAULT_HAZMAT_PERCENT,
+        }
+    }
+}
+
+/// Largest ship a spawn configuration may produce
+pub const MAX_SHIP_CONTAINERS: u32 = 500;
+
+/// Score a port must exceed to win outright
+const WIN_SCORE: i32 = 1000;
+
+/// More waiting ships than this and the port loses
+const MAX_WAITING_SHIPS: usize = 10;
+
+/// Default maximum game length in turns
+pub const MAX_TURNS: u32 = 30;
+
+/// Default score the shared port must reach in co-op
+pub const DEFAULT_COOP_TARGET_SCORE: i32 = 500;
+
+/// Crane speeds handed out in turn to a session's cranes
+const CRANE_SPEEDS: [f64; 3] = [1.0, 2.0, 3.0];
+
+/// Default number of dock/assign/unassign actions allowed per turn
+pub const DEFAULT_ACTION_POINTS: u32 = 3;
+
+/// Default number of emergency cranes the player can deploy per game
+pub const DEFAULT_EMERGENCY_CRANES: u32 = 2;
+
+/// Default docking fee of each port's premium deep-water berth
+pub const DEFAULT_PREMIUM_BERTH_FEE: i32 = 20;
+
+/// Turns in a row without any container unloaded before a stuck game is called
+pub const STALEMATE_TURNS: u32 = 5;
+
+/// Session-wide rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRules {
+    pub max_turns: u32,
+    pub harbor_capacity: Option<usize>, // Max waiting ships per port, None = unbounded
+    pub containers_per_crane_per_turn: u32, // Base unloading rate, also used by MCTS rollouts
+    pub action_points_per_turn: u32,    // Dock/assign/unassign budget of each side
+    pub emergency_cranes: u32,          // One-turn extra cranes available to the player
+    pub points_per_container: u32,      // Score of each processed container
+    pub idle_crane_penalty: u32,        // Score lost per idle crane each turn
+    pub container_batch_size: Option<u32>, // One ContainerProcessed per batch, None = per ship
+    #[serde(default)]
+    pub processing_policy: ProcessingPolicy, // Order docked ships are unloaded in
+    #[serde(default = "default_coop_target_score")]
+    pub coop_target_score: i32, // Shared goal of a `GameMode::Coop` game
+    #[serde(default = "default_premium_berth_fee")]
+    pub premium_berth_fee: i32, // Paid to dock at a port's last berth, the others are free
+}
+
+fn default_coop_target_score() -> i32 {
+    DEFAULT_COOP_TARGET_SCORE
+}
+
+fn default_premium_berth_fee() -> i32 {
+    DEFAULT_PREMIUM_BERTH_FEE
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            max_turns: MAX_TURNS,
+            harbor_capacity: None,
+            containers_per_crane_per_turn: DEFAULT_CONTAINERS_PER_CRANE,
+            action_points_per_turn: DEFAULT_ACTION_POINTS,
+            emergency_cranes: DEFAULT_EMERGENCY_CRANES,
+            points_per_container: DEFAULT_POINTS_PER_CONTAINER,
+            idle_crane_penalty: DEFAULT_IDLE_CRANE_PENALTY,
+            container_batch_size: None,
+            processing_policy: ProcessingPolicy::Fifo,
+            coop_target_score: DEFAULT_COOP_TARGET_SCORE,
+            premium_berth_fee: DEFAULT_PREMIUM_BERTH_FEE,
+        }
+    }
+}
 
-/// Game mode
+/// Why the game ended
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum GameMode {
-    VersusAI, // Player vs AI MCTS
-    Tutorial, // Learning mode
-    Sandbox,  // Free play
+pub enum GameOverReason {
+    ScoreTarget,     // A port crossed the win score
+    WaitingOverflow, // A port let too many ships wait
+    TurnLimit,       // Maximum number of turns reached
+    Stalemate,       // Nothing unloaded for a while and no move can change that
+}
+
+/// Result of a finished game (winner None = tie)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameOutcome {
+    pub winner: Option<PlayerId>,
+    pub reason: GameOverReason,
+}
+
+/// Result of the game from the player's point of view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    PlayerWins,
+    AiWins,
+    Tie,
+    InProgress,
+}
+
+impl GameResult {
+    /// Label used by the JS frontend: "player", "ai", "tie" (None while playing)
+    pub fn winner_label(self) -> Option<&'static str> {
+        match self {
+            GameResult::PlayerWins => Some("player"),
+            GameResult::AiWins => Some("ai"),
+            GameResult::Tie => Some("tie"),
+            GameResult::InProgress => None,
+        }
+    }
 }
 
+/// Hook run once when a session's game ends (e.g. to export its replay)
+pub type GameOverCallback<E = InMemoryEventStore> = Box<dyn FnOnce(&GameSession<E>) + Send>;
+
 /// Game session - Main game state manager
-pub struct GameSession {
+/// Generic over the event store so other backends can be plugged in
+/// (defaults to the in-memory store).
+pub struct GameSession<E: EventStore = InMemoryEventStore> {
     pub session_id: Uuid,
     pub mode: GameMode,
     pub player_port: Port,
     pub ai_port: Port,
+    pub extra_ports: Vec<Port>, // Further seats (3-4 player tournaments), driven by commands
     pub current_turn: u32,
     pub current_player: PlayerId,
     pub mcts_engine: MCTSEngine,
-    pub event_store: InMemoryEventStore,
+    pub event_store: E,
     pub event_generator: EventGenerator,
-    pub active_events: Vec<ActiveEvent>,
-    pub crane_efficiency_modifier: f64, // 1.0 = normal, <1.0 = penalty, >1.0 = bonus
+    pub active_events: Vec<ActiveEvent>, // Effects on the player port
+    pub crane_efficiency_modifier: f64,  // Player port: 1.0 = normal, <1.0 = penalty, >1.0 = bonus
+    pub ai_active_events: Vec<ActiveEvent>, // Effects on the AI port, rolled independently
+    pub ai_crane_efficiency_modifier: f64,
+    pub spawn_config: SpawnConfig,
+    pub rules: GameRules,
+    scoring: Option<Arc<dyn ScoringStrategy>>, // None = Port::calculate_score
     next_ship_id: usize,
+    game_ended: bool,
+    action_points: u32, // Left this turn for the player
+    ai_action_points: u32,
+    extra_action_points: HashMap<PlayerId, u32>, // Budgets of the extra seats
+    processed_by_command: Vec<PlayerId>,         // Ports unloaded by `ProcessContainers` this turn
+    events_enabled: bool, // false = no new random events (active ones still expire)
+    emergency_cranes_left: u32,
+    emergency_cranes: Vec<CraneId>, // Deployed on the player port this turn
+    player_actions: Vec<(u64, usize)>, // This turn's undoable actions: (port version after, events)
+    idle_turns: u32,                // Consecutive turns without a ContainerProcessed
+    ai_enabled: bool,               // false = practice, the AI port stays idle
+    game_over_callback: Option<GameOverCallback<E>>,
+    // GameStarted, written with the first append so layout builders can still
+    // change what it records (None once written)
+    pending_start: Option<DomainEvent>,
 }
 
 impl GameSession {
     pub fn new(mode: GameMode, player_id: PlayerId, ai_id: PlayerId) -> Self {
+        Self::with_event_store(mode, player_id, ai_id, InMemoryEventStore::new())
+    }
+
+    /// Continue playing from an exported replay: every port is rebuilt from
+    /// the history (mode, layout and extra seats come from `GameStarted`), which
+    /// also seeds the new session's store so further events append after it.
+    /// Weather effects and action points start fresh.
+    pub fn resume_from_replay(json: &str) -> Result<Self, String> {
+        Self::resume_with_rules(ReplayEnvelope::from_json(json)?, GameRules::default())
+    }
+
+    /// Where the CLI saves a game left mid-way
+    pub fn autosave_path() -> PathBuf {
+        PathBuf::from(AUTOSAVE_FILE)
+    }
+
+    /// Continue a game saved by `save_to_json`, including the state its
+    /// events do not carry (action points, weather). The scoring
+    /// strategy is not saved.
+    pub fn load_from_json(json: &str) -> Result<Self, String> {
+        let save: SaveGame = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let mut session = Self::resume_with_rules(save.replay, save.rules)?;
+        session.mode = save.mode;
+        session.spawn_config = save.spawn_config;
+        session.active_events = save.active_events;
+        session.crane_efficiency_modifier = save.crane_efficiency_modifier;
+        session.ai_active_events = save.ai_active_events;
+        session.ai_crane_efficiency_modifier = save.ai_crane_efficiency_modifier;
+        session.action_points = save.action_points;
+        session.ai_action_points = save.ai_action_points;
+        session.emergency_cranes_left = save.emergency_cranes_left;
+        session.events_enabled = save.events_enabled;
+        session.ai_enabled = save.ai_enabled;
+        session.idle_turns = save.idle_turns;
+        Ok(session)
+    }
+
+    /// Rebuild both ports from `replay`, scored with `rules`
+    fn resume_with_rules(replay: ReplayEnvelope, rules: GameRules) -> Result<Self, String> {
+        let Some(DomainEvent::GameStarted {
+            player_id,
+            ai_player_id: ai_id,
+            num_berths,
+            num_cranes,
+            crane_speeds,
+            mode,
+            extra_players,
+            ..
+        }) = replay
+            .events
+            .iter()
+            .find(|event| matches!(event, DomainEvent::GameStarted { .. }))
+            .cloned()
+        else {
+            return Err("Replay has no GameStarted event".to_string());
+        };
+
+        let mut session = Self::new(mode, player_id, ai_id);
+        // Older replays only know the crane count: assume the default speeds
+        let crane_speeds = if crane_speeds.is_empty() {
+            CRANE_SPEEDS
+                .iter()
+                .copied()
+                .cycle()
+                .take(num_cranes)
+                .collect()
+        } else {
+            crane_speeds
+        };
+        session.player_port = Port::new_with_crane_speeds(player_id, num_berths, &crane_speeds)
+            .with_aggregate_id(replay.session_id);
+        session.ai_port = Port::new_with_crane_speeds(ai_id, num_berths, &crane_speeds)
+            .with_aggregate_id(replay.session_id);
+        session.session_id = replay.session_id;
+        session = session.with_rules(rules);
+        for extra_player in extra_players {
+            session = session.with_extra_port(extra_player);
+        }
+
+        // Each port recorded its events with strictly increasing versions:
+        // anything else means a reordered or corrupted file
+        let mut last_versions = HashMap::new();
+        for (index, event) in replay.events.iter().enumerate() {
+            if let Some(port) = session.recording_port(event) {
+                let version = event.metadata().version;
+                if let Some(last) = last_versions
+                    .insert(port, version)
+                    .filter(|&last| version <= last)
+                {
+                    return Err(format!(
+                        "Event {} ({}) has version {} after version {} of the same port",
+                        index,
+                        event.event_type(),
+                        version,
+                        last
+                    ));
+                }
+            }
+            session.rehydrate_event(event);
+        }
+
+        session.event_store = InMemoryEventStore::new();
+        session
+            .event_store
+            .append(replay.session_id, replay.events)?;
+        session.pending_start = None;
+        Ok(session)
+    }
+}
+
+impl<E: EventStore> GameSession<E> {
+    /// Create a session that records its events into the given store
+    pub fn with_event_store(
+        mode: GameMode,
+        player_id: PlayerId,
+        ai_id: PlayerId,
+        event_store: E,
+    ) -> Self {
         let session_id = Uuid::new_v4();
 
-        // Simple configuration: 2 berths, 2 cranes
-        let player_port = Port::new(player_id, 2, 2);
-        let ai_port = Port::new(ai_id, 2, 2);
+        // Simple configuration: 2 berths, 2 cranes of different speeds
+        let crane_speeds: Vec<f64> = CRANE_SPEEDS.iter().copied().cycle().take(2).collect();
+        let mut player_port =
+            Port::new_with_crane_speeds(player_id, 2, &crane_speeds).with_aggregate_id(session_id);
+        let mut ai_port =
+            Port::new_with_crane_speeds(ai_id, 2, &crane_speeds).with_aggregate_id(session_id);
+        for port in [&mut player_port, &mut ai_port] {
+            port.set_premium_berth_fee(DEFAULT_PREMIUM_BERTH_FEE);
+        }
 
         let mcts_config = MCTSConfig {
             num_simulations: 100, // Small for MVP
             exploration_constant: 1.41,
             max_depth: 20,
             max_actions_per_turn: 1,
+            reward: RewardConfig {
+                idle_crane_weight: DEFAULT_IDLE_CRANE_PENALTY as f64,
+                ..RewardConfig::default()
+            },
+            adversarial: false,
+            max_nodes: 10_000,
+            use_rave: false,
+            rave_equivalence: 300.0,
+            exploration_schedule: ExplorationSchedule::Constant,
+            rollout_noise: 5.0,
+            use_priors: false,
         };
 
         let mcts_engine = MCTSEngine::new(mcts_config);
-        let mut event_store = InMemoryEventStore::new();
-
-        // Emit GameStarted event
-        let start_event = DomainEvent::GameStarted {
-            metadata: EventMetadata::new(session_id, 1),
-            player_id,
-            ai_player_id: ai_id,
-            num_berths: 2,
-            num_cranes: 2,
-        };
 
-        event_store.append(session_id, vec![start_event]).ok();
-
-        Self {
+        let mut session = Self {
             session_id,
             mode,
             player_port,
             ai_port,
+            extra_ports: Vec::new(),
             current_turn: 0,
             current_player: player_id,
             mcts_engine,
@@ -78,12 +481,215 @@ impl GameSession {
             event_generator: EventGenerator::default(),
             active_events: Vec::new(),
             crane_efficiency_modifier: 1.0,
+            ai_active_events: Vec::new(),
+            ai_crane_efficiency_modifier: 1.0,
+            spawn_config: SpawnConfig::default(),
+            rules: GameRules::default(),
+            scoring: None,
             next_ship_id: 0,
+            game_ended: false,
+            action_points: DEFAULT_ACTION_POINTS,
+            ai_action_points: DEFAULT_ACTION_POINTS,
+            extra_action_points: HashMap::new(),
+            processed_by_command: Vec::new(),
+            // Tutorials stay predictable
+            events_enabled: mode != GameMode::Tutorial,
+            emergency_cranes_left: DEFAULT_EMERGENCY_CRANES,
+            emergency_cranes: Vec::new(),
+            player_actions: Vec::new(),
+            idle_turns: 0,
+            ai_enabled: true,
+            game_over_callback: None,
+            pending_start: None,
+        };
+        session.restate_start();
+        session
+    }
+
+    /// Replace the AI engine with one built from the given configuration
+    pub fn with_mcts_config(mut self, config: MCTSConfig) -> Self {
+        self.mcts_engine = MCTSEngine::new(config);
+        if let Some(strategy) = &self.scoring {
+            self.mcts_engine.set_scoring(strategy.clone());
+        }
+        self
+    }
+
+    /// Score both ports (game result and AI rollouts) with a custom strategy
+    pub fn with_scoring(mut self, strategy: Arc<dyn ScoringStrategy>) -> Self {
+        self.mcts_engine.set_scoring(strategy.clone());
+        self.scoring = Some(strategy);
+        self
+    }
+
+    /// Score of a port under the session's scoring strategy
+    pub fn score(&self, port: &Port) -> i32 {
+        self.scoring().score(port)
+    }
+
+    /// Read model of `port`, scored by the session's strategy
+    pub fn port_state(&self, port: &Port) -> PortStateView {
+        query_port_state_with_scoring(port, self.scoring())
+    }
+
+    fn scoring(&self) -> &dyn ScoringStrategy {
+        match &self.scoring {
+            Some(strategy) => strategy.as_ref(),
+            None => &DefaultScoring,
+        }
+    }
+
+    /// Ships the player has fully unloaded and undocked so far
+    pub fn ships_completed(&self, player_id: PlayerId) -> u32 {
+        count_ships_completed(&self.session_events().unwrap_or_default(), player_id)
+    }
+
+    pub fn comparison_stats(&self) -> ComparisonStats {
+        let events = self.session_events().unwrap_or_default();
+        let mut stats = query_comparison_stats_with_scoring(
+            &self.player_port,
+            &self.ai_port,
+            &events,
+            self.scoring(),
+        );
+        if !self.ai_has_own_port() {
+            // An idle AI port is no opponent: nothing on its side to compare
+            stats.ai_score = 0;
+            stats.ai_ships_processed = 0;
+            stats.ai_avg_wait_time = 0.0;
         }
+        stats
+    }
+
+    /// Use the given range for the container count of spawned ships,
+    /// refusing one that fails `SpawnConfig::validate`
+    pub fn with_spawn_config(mut self, config: SpawnConfig) -> Result<Self, String> {
+        self.set_spawn_config(config)?;
+        Ok(self)
+    }
+
+    /// Replace the spawn configuration, refusing one that fails `SpawnConfig::validate`
+    pub fn set_spawn_config(&mut self, config: SpawnConfig) -> Result<(), String> {
+        config.validate()?;
+        self.spawn_config = config;
+        Ok(())
+    }
+
+    /// Play under the given rules (turn limit, harbor capacity)
+    pub fn with_rules(mut self, rules: GameRules) -> Self {
+        self.rules = rules;
+        self.player_port.containers_per_crane = rules.containers_per_crane_per_turn;
+        self.ai_port.containers_per_crane = rules.containers_per_crane_per_turn;
+        self.player_port.points_per_container = rules.points_per_container;
+        self.ai_port.points_per_container = rules.points_per_container;
+        self.player_port.idle_crane_penalty = rules.idle_crane_penalty;
+        self.ai_port.idle_crane_penalty = rules.idle_crane_penalty;
+        self.player_port
+            .set_premium_berth_fee(rules.premium_berth_fee);
+        self.ai_port.set_premium_berth_fee(rules.premium_berth_fee);
+        // The AI weighs idle cranes as the rules charge them
+        let reward = RewardConfig {
+            idle_crane_weight: rules.idle_crane_penalty as f64,
+            ..self.mcts_engine.config().reward.clone()
+        };
+        self.mcts_engine.set_reward(reward);
+        self.reset_action_points();
+        self.emergency_cranes_left = rules.emergency_cranes;
+        self
+    }
+
+    /// Give both ports the same seeded random layout (see `Port::random_layout`),
+    /// keeping the current rules. Call before any ship arrives: the ports are
+    /// rebuilt empty.
+    pub fn with_random_layout(mut self, seed: u64, constraints: LayoutConstraints) -> Self {
+        let mut layout = Port::random_layout(self.player_port.player_id, seed, constraints)
+            .with_aggregate_id(self.session_id);
+        layout.containers_per_crane = self.rules.containers_per_crane_per_turn;
+        layout.points_per_container = self.rules.points_per_container;
+        layout.idle_crane_penalty = self.rules.idle_crane_penalty;
+        layout.set_premium_berth_fee(self.rules.premium_berth_fee);
+
+        let mut ai_port = layout.clone();
+        ai_port.player_id = self.ai_port.player_id;
+        self.player_port = layout;
+        self.ai_port = ai_port;
+        self.restate_start();
+        self
+    }
+
+    /// Seat one more port with the player's layout and the current rules.
+    /// Call before any ship arrives. Extra ports receive the mirrored ships,
+    /// process containers with the player's weather and are played through
+    /// `execute_command`; the MCTS engine only drives `ai_port`.
+    pub fn with_extra_port(mut self, player_id: PlayerId) -> Self {
+        let mut port = self.player_port.clone();
+        port.player_id = player_id;
+        self.extra_ports.push(port);
+        self.extra_action_points
+            .insert(player_id, self.rules.action_points_per_turn);
+        self.restate_start();
+        self
+    }
+
+    /// Player port, AI port, then the extra seats
+    pub fn ports(&self) -> impl Iterator<Item = &Port> {
+        [&self.player_port, &self.ai_port]
+            .into_iter()
+            .chain(&self.extra_ports)
+    }
+
+    /// Port of `player_id`, whichever seat it is
+    pub fn port(&self, player_id: PlayerId) -> Option<&Port> {
+        self.ports().find(|port| port.player_id == player_id)
+    }
+
+    pub fn port_mut(&mut self, player_id: PlayerId) -> Option<&mut Port> {
+        self.ports_mut().find(|port| port.player_id == player_id)
+    }
+
+    fn ports_mut(&mut self) -> impl Iterator<Item = &mut Port> {
+        [&mut self.player_port, &mut self.ai_port]
+            .into_iter()
+            .chain(&mut self.extra_ports)
+    }
+
+    /// Ports taking part in the game: the AI port sits out when disabled
+    fn active_ports(&self) -> impl Iterator<Item = &Port> {
+        std::iter::once(&self.player_port)
+            .chain(self.ai_has_own_port().then_some(&self.ai_port))
+            .chain(&self.extra_ports)
+    }
+
+    fn active_ports_mut(&mut self) -> impl Iterator<Item = &mut Port> {
+        let ai_has_own_port = self.ai_has_own_port();
+        std::iter::once(&mut self.player_port)
+            .chain(ai_has_own_port.then_some(&mut self.ai_port))
+            .chain(&mut self.extra_ports)
+    }
+
+    /// Calculated scores of every port, best first
+    pub fn standings(&self) -> Vec<(PlayerId, i32)> {
+        let mut standings: Vec<_> = self
+            .ports()
+            .map(|port| (port.player_id, self.score(port)))
+            .collect();
+        standings.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        standings
     }
 
     pub fn start_turn(&mut self) {
+        // Persist everything recorded during the previous turn in one batch
+        self.commit_events();
+
+        if self.containers_processed_last_turn() {
+            self.idle_turns = 0;
+        } else {
+            self.idle_turns += 1;
+        }
+
         self.current_turn += 1;
+        self.reset_action_points();
+        self.processed_by_command.clear();
 
         // Nous ne libérons plus automatiquement toutes les grues
         // self.player_port.free_all_cranes();
@@ -95,516 +701,4184 @@ impl GameSession {
             current_player: self.current_player,
         };
 
-        self.event_store.append(self.session_id, vec![event]).ok();
+        // Port clocks follow the turn counter so waiting penalties accrue
+        for port in self.active_ports_mut() {
+            port.apply_event(&event);
+            port.charge_waiting_penalty();
+        }
+        self.append_to_store(vec![event]);
+        // The charges open the turn: they are final, not undoable moves
+        self.commit_events();
+
+        self.end_game_if_over();
     }
 
+    /// Mirror match: all ports always receive the same ships (ids, cargo,
+    /// arrival time), including rush-hour extras. When any harbor is at capacity
+    /// the ship is refused for all and an `ArrivalRejected` is recorded per full harbor.
     pub fn spawn_ships(&mut self, count: usize) {
-        let mut events = Vec::new();
-
-        for i in 0..count {
+        for _ in 0..count {
             let ship_id = ShipId::new(self.next_ship_id);
             self.next_ship_id += 1;
-            let containers = 20 + (i * 10) as u32; // Varying sizes
+            let containers = self.spawn_config.sample_containers();
+
+            let full_harbors: Vec<PlayerId> = self
+                .active_ports()
+                .filter(|port| self.harbor_is_full(port))
+                .map(|port| port.player_id)
+                .collect();
+
+            if !full_harbors.is_empty() {
+                for player in full_harbors {
+                    let event = DomainEvent::ArrivalRejected {
+                        metadata: EventMetadata::new(
+                            self.session_id,
+                            self.player_port.version() + 1,
+                        ),
+                        ship_id,
+                        container_count: containers,
+                        player,
+                    };
+                    self.mirror_shared_event(&event);
+                    self.player_port.record_event(event);
+                }
+                continue;
+            }
 
             let event = DomainEvent::ShipArrived {
                 metadata: EventMetadata::new(self.session_id, self.player_port.version() + 1),
                 ship_id,
                 container_count: containers,
                 arrival_time: self.current_turn as f64,
+                priority: random::hit(PRIORITY_SHIP_PROBABILITY),
+                cargo: Some(self.spawn_config.cargo_mix(containers)),
+                player: (!self.ai_has_own_port()).then_some(self.player_port.player_id),
             };
 
-            events.push(event.clone());
-            self.player_port.apply_event(&event);
-            self.ai_port.apply_event(&event);
+            // Arrivals are shared: the player port records the event, the others mirror it
+            self.mirror_shared_event(&event);
+            self.player_port.record_event(event);
         }
 
-        self.event_store.append(self.session_id, events).ok();
+        self.end_game_if_over();
     }
 
-    pub fn get_player_port(&self) -> &Port {
-        &self.player_port
+    /// Apply an event recorded by the player port (arrival, harbor-wide
+    /// random event) to the other active ports
+    fn mirror_shared_event(&mut self, event: &DomainEvent) {
+        for port in self.active_ports_mut().skip(1) {
+            port.apply_event(event);
+        }
     }
 
-    pub fn get_ai_port(&self) -> &Port {
-        &self.ai_port
+    fn harbor_is_full(&self, port: &Port) -> bool {
+        self.rules
+            .harbor_capacity
+            .is_some_and(|capacity| port.waiting_ships().len() >= capacity)
     }
 
-    pub fn export_replay(&self) -> Result<String, String> {
-        self.event_store.export_to_json(self.session_id)
+    /// Whether any container was unloaded since the last `TurnStarted` in the store
+    fn containers_processed_last_turn(&self) -> bool {
+        self.session_events()
+            .unwrap_or_default()
+            .iter()
+            .rev()
+            .take_while(|event| !matches!(event, DomainEvent::TurnStarted { .. }))
+            .any(|event| matches!(event, DomainEvent::ContainerProcessed { .. }))
     }
 
-    /// Player docks a ship
-    pub fn player_dock_ship(
-        &mut self,
-        ship_id: ShipId,
-        berth_id: crate::domain::value_objects::BerthId,
-    ) -> Result<(), String> {
-        use crate::application::handlers::handle_dock_ship_command;
+    /// Whether docking a ship or assigning a crane is possible on `port`
+    fn has_progress_move(port: &Port) -> bool {
+        MCTSTree::new()
+            .generate_actions(port)
+            .iter()
+            .any(|action| *action != MCTSAction::Pass)
+    }
 
-        let events = handle_dock_ship_command(
-            &self.player_port,
-            self.session_id,
-            ship_id,
-            berth_id,
-            self.player_port.player_id,
-        )?;
+    /// Turns left before the turn limit (0 once it is reached)
+    pub fn turns_remaining(&self) -> u32 {
+        self.rules.max_turns.saturating_sub(self.current_turn)
+    }
 
-        for event in &events {
-            self.player_port.apply_event(event);
+    /// Share of the turn limit already played, in `[0, 1]`
+    pub fn progress(&self) -> f64 {
+        if self.rules.max_turns == 0 {
+            return 1.0;
         }
-
-        self.event_store.append(self.session_id, events).ok();
-        Ok(())
+        self.current_turn.min(self.rules.max_turns) as f64 / self.rules.max_turns as f64
     }
 
-    /// Player assigns crane
-    pub fn player_assign_crane(
-        &mut self,
-        crane_id: crate::domain::value_objects::CraneId,
-        ship_id: ShipId,
-    ) -> Result<(), String> {
-        use crate::application::handlers::handle_assign_crane_command;
+    /// Current tide, shared by both ports
+    pub fn tide(&self) -> Tide {
+        self.player_port.tide
+    }
 
-        let events = handle_assign_crane_command(
-            &self.player_port,
-            self.session_id,
-            crane_id,
-            ship_id,
-            self.player_port.player_id,
-        )?;
+    /// Moves currently open to the player, as seen by the MCTS engine.
+    /// Empty when nothing can be done (passing is always allowed).
+    pub fn legal_actions(&self) -> Vec<MCTSAction> {
+        MCTSTree::new()
+            .generate_actions(&self.player_port)
+            .into_iter()
+            .filter(|action| *action != MCTSAction::Pass)
+            .collect()
+    }
 
-        for event in &events {
-            self.player_port.apply_event(event);
+    /// Both ports and the session status captured together
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            player_port: self.port_state(&self.player_port),
+            ai_port: self.port_state(&self.ai_port),
+            current_turn: self.current_turn,
+            is_game_over: self.is_game_over(),
+            winner: self.get_winner().map(str::to_string),
+            active_effects: self.get_active_effects_description(),
+            crane_efficiency: self.crane_efficiency_modifier,
+            action_points: self.action_points,
         }
-
-        self.event_store.append(self.session_id, events).ok();
-        Ok(())
     }
 
-    /// Process containers for all docked ships with assigned cranes
-    pub fn process_containers(&mut self) {
-        use crate::domain::events::DomainEvent;
+    /// FNV-1a hash of every port, the turn and the active random events. A
+    /// server and a WASM client running the same version compare it to detect
+    /// a desync, whatever toolchain built them.
+    pub fn state_fingerprint(&self) -> u64 {
+        let mut hasher = Fnv1a::default();
+        hasher.write_u32(self.current_turn);
+        for port in self.ports() {
+            port.hash_state(&mut hasher);
+        }
+        for (effects, efficiency) in [
+            (&self.active_events, self.crane_efficiency_modifier),
+            (&self.ai_active_events, self.ai_crane_efficiency_modifier),
+        ] {
+            hasher.write_u64(effects.len() as u64);
+            for effect in effects {
+                hasher.write(effect.event.description().as_bytes());
+                hasher.write_u32(effect.turns_remaining);
+            }
+            hasher.write_u64(efficiency.to_bits());
+        }
+        hasher.finish()
+    }
 
-        // Player port
-        let mut events = Vec::new();
-        for ship in self.player_port.docked_ships() {
-            if !ship.assigned_cranes.is_empty() {
-                let crane_count = ship.assigned_cranes.len() as u32;
-                let base_amount = crane_count * 10; // Each crane processes 10 containers
-                let process_amount = (base_amount as f64 * self.crane_efficiency_modifier) as u32;
+    pub fn get_player_port(&self) -> &Port {
+        &self.player_port
+    }
 
-                if ship.containers_remaining > 0 {
-                    let processed = process_amount.min(ship.containers_remaining);
-                    let remaining = ship.containers_remaining - processed;
+    pub fn get_ai_port(&self) -> &Port {
+        &self.ai_port
+    }
 
-                    let event = DomainEvent::ContainerProcessed {
-                        metadata: EventMetadata::new(
-                            self.session_id,
-                            self.player_port.version() + 1,
-                        ),
-                        crane_id: ship.assigned_cranes[0], // Representative crane
-                        ship_id: ship.id,
-                        containers_remaining: remaining,
-                    };
+    pub fn export_replay(&self) -> Result<String, String> {
+        let events = self.session_events()?;
+        ReplayEnvelope::new(self.session_id, events).to_json()
+    }
 
-                    events.push(event);
-                }
-            }
-        }
+    /// Full session as JSON, to continue later with `load_from_json`
+    pub fn save_to_json(&self) -> Result<String, String> {
+        let save = SaveGame {
+            replay: ReplayEnvelope::new(self.session_id, self.session_events()?),
+            mode: self.mode,
+            rules: self.rules,
+            spawn_config: self.spawn_config,
+            active_events: self.active_events.clone(),
+            crane_efficiency_modifier: self.crane_efficiency_modifier,
+            ai_active_events: self.ai_active_events.clone(),
+            ai_crane_efficiency_modifier: self.ai_crane_efficiency_modifier,
+            action_points: self.action_points,
+            ai_action_points: self.ai_action_points,
+            emergency_cranes_left: self.emergency_cranes_left,
+            events_enabled: self.events_enabled,
+            ai_enabled: self.ai_enabled,
+            idle_turns: self.idle_turns,
+        };
+        serde_json::to_string_pretty(&save).map_err(|e| e.to_string())
+    }
 
-        for event in &events {
-            self.player_port.apply_event(event);
-        }
+    /// Every event of the game so far, described in plain words
+    pub fn event_log(&self) -> Vec<String> {
+        self.session_events()
+            .unwrap_or_default()
+            .iter()
+            .map(DomainEvent::describe)
+            .collect()
+    }
 
-        self.event_store.append(self.session_id, events).ok();
+    /// Turn-by-turn summary of the game, rebuilt from the event history
+    pub fn turn_log(&self) -> Vec<TurnSummary> {
+        let events = self.session_events().unwrap_or_default();
+        history::turn_log(
+            &events,
+            self.player_port.player_id,
+            self.ai_port.player_id,
+            self.rules.points_per_container,
+        )
+    }
 
-        // AI port (same logic)
-        let mut events = Vec::new();
-        for ship in self.ai_port.docked_ships() {
-            if !ship.assigned_cranes.is_empty() {
-                let crane_count = ship.assigned_cranes.len() as u32;
-                let base_amount = crane_count * 10;
-                let process_amount = (base_amount as f64 * self.crane_efficiency_modifier) as u32;
-
-                if ship.containers_remaining > 0 {
-                    let processed = process_amount.min(ship.containers_remaining);
-                    let remaining = ship.containers_remaining - processed;
-
-                    let event = DomainEvent::ContainerProcessed {
-                        metadata: EventMetadata::new(self.session_id, self.ai_port.version() + 1),
-                        crane_id: ship.assigned_cranes[0],
-                        ship_id: ship.id,
-                        containers_remaining: remaining,
-                    };
+    /// Container throughput per turn as CSV, rebuilt from the event history:
+    /// `turn,player_processed,ai_processed,cumulative_score` where the score is
+    /// the player's container score (waiting penalties excluded)
+    pub fn export_throughput_csv(&self) -> String {
+        let mut csv = String::from("turn,player_processed,ai_processed,cumulative_score\n");
+        let mut cumulative_score = 0;
 
-                    events.push(event);
-                }
-            }
+        for summary in self.turn_log() {
+            cumulative_score += summary.score_delta;
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                summary.turn,
+                summary.containers_processed,
+                summary.ai_containers_processed,
+                cumulative_score
+            ));
         }
+        csv
+    }
 
-        for event in &events {
-            self.ai_port.apply_event(event);
+    /// Stored events followed by the ones recorded since the last commit
+    fn session_events(&self) -> Result<Vec<DomainEvent>, String> {
+        let mut events = self.event_store.load(self.session_id)?;
+        events.splice(0..0, self.pending_start.iter().cloned());
+        events.extend(self.player_port.uncommitted_events().iter().cloned());
+        events.extend(self.ai_port.uncommitted_events().iter().cloned());
+        for port in &self.extra_ports {
+            events.extend(port.uncommitted_events().iter().cloned());
         }
-
-        self.event_store.append(self.session_id, events).ok();
+        Ok(events)
     }
 
-    /// AI takes its turn using MCTS
-    pub fn ai_take_turn(&mut self) {
-        let max_actions = self
-            .mcts_engine
-            .config()
-            .max_actions_per_turn
-            .max(1);
-
-        for _ in 0..max_actions {
-            // Get best action from MCTS
-            let Some(action) = self.mcts_engine.search(&self.ai_port) else {
-                break;
-            };
-
-            // Apply action to AI port
-            let applied = match action.clone() {
-                crate::mcts::MCTSAction::DockShip { ship_id, berth_id } => {
-                    self.ai_dock_ship(ship_id, berth_id)
+    /// Apply one history event to the port(s) it was recorded on
+    fn rehydrate_event(&mut self, event: &DomainEvent) {
+        match event {
+            DomainEvent::TurnStarted { turn_number, .. } => {
+                // Same clock and tide as `start_turn`; the charges come back
+                // through their own events
+                self.current_turn = *turn_number;
+                for port in self.ports_mut() {
+                    port.apply_event(event);
                 }
-                crate::mcts::MCTSAction::AssignCrane { crane_id, ship_id } => {
-                    self.ai_assign_crane(crane_id, ship_id)
+            }
+            DomainEvent::ShipArrived { ship_id, .. }
+            | DomainEvent::ArrivalRejected { ship_id, .. } => {
+                // Arrivals are mirrored on every port, unless the AI sat out
+                self.next_ship_id = self.next_ship_id.max(ship_id.0 + 1);
+                let mirrored = match event {
+                    DomainEvent::ShipArrived { .. } => event.concerns_port(self.ai_port.player_id),
+                    _ => true,
+                };
+                self.player_port.apply_event(event);
+                if mirrored {
+                    self.ai_port.apply_event(event);
                 }
-                crate::mcts::MCTSAction::UnassignCrane { crane_id } => {
-                    // Utilise la logique de domaine directe: libère la grue si affectée
-                    self.ai_port.free_crane(crane_id);
-                    true
+                for port in &mut self.extra_ports {
+                    port.apply_event(event);
+                }
+            }
+            DomainEvent::RandomEventTriggered { player, .. } => {
+                // Harbor-wide events (no player) are mirrored like arrivals.
+                // Older replays stamped these with the turn number, so they
+                // stay out of `recording_port`'s version check.
+                match player {
+                    Some(player) => {
+                        if let Some(port) = self.port_mut(*player) {
+                            port.apply_event(event);
+                        }
+                    }
+                    None => {
+                        for port in self.ports_mut() {
+                            port.apply_event(event);
+                        }
+                    }
+                }
+            }
+            DomainEvent::GameEnded { .. } => self.game_ended = true,
+            DomainEvent::EmergencyCraneDeployed { crane_id, .. } => {
+                self.player_port.apply_event(event);
+                self.emergency_cranes.push(*crane_id);
+                self.emergency_cranes_left = self.emergency_cranes_left.saturating_sub(1);
+            }
+            DomainEvent::EmergencyCraneRemoved { crane_id, .. } => {
+                self.player_port.apply_event(event);
+                self.emergency_cranes.retain(|id| id != crane_id);
+            }
+            _ => {
+                // Session-level events (start, turn end, random events) have no port
+                let recorded_on = self.recording_port(event);
+                if let Some(port) = recorded_on.and_then(|player| self.port_mut(player)) {
+                    port.apply_event(event);
                 }
-                crate::mcts::MCTSAction::Pass => false,
-            };
-
-            if !applied {
-                break;
             }
         }
-
-        self.ai_fill_open_berths_and_assign_cranes();
     }
 
-    /// Check if game is over (all ships processed)
-    pub fn is_game_over(&self) -> bool {
-        // Conditions de fin de jeu :
-        // 1. Score suffisamment élevé (victoire)
-        if self.player_port.score > 1000 {
-            return true;
+    /// Port whose stream `event` was recorded on, None for session-level events.
+    /// Mirrored arrivals are recorded (and versioned) by the player port.
+    fn recording_port(&self, event: &DomainEvent) -> Option<PlayerId> {
+        match event {
+            DomainEvent::ShipArrived { .. } | DomainEvent::ArrivalRejected { .. } => {
+                Some(self.player_port.player_id)
+            }
+            DomainEvent::ShipDocked { player, .. }
+            | DomainEvent::ShipUndocked { player, .. }
+            | DomainEvent::CraneAssigned { player, .. }
+            | DomainEvent::ContainerProcessed { player, .. }
+            | DomainEvent::PlayerPassed { player, .. }
+            | DomainEvent::EmergencyCraneDeployed { player, .. }
+            | DomainEvent::EmergencyCraneRemoved { player, .. }
+            | DomainEvent::WaitingPenaltyCharged { player, .. }
+            | DomainEvent::IdleCranesCharged { player, .. }
+            | DomainEvent::CraneWearChanged { player, .. }
+            | DomainEvent::MCTSSearchStarted { player, .. }
+            | DomainEvent::MCTSSearchCompleted { player, .. } => Some(*player),
+            DomainEvent::CraneUnassigned {
+                player: Some(player),
+                ..
+            } => Some(*player),
+            DomainEvent::CraneUnassigned {
+                crane_id, ship_id, ..
+            } => {
+                // Older replays have no player on this event: it belongs to the port
+                // whose crane works on that ship (the player's first if both match)
+                let works_on_ship = |port: &Port| {
+                    port.cranes
+                        .get(crane_id)
+                        .is_some_and(|crane| crane.assigned_to == Some(*ship_id))
+                };
+                [&self.player_port, &self.ai_port]
+                    .into_iter()
+                    .find(|port| works_on_ship(port))
+                    .map(|port| port.player_id)
+            }
+            _ => None,
         }
-
-        // 2. Trop de navires en attente (défaite)
-        let waiting_ships = self.player_port.waiting_ships().len();
-        if waiting_ships > 10 {
-            return true;
+    }
+    /// Append the events recorded by both ports to the event store
+    pub fn commit_events(&mut self) {
+        self.player_actions.clear();
+        let mut events = self.player_port.take_uncommitted_events();
+        events.extend(self.ai_port.take_uncommitted_events());
+        for port in &mut self.extra_ports {
+            events.extend(port.take_uncommitted_events());
         }
 
-        // 3. Durée maximum atteinte (30 tours)
-        if self.current_turn >= 30 {
-            return true;
+        if !events.is_empty() {
+            self.append_to_store(events);
         }
+    }
 
-        false
+    /// Append to the store, opening the stream with `GameStarted` first
+    fn append_to_store(&mut self, mut events: Vec<DomainEvent>) {
+        if let Some(start) = self.pending_start.take() {
+            events.insert(0, start);
+        }
+        self.event_store.append(self.session_id, events).ok();
     }
 
-    /// Get winner (if game is over)
-    pub fn get_winner(&self) -> Option<&str> {
-        if !self.is_game_over() {
-            return None;
+    /// Rebuild the pending `GameStarted` from the current layout (no-op once
+    /// the game is under way)
+    fn restate_start(&mut self) {
+        if self.event_store.event_count(self.session_id) > 0 {
+            return;
         }
+        let port = &self.player_port;
+        self.pending_start = Some(DomainEvent::GameStarted {
+            metadata: EventMetadata::new(self.session_id, 1),
+            player_id: port.player_id,
+            ai_player_id: self.ai_port.player_id,
+            num_berths: port.berths.len(),
+            num_cranes: port.cranes.len(),
+            crane_speeds: port
+                .cranes
+                .values()
+                .map(|crane| crane.processing_speed)
+                .collect(),
+            mode: self.mode,
+            extra_players: self.extra_ports.iter().map(|port| port.player_id).collect(),
+        });
+    }
 
-        let player_score = self.player_port.calculate_score();
-        let ai_score = self.ai_port.calculate_score();
+    /// Validate and apply commands in order, stopping at the first failure.
+    /// Commands before the failing one stay applied; the error carries its index.
+    pub fn execute_commands(
+        &mut self,
+        commands: &[Command],
+    ) -> Result<Vec<DomainEvent>, (usize, String)> {
+        let mut all_events = Vec::new();
 
-        if player_score > ai_score {
-            Some("player")
-        } else if ai_score > player_score {
-            Some("ai")
-        } else {
-            Some("tie")
+        for (index, command) in commands.iter().enumerate() {
+            let events = self
+                .execute_command(command)
+                .map_err(|error| (index, error))?;
+            all_events.extend(events);
         }
+
+        Ok(all_events)
     }
 
-    /// Process random events
-    pub fn process_random_events(&mut self) -> Vec<RandomEvent> {
-        let mut new_events = Vec::new();
+    /// Check a command against the port and action budget of its player
+    /// without applying it (same validation as `execute_command`, no events produced)
+    pub fn can_execute(&self, command: &Command) -> Result<(), String> {
+        let player_id = command.player_id();
+        let port = self
+            .port(player_id)
+            .ok_or_else(|| format!("Unknown player {}", player_id))?;
+        if command.is_action() {
+            self.ensure_action_points_of(player_id)?;
+        }
 
-        // Update active events
-        self.active_events.retain_mut(|active| {
-            let expired = active.tick();
-            !expired
-        });
+        match command {
+            Command::DockShip {
+                ship_id, berth_id, ..
+            } => validate_dock_ship(port, *ship_id, *berth_id),
+            Command::AssignCrane {
+                crane_id, ship_id, ..
+            } => validate_assign_crane(port, *crane_id, *ship_id),
+            Command::ReassignCrane {
+                crane_id,
+                new_ship_id,
+                ..
+            } => validate_reassign_crane(port, *crane_id, *new_ship_id).map(|_| ()),
+            Command::UnassignCrane {
+                crane_id, ship_id, ..
+            } => validate_unassign_crane(port, *crane_id, *ship_id),
+            Command::ProcessContainers { .. } => self.ensure_not_processed(player_id),
+            Command::Pass { .. } => Ok(()),
+            other => Err(format!(
+                "{} cannot be executed as a command yet",
+                other.command_type()
+            )),
+        }
+    }
 
-        // Reset modifiers
-        self.crane_efficiency_modifier = 1.0;
+    /// Validate and apply a single command on the port of its player.
+    /// Dock, assign, unassign, reassign and pass spend one of that side's action points;
+    /// `ProcessContainers` is free but accepted once per port and turn.
+    pub fn execute_command(&mut self, command: &Command) -> Result<Vec<DomainEvent>, String> {
+        let player_id = command.player_id();
+        if self.port(player_id).is_none() {
+            return Err(format!("Unknown player {}", player_id));
+        }
+        if command.is_action() {
+            self.ensure_action_points_of(player_id)?;
+        }
+        if let Command::ProcessContainers { .. } = command {
+            self.ensure_not_processed(player_id)?;
+        }
+        let session_id = self.session_id;
+        let efficiency = self.crane_efficiency(player_id);
+        let batch_size = self.rules.container_batch_size;
+        let processing_policy = self.rules.processing_policy;
+        let port = self
+            .port_mut(player_id)
+            .ok_or_else(|| format!("Unknown player {}", player_id))?;
 
-        // Apply active event effects
-        for active in &self.active_events {
-            match &active.event {
-                RandomEvent::Storm {
-                    efficiency_penalty, ..
-                } => {
-                    self.crane_efficiency_modifier *= 1.0 - efficiency_penalty;
-                }
-                RandomEvent::GoodWeather {
-                    efficiency_bonus, ..
-                } => {
-                    self.crane_efficiency_modifier *= 1.0 + efficiency_bonus;
-                }
-                _ => {}
+        let events = match command {
+            Command::DockShip {
+                ship_id, berth_id, ..
+            } => handle_dock_ship_command(port, session_id, *ship_id, *berth_id, player_id)?,
+            Command::AssignCrane {
+                crane_id, ship_id, ..
+            } => handle_assign_crane_command(port, session_id, *crane_id, *ship_id, player_id)?,
+            Command::ReassignCrane {
+                crane_id,
+                new_ship_id,
+                ..
+            } => {
+                handle_reassign_crane_command(port, session_id, *crane_id, *new_ship_id, player_id)?
             }
-        }
+            Command::UnassignCrane {
+                crane_id, ship_id, ..
+            } => handle_unassign_crane_command(port, session_id, *crane_id, *ship_id, player_id)?,
+            Command::ProcessContainers { .. } => handle_process_containers_with_policy(
+                port,
+                session_id,
+                efficiency,
+                batch_size,
+                processing_policy,
+            ),
+            Command::Pass { .. } => handle_pass_command(port, session_id, player_id),
+            other => {
+                return Err(format!(
+                    "{} cannot be executed as a command yet",
+                    other.command_type()
+                ))
+            }
+        };
 
-        // Generate new event
-        if let Some(event) = self.event_generator.generate() {
-            match &event {
-                RandomEvent::RushHour { extra_ships } => {
-                    self.spawn_ships(*extra_ships);
-                }
-                RandomEvent::CustomsInspection { .. } => {
-                    // Instant effect - handled in display
-                }
-                _ => {
-                    // Add to active events
-                    self.active_events.push(ActiveEvent::new(event.clone()));
-                }
+        for event in &events {
+            port.record_event(event.clone());
+        }
+        if command.is_action() {
+            if let Some(points) = self.action_points_mut(player_id) {
+                *points -= 1;
+            }
+            if player_id == self.player_port.player_id {
+                self.player_actions
+                    .push((self.player_port.version(), events.len()));
             }
-            new_events.push(event);
+        }
+        if let Command::ProcessContainers { .. } = command {
+            self.processed_by_command.push(player_id);
         }
 
-        new_events
+        Ok(events)
     }
 
-    /// Get description of active effects
-    pub fn get_active_effects_description(&self) -> Vec<String> {
-        self.active_events
-            .iter()
-            .map(|active| {
-                format!(
-                    "{} ({}  turns left)",
-                    active.event.description(),
-                    active.turns_remaining
-                )
-            })
-            .collect()
+    /// Player docks a ship
+    pub fn player_dock_ship(
+        &mut self,
+        ship_id: ShipId,
+        berth_id: crate::domain::value_objects::BerthId,
+    ) -> Result<(), String> {
+        use crate::application::handlers::handle_dock_ship_command;
+
+        self.ensure_action_points()?;
+        let events = handle_dock_ship_command(
+            &self.player_port,
+            self.session_id,
+            ship_id,
+            berth_id,
+            self.player_port.player_id,
+        )?;
+
+        self.record_player_action(events);
+        self.action_points -= 1;
+
+        Ok(())
     }
 
-    /// Free completed ships and their assigned cranes
-    pub fn free_completed_ships(&mut self) {
-        // Ne récupérer que les navires qui sont complètement déchargés
-        let completed_ships: Vec<_> = self
-            .player_port
-            .ships
-            .iter()
-            .filter(|(_, ship)| {
-                ship.is_docked() && ship.containers_remaining == 0 // Uniquement les navires complètement déchargés
-            })
-            .map(|(id, ship)| (*id, ship.docked_at.unwrap(), ship.assigned_cranes.clone()))
-            .collect();
+    /// Player assigns crane
+    pub fn player_assign_crane(
+        &mut self,
+        crane_id: crate::domain::value_objects::CraneId,
+        ship_id: ShipId,
+    ) -> Result<(), String> {
+        use crate::application::handlers::handle_assign_crane_command;
 
-        for (ship_id, berth_id, crane_ids) in completed_ships {
-            // Libérer les grues uniquement pour les navires terminés
-            for crane_id in crane_ids {
-                self.player_port.free_crane(crane_id);
-            }
-            // Puis libérer le quai
-            self.player_port.undock_ship(ship_id, berth_id);
-            // Retirer le navire
-            self.player_port.ships.remove(&ship_id);
-        }
+        self.ensure_action_points()?;
+        let events = handle_assign_crane_command(
+            &self.player_port,
+            self.session_id,
+            crane_id,
+            ship_id,
+            self.player_port.player_id,
+        )?;
 
-        let completed_ai_ships: Vec<_> = self
-            .ai_port
-            .ships
-            .iter()
-            .filter(|(_, ship)| ship.is_docked() && ship.containers_remaining == 0)
-            .map(|(id, ship)| (*id, ship.docked_at.unwrap(), ship.assigned_cranes.clone()))
-            .collect();
+        self.record_player_action(events);
+        self.action_points -= 1;
 
-        for (ship_id, berth_id, crane_ids) in completed_ai_ships {
-            for crane_id in crane_ids {
-                self.ai_port.free_crane(crane_id);
-            }
-            self.ai_port.undock_ship(ship_id, berth_id);
-        }
+        Ok(())
     }
 
-    /// End turn with proper sequence
-    pub fn end_turn(&mut self) {
-        // 1. Process containers one last time
-        self.process_containers();
+    /// Player puts several free cranes on one docked ship (one action).
+    /// All or nothing: if any crane cannot be assigned, none is.
+    pub fn player_assign_cranes(
+        &mut self,
+        ship_id: ShipId,
+        crane_ids: &[CraneId],
+    ) -> Result<(), String> {
+        self.ensure_action_points()?;
+        let events = handle_assign_cranes_command(
+            &self.player_port,
+            self.session_id,
+            ship_id,
+            crane_ids,
+            self.player_port.player_id,
+        )?;
 
-        // 2. Free completed ships and their assigned cranes
-        self.free_completed_ships();
+        self.record_player_action(events);
+        self.action_points -= 1;
 
-        // 3. Process random events for next turn
-        self.process_random_events();
+        Ok(())
+    }
 
-        // 4. Let AI take its turn
-        self.ai_take_turn();
+    /// Player moves a busy crane to another docked ship (one action)
+    pub fn player_reassign_crane(
+        &mut self,
+        crane_id: CraneId,
+        new_ship_id: ShipId,
+    ) -> Result<(), String> {
+        self.ensure_action_points()?;
+        let events = handle_reassign_crane_command(
+            &self.player_port,
+            self.session_id,
+            crane_id,
+            new_ship_id,
+            self.player_port.player_id,
+        )?;
 
-        // 4bis. AI fills any remaining free berths and assign cranes
-        self.ai_fill_open_berths_and_assign_cranes();
+        self.record_player_action(events);
+        self.action_points -= 1;
 
-        // 5. Start new turn
-        self.start_turn();
+        Ok(())
     }
 
-    fn apply_ai_events(&mut self, events: Vec<DomainEvent>) {
-        for event in &events {
-            self.ai_port.apply_event(event);
-        }
-        self.event_store.append(self.session_id, events).ok();
+    /// Player takes a crane off its ship, freeing it for another (one action)
+    pub fn player_unassign_crane(&mut self, crane_id: CraneId) -> Result<(), String> {
+        self.ensure_action_points()?;
+        let ship_id = self
+            .player_port
+            .cranes
+            .get(&crane_id)
+            .ok_or("Crane not found")?
+            .assigned_to
+            .ok_or_else(|| format!("Crane {} is not assigned", crane_id))?;
+        let events = handle_unassign_crane_command(
+            &self.player_port,
+            self.session_id,
+            crane_id,
+            ship_id,
+            self.player_port.player_id,
+        )?;
+
+        self.record_player_action(events);
+        self.action_points -= 1;
+
+        Ok(())
     }
 
-    fn ai_dock_ship(&mut self, ship_id: ShipId, berth_id: BerthId) -> bool {
-        match handle_dock_ship_command(
-            &self.ai_port,
+    /// Player spends an action doing nothing, without ending the turn
+    pub fn player_pass(&mut self) -> Result<(), String> {
+        self.ensure_action_points()?;
+        let events = handle_pass_command(
+            &self.player_port,
             self.session_id,
-            ship_id,
-            berth_id,
-            self.ai_port.player_id,
-        ) {
-            Ok(events) => {
-                self.apply_ai_events(events);
-                true
+            self.player_port.player_id,
+        );
+
+        self.record_player_action(events);
+        self.action_points -= 1;
+
+        Ok(())
+    }
+
+    /// Take back the player's last dock, crane (re/un)assignment, pass or
+    /// emergency crane of this turn, whole: every event of that action is
+    /// reverted and its action point or emergency crane refunded once
+    pub fn undo_last_action(&mut self) -> Result<(), String> {
+        let &(version, count) = self
+            .player_actions
+            .last()
+            .ok_or("Nothing to undo in the current turn")?;
+        if version != self.player_port.version() {
+            return Err("The last action can no longer be undone".to_string());
+        }
+        let undone = self.player_port.undo_last_events(count)?;
+        self.player_actions.pop();
+
+        match undone.as_slice() {
+            [DomainEvent::EmergencyCraneDeployed { crane_id, .. }] => {
+                let crane_id = *crane_id;
+                self.emergency_cranes.retain(|id| *id != crane_id);
+                self.emergency_cranes_left += 1;
+            }
+            _ => {
+                self.action_points =
+                    (self.action_points + 1).min(self.rules.action_points_per_turn);
             }
-            Err(_) => false,
         }
+        Ok(())
     }
 
-    fn ai_assign_crane(&mut self, crane_id: CraneId, ship_id: ShipId) -> bool {
-        match handle_assign_crane_command(
-            &self.ai_port,
-            self.session_id,
-            crane_id,
-            ship_id,
-            self.ai_port.player_id,
-        ) {
-            Ok(events) => {
-                self.apply_ai_events(events);
-                true
-            }
-            Err(_) => false,
+    /// Record the events of one player action on the player port, as a unit
+    /// for `undo_last_action`
+    fn record_player_action(&mut self, events: Vec<DomainEvent>) {
+        let count = events.len();
+        for event in events {
+            self.player_port.record_event(event);
         }
+        self.player_actions
+            .push((self.player_port.version(), count));
     }
 
-    fn ai_fill_open_berths_and_assign_cranes(&mut self) {
-        loop {
-            let waiting_ships = self.ai_port.waiting_ships();
-            let free_berths = self.ai_port.free_berths();
+    /// Actions the player can still take this turn
+    pub fn action_points(&self) -> u32 {
+        self.action_points
+    }
 
-            if waiting_ships.is_empty() || free_berths.is_empty() {
-                break;
-            }
+    /// Reminder for docked player ships left without a crane (None if all are worked)
+    pub fn idle_docked_warning(&self) -> Option<String> {
+        match self.player_port.idle_docked_ships().len() {
+            0 => None,
+            1 => Some("1 docked ship has no crane assigned.".to_string()),
+            n => Some(format!("{} docked ships have no crane assigned.", n)),
+        }
+    }
 
-            let ship_id = waiting_ships[0].id;
-            let berth_id = free_berths[0].id;
+    /// Emergency cranes the player can still deploy this game
+    pub fn emergency_cranes_left(&self) -> u32 {
+        self.emergency_cranes_left
+    }
 
-            if !self.ai_dock_ship(ship_id, berth_id) {
-                break;
-            }
+    /// Put an extra crane on a docked ship until the end of the turn.
+    /// Free of action points and penalties, but the pool is limited.
+    pub fn deploy_emergency_crane(&mut self, ship_id: ShipId) -> Result<CraneId, String> {
+        if self.emergency_cranes_left == 0 {
+            return Err("No emergency cranes left".to_string());
+        }
+        let crane_id = self
+            .player_port
+            .deploy_emergency_crane(ship_id, DEFAULT_CRANE_SPEED)?;
 
-            if let Some(crane) = self.ai_port.free_cranes().first() {
-                self.ai_assign_crane(crane.id, ship_id);
-            }
+        self.emergency_cranes.push(crane_id);
+        self.emergency_cranes_left -= 1;
+        self.player_actions.push((self.player_port.version(), 1));
+        Ok(crane_id)
+    }
+
+    fn remove_emergency_cranes(&mut self) {
+        for crane_id in self.emergency_cranes.drain(..) {
+            self.player_port.withdraw_emergency_crane(crane_id);
         }
+    }
 
-        loop {
-            let free_crane = match self.ai_port.free_cranes().first() {
-                Some(crane) => crane.id,
-                None => break,
-            };
+    /// `ProcessContainers` is free of action points, so a port may only be
+    /// unloaded by command once per turn
+    fn ensure_not_processed(&self, player_id: PlayerId) -> Result<(), String> {
+        if self.processed_by_command.contains(&player_id) {
+            return Err(format!(
+                "Containers of {} were already processed this turn",
+                player_id
+            ));
+        }
+        Ok(())
+    }
+
+    fn ensure_action_points(&self) -> Result<(), String> {
+        self.ensure_action_points_of(self.player_port.player_id)
+    }
+
+    fn ensure_action_points_of(&self, player_id: PlayerId) -> Result<(), String> {
+        let left = if player_id == self.player_port.player_id {
+            self.action_points
+        } else if player_id == self.ai_port.player_id {
+            self.ai_action_points
+        } else {
+            self.extra_action_points
+                .get(&player_id)
+                .copied()
+                .unwrap_or_default()
+        };
+        if left == 0 {
+            return Err(format!(
+                "Out of actions: {} actions per turn already used",
+                self.rules.action_points_per_turn
+            ));
+        }
+        Ok(())
+    }
+
+    /// Action budget of the side playing `player_id`
+    fn action_points_mut(&mut self, player_id: PlayerId) -> Option<&mut u32> {
+        if player_id == self.player_port.player_id {
+            Some(&mut self.action_points)
+        } else if player_id == self.ai_port.player_id {
+            Some(&mut self.ai_action_points)
+        } else {
+            self.extra_action_points.get_mut(&player_id)
+        }
+    }
+
+    fn reset_action_points(&mut self) {
+        let points = self.rules.action_points_per_turn;
+        self.action_points = points;
+        self.ai_action_points = points;
+        self.extra_action_points
+            .values_mut()
+            .for_each(|left| *left = points);
+    }
+
+    /// Move each idle player crane to the docked ship with the most remaining
+    /// containers that has no crane yet. Returns the assignments made.
+    pub fn auto_assign_idle_cranes(&mut self) -> Vec<(CraneId, ShipId)> {
+        let mut assignments = Vec::new();
 
+        while let Some(crane_id) = self.player_port.free_cranes().first().map(|crane| crane.id) {
             let target_ship = match self
-                .ai_port
+                .player_port
                 .docked_ships()
                 .into_iter()
-                .find(|ship| ship.assigned_cranes.is_empty())
+                .filter(|ship| ship.assigned_cranes.is_empty() && ship.containers_remaining > 0)
+                .max_by_key(|ship| (ship.containers_remaining, std::cmp::Reverse(ship.id.0)))
             {
                 Some(ship) => ship.id,
                 None => break,
             };
 
-            if !self.ai_assign_crane(free_crane, target_ship) {
+            if self.player_assign_crane(crane_id, target_ship).is_err() {
                 break;
             }
+            assignments.push((crane_id, target_ship));
         }
+
+        assignments
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Process containers for all docked ships with assigned cranes
+    pub fn process_containers(&mut self) {
+        let session_id = self.session_id;
+        let batch_size = self.rules.container_batch_size;
+        let processing_policy = self.rules.processing_policy;
+        let efficiencies: Vec<f64> = self
+            .active_ports()
+            .map(|port| self.crane_efficiency(port.player_id))
+            .collect();
 
-    #[test]
-    fn test_game_session_creation() {
-        let player_id = PlayerId::new();
-        let ai_id = PlayerId::new();
+        for (port, efficiency) in self.active_ports_mut().zip(efficiencies) {
+            // Cranes the player left idle this turn cost points before unloading
+            port.charge_idle_cranes();
+            let events = handle_process_containers_with_policy(
+                port,
+                session_id,
+                efficiency,
+                batch_size,
+                processing_policy,
+            );
+            for event in events {
+                port.record_event(event);
+            }
+            port.tick_crane_wear();
+        }
 
-        let session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+        self.end_game_if_over();
+    }
 
-        assert_eq!(session.mode, GameMode::VersusAI);
-        assert_eq!(session.current_turn, 0);
+    /// Weather modifier on the cranes of `player_id` (extra seats share the player's)
+    fn crane_efficiency(&self, player_id: PlayerId) -> f64 {
+        if player_id == self.ai_port.player_id {
+            self.ai_crane_efficiency_modifier
+        } else {
+            self.crane_efficiency_modifier
+        }
     }
 
-    #[test]
-    fn test_spawn_ships() {
-        let player_id = PlayerId::new();
-        let ai_id = PlayerId::new();
+    /// AI takes its turn using MCTS
+    /// Re-searches from the real AI port after each applied action, up to
+    /// `max_actions_per_turn` and the action budget, and returns how many
+    /// MCTS actions were applied
+    pub fn ai_take_turn(&mut self) -> usize {
+        if !self.ai_enabled {
+            return 0;
+        }
+        self.ai_search_and_play(self.ai_target())
+    }
+
+    /// Port the AI plays on: its own, or in co-op the shared player port, so
+    /// its events there carry the player's id
+    fn ai_target(&self) -> PlayerId {
+        if self.mode == GameMode::Coop {
+            self.player_port.player_id
+        } else {
+            self.ai_port.player_id
+        }
+    }
+
+    fn target_port(&self, target: PlayerId) -> &Port {
+        self.port(target)
+            .expect("the AI plays a port of the session")
+    }
+
+    fn target_port_mut(&mut self, target: PlayerId) -> &mut Port {
+        self.port_mut(target)
+            .expect("the AI plays a port of the session")
+    }
+
+    fn ai_search_and_play(&mut self, target: PlayerId) -> usize {
+        let max_actions = self.mcts_engine.config().max_actions_per_turn.max(1);
+        let mut applied_actions = 0;
+
+        for _ in 0..max_actions {
+            // The AI plays under the same action budget as the player
+            if self.ai_action_points == 0 {
+                break;
+            }
+
+            // Get best action from MCTS, leaving a reasoning trail in the event stream
+            let started = DomainEvent::MCTSSearchStarted {
+                metadata: EventMetadata::new(
+                    self.session_id,
+                    self.target_port(target).version() + 1,
+                ),
+                player: target,
+                num_simulations: self.mcts_engine.config().num_simulations,
+            };
+            self.target_port_mut(target).record_event(started);
+            self.mcts_engine.set_turn(self.current_turn);
+            let best_action = if target == self.player_port.player_id {
+                self.mcts_engine.search(&self.player_port)
+            } else {
+                self.mcts_engine.search(&self.ai_port)
+            };
+            self.record_search_completed(target, best_action.as_ref());
+
+            let Some(action) = best_action else {
+                break;
+            };
+
+            // Stop as soon as nothing beneficial is left to do
+            if !self.ai_apply_action(target, &action) {
+                break;
+            }
+            applied_actions += 1;
+        }
+
+        self.ai_fill_open_berths_and_assign_cranes(target);
+
+        applied_actions
+    }
+
+    /// Let the MCTS engine play the player port (AI-vs-AI games)
+    /// Same search and fill logic as `ai_take_turn`, applied to the other side
+    pub fn player_take_ai_turn(&mut self) -> usize {
+        self.swap_sides();
+        let applied_actions = self.ai_take_turn();
+        self.swap_sides();
+        applied_actions
+    }
+
+    fn swap_sides(&mut self) {
+        std::mem::swap(&mut self.player_port, &mut self.ai_port);
+        std::mem::swap(&mut self.action_points, &mut self.ai_action_points);
+    }
+
+    /// Check if game is over (either port won, overflowed, or turn limit)
+    pub fn is_game_over(&self) -> bool {
+        self.game_outcome().is_some()
+    }
+
+    /// Outcome of the game, None while it is still running
+    /// All ports are checked symmetrically, in this order:
+    /// 1. score above the win score wins (several: higher calculated score wins)
+    /// 2. too many waiting ships loses (the best other port wins; all of them:
+    ///    higher calculated score wins)
+    /// 3. turn limit: higher calculated score wins
+    /// 4. stalemate: nothing unloaded for `STALEMATE_TURNS` turns and no
+    ///    port can dock or assign anything, a tie
+    ///
+    /// With the AI disabled its idle port takes no part: it neither ends the
+    /// game nor wins it, and a lone port that overflows ends it with no winner.
+    pub fn game_outcome(&self) -> Option<GameOutcome> {
+        if self.mode == GameMode::Coop {
+            return self.coop_outcome();
+        }
+
+        // Conditions de fin de jeu :
+        // 1. Score suffisamment élevé (victoire)
+        let winners: Vec<&Port> = self
+            .active_ports()
+            .filter(|port| self.score(port) > WIN_SCORE)
+            .collect();
+        if !winners.is_empty() {
+            return Some(GameOutcome {
+                winner: self.leader_among(winners),
+                reason: GameOverReason::ScoreTarget,
+            });
+        }
+
+        // 2. Trop de navires en attente (défaite)
+        let overflows = |port: &&Port| port.waiting_ships().len() > MAX_WAITING_SHIPS;
+        if self.active_ports().any(|port| overflows(&port)) {
+            let others: Vec<&Port> = self
+                .active_ports()
+                .filter(|port| !overflows(port))
+                .collect();
+            let winner = if !others.is_empty() {
+                self.leader_among(others)
+            } else if self.active_ports().count() > 1 {
+                self.leader_by_score()
+            } else {
+                None // A lone port that overflows has lost, nobody wins
+            };
+            return Some(GameOutcome {
+                winner,
+                reason: GameOverReason::WaitingOverflow,
+            });
+        }
+
+        // 3. Durée maximum atteinte
+        if self.current_turn >= self.rules.max_turns {
+            return Some(GameOutcome {
+                winner: self.leader_by_score(),
+                reason: GameOverReason::TurnLimit,
+            });
+        }
+
+        // 4. Plus aucune progression possible
+        if self.idle_turns >= STALEMATE_TURNS
+            && !self
+                .active_ports()
+                .any(|port| Self::has_progress_move(port))
+        {
+            return Some(GameOutcome {
+                winner: None,
+                reason: GameOverReason::Stalemate,
+            });
+        }
+
+        None
+    }
+
+    /// Co-op: the shared port wins by reaching `coop_target_score`; an
+    /// overflow, the turn limit or a stalemate ends the game with no winner
+    fn coop_outcome(&self) -> Option<GameOutcome> {
+        let port = &self.player_port;
+        let (winner, reason) = if self.score(port) >= self.rules.coop_target_score {
+            (Some(port.player_id), GameOverReason::ScoreTarget)
+        } else if port.waiting_ships().len() > MAX_WAITING_SHIPS {
+            (None, GameOverReason::WaitingOverflow)
+        } else if self.current_turn >= self.rules.max_turns {
+            (None, GameOverReason::TurnLimit)
+        } else if self.idle_turns >= STALEMATE_TURNS && !Self::has_progress_move(port) {
+            (None, GameOverReason::Stalemate)
+        } else {
+            return None;
+        };
+        Some(GameOutcome { winner, reason })
+    }
+
+    /// Who won, if anyone yet
+    /// Equal calculated scores are always a tie, there is no secondary tie-breaker.
+    pub fn outcome(&self) -> GameResult {
+        match self.game_outcome() {
+            None => GameResult::InProgress,
+            Some(GameOutcome { winner: None, .. }) => GameResult::Tie,
+            Some(GameOutcome {
+                winner: Some(winner),
+                ..
+            }) if winner == self.player_port.player_id => GameResult::PlayerWins,
+            Some(_) => GameResult::AiWins,
+        }
+    }
+
+    /// Get winner (if game is over): "player", "ai" or "tie"
+    pub fn get_winner(&self) -> Option<&str> {
+        self.outcome().winner_label()
+    }
+
+    /// Port in the game with the highest calculated score (None on equality
+    /// at the top)
+    fn leader_by_score(&self) -> Option<PlayerId> {
+        self.leader_among(self.active_ports())
+    }
+
+    fn leader_among<'a>(&self, ports: impl IntoIterator<Item = &'a Port>) -> Option<PlayerId> {
+        let mut scores: Vec<_> = ports
+            .into_iter()
+            .map(|port| (self.score(port), port.player_id))
+            .collect();
+        scores.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+
+        match scores.as_slice() {
+            [(best, leader), (second, _), ..] if best > second => Some(*leader),
+            [(_, leader)] => Some(*leader),
+            _ => None,
+        }
+    }
+
+    /// Emit `GameEnded` the first time the game-over condition is reached
+    fn end_game_if_over(&mut self) {
+        if self.game_ended {
+            return;
+        }
+        let Some(GameOutcome { winner, .. }) = self.game_outcome() else {
+            return;
+        };
+        self.game_ended = true;
+
+        let event = DomainEvent::GameEnded {
+            metadata: EventMetadata::new(self.session_id, self.current_turn as u64),
+            winner,
+            player_score: self.score(&self.player_port),
+            ai_score: self.score(&self.ai_port),
+        };
+
+        // Flush pending port events first so GameEnded closes the stream
+        self.commit_events();
+        self.append_to_store(vec![event]);
+
+        if let Some(callback) = self.game_over_callback.take() {
+            callback(self);
+        }
+    }
+
+    /// Run `callback` once, right after `GameEnded` is recorded (immediately
+    /// if the game is already over). Replaces any callback not yet run.
+    pub fn on_game_over(&mut self, callback: GameOverCallback<E>) {
+        if self.game_ended {
+            callback(self);
+        } else {
+            self.game_over_callback = Some(callback);
+        }
+    }
+
+    /// Process random events
+    /// Rush hours are harbor-wide: rolled once per turn, their arrivals are
+    /// mirrored to keep the match fair. Then each port rolls its own events
+    /// (storms, breakdowns...) so both players face independent conditions.
+    /// Returns the events felt by the player.
+    pub fn process_random_events(&mut self) -> Vec<RandomEvent> {
+        // Update active events
+        self.active_events.retain_mut(|active| !active.tick());
+        self.ai_active_events.retain_mut(|active| !active.tick());
+
+        // Apply active event effects
+        self.crane_efficiency_modifier = efficiency_modifier(&self.active_events);
+        self.ai_crane_efficiency_modifier = efficiency_modifier(&self.ai_active_events);
+
+        // Generate new events
+        let mut player_events = Vec::new();
+        if !self.events_enabled {
+            return player_events;
+        }
+        let session_id = self.session_id;
+
+        // Harbor-wide events: one roll per turn, recorded by the player port
+        // and mirrored, like arrivals
+        if let Some(event) = self.event_generator.generate_harbor_event() {
+            let triggered = DomainEvent::RandomEventTriggered {
+                metadata: EventMetadata::new(session_id, self.player_port.version() + 1),
+                description: event.description(),
+                player: None,
+            };
+            self.player_port.record_event(triggered.clone());
+            self.mirror_shared_event(&triggered);
+            if let RandomEvent::RushHour { extra_ships } = event {
+                self.spawn_ships(extra_ships);
+            }
+            player_events.push(event);
+        }
+
+        // Then one roll per port, recorded by the port it hits
+        let players = [self.player_port.player_id, self.ai_port.player_id];
+        let rolling = if self.ai_has_own_port() { 2 } else { 1 };
+        for player in players.into_iter().take(rolling) {
+            let Some(event) = self.event_generator.generate_port_event() else {
+                continue;
+            };
+            let is_player = player == self.player_port.player_id;
+            let recorder = if is_player {
+                &mut self.player_port
+            } else {
+                &mut self.ai_port
+            };
+            recorder.record_event(DomainEvent::RandomEventTriggered {
+                metadata: EventMetadata::new(session_id, recorder.version() + 1),
+                description: event.description(),
+                player: Some(player),
+            });
+
+            match &event {
+                RandomEvent::CustomsInspection { .. } => {
+                    // Instant effect - handled in display
+                }
+                _ if is_player => self.active_events.push(ActiveEvent::new(event.clone())),
+                _ => self.ai_active_events.push(ActiveEvent::new(event.clone())),
+            }
+
+            if is_player {
+                player_events.push(event);
+            }
+        }
+
+        player_events
+    }
+
+    /// Turn new random events on or off without replacing the generator
+    pub fn set_events_enabled(&mut self, enabled: bool) {
+        self.events_enabled = enabled;
+    }
+
+    pub fn events_enabled(&self) -> bool {
+        self.events_enabled
+    }
+
+    /// Practice mode when false: the AI no longer plays, receives ships or
+    /// weather, and its idle port cannot end the game
+    pub fn set_ai_enabled(&mut self, enabled: bool) {
+        self.ai_enabled = enabled;
+    }
+
+    pub fn ai_enabled(&self) -> bool {
+        self.ai_enabled
+    }
+
+    /// Whether the AI plays a port of its own (not in practice or co-op)
+    fn ai_has_own_port(&self) -> bool {
+        self.ai_enabled && self.mode != GameMode::Coop
+    }
+
+    /// Get description of active effects
+    pub fn get_active_effects_description(&self) -> Vec<String> {
+        self.active_events
+            .iter()
+            .map(|active| {
+                format!(
+                    "{} ({}  turns left)",
+                    active.event.description(),
+                    active.turns_remaining
+                )
+            })
+            .collect()
+    }
+
+    /// Free completed ships and their assigned cranes
+    pub fn free_completed_ships(&mut self) {
+        let ports = [&mut self.player_port, &mut self.ai_port]
+            .into_iter()
+            .chain(&mut self.extra_ports);
+        for port in ports {
+            // Ne récupérer que les navires qui sont complètement déchargés
+            let completed_ships: Vec<_> = port
+                .ships
+                .iter()
+                .filter(|(_, ship)| ship.is_docked() && ship.containers_remaining == 0)
+                .map(|(id, ship)| (*id, ship.docked_at.unwrap(), ship.assigned_cranes.clone()))
+                .collect();
+
+            for (ship_id, berth_id, crane_ids) in completed_ships {
+                // Libérer les grues uniquement pour les navires terminés
+                for crane_id in crane_ids {
+                    port.free_crane(crane_id);
+                }
+                // Puis libérer le quai (le navire est retiré du port)
+                port.undock_ship(ship_id, berth_id);
+            }
+        }
+    }
+
+    /// End turn with proper sequence
+    pub fn end_turn(&mut self) {
+        // 0. Close the current turn in the event stream
+        self.commit_events();
+        let event = DomainEvent::TurnEnded {
+            metadata: EventMetadata::new(self.session_id, self.current_turn as u64),
+            turn_number: self.current_turn,
+            player: self.current_player,
+        };
+        self.append_to_store(vec![event]);
+
+        // 1. Process containers one last time
+        self.process_containers();
+
+        // 2. Free completed ships and their assigned cranes
+        self.free_completed_ships();
+
+        // 2bis. Emergency cranes only last for the turn they were deployed
+        self.remove_emergency_cranes();
+
+        // 3. Process random events for next turn
+        self.process_random_events();
+
+        // 4. Let AI take its turn
+        self.ai_take_turn();
+
+        // 4bis. AI fills any remaining free berths and assign cranes
+        self.ai_fill_open_berths_and_assign_cranes(self.ai_target());
+
+        // 5. Start new turn
+        self.start_turn();
+    }
+
+    fn apply_ai_events(&mut self, target: PlayerId, events: Vec<DomainEvent>) {
+        for event in events {
+            self.target_port_mut(target).record_event(event);
+        }
+    }
+
+    fn record_search_completed(&mut self, target: PlayerId, action: Option<&MCTSAction>) {
+        let stats = self.mcts_engine.get_statistics();
+        let best_action =
+            serde_json::to_string(action.unwrap_or(&MCTSAction::Pass)).unwrap_or_default();
+        let completed = DomainEvent::MCTSSearchCompleted {
+            metadata: EventMetadata::new(self.session_id, self.target_port(target).version() + 1),
+            player: target,
+            best_action,
+            confidence: stats.confidence,
+            simulations_performed: stats.simulations_performed,
+        };
+        self.target_port_mut(target).record_event(completed);
+    }
+
+    /// Apply an MCTS action to the `target` port, returns false if nothing changed
+    fn ai_apply_action(&mut self, target: PlayerId, action: &MCTSAction) -> bool {
+        match *action {
+            MCTSAction::DockShip { ship_id, berth_id } => {
+                self.ai_dock_ship(target, ship_id, berth_id)
+            }
+            MCTSAction::AssignCrane { crane_id, ship_id } => {
+                self.ai_assign_crane(target, crane_id, ship_id)
+            }
+            MCTSAction::UnassignCrane { crane_id } => {
+                let busy = self
+                    .target_port(target)
+                    .cranes
+                    .get(&crane_id)
+                    .is_some_and(|crane| !crane.is_free());
+                if !busy || self.ai_action_points == 0 {
+                    return false;
+                }
+                // Utilise la logique de domaine directe: libère la grue si affectée
+                self.target_port_mut(target).free_crane(crane_id);
+                self.ai_action_points -= 1;
+                true
+            }
+            MCTSAction::Pass => false,
+        }
+    }
+
+    fn ai_dock_ship(&mut self, target: PlayerId, ship_id: ShipId, berth_id: BerthId) -> bool {
+        if self.ai_action_points == 0 {
+            return false;
+        }
+        match handle_dock_ship_command(
+            self.target_port(target),
+            self.session_id,
+            ship_id,
+            berth_id,
+            target,
+        ) {
+            Ok(events) => {
+                self.apply_ai_events(target, events);
+                self.ai_action_points -= 1;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn ai_assign_crane(&mut self, target: PlayerId, crane_id: CraneId, ship_id: ShipId) -> bool {
+        if self.ai_action_points == 0 {
+            return false;
+        }
+        match handle_assign_crane_command(
+            self.target_port(target),
+            self.session_id,
+            crane_id,
+            ship_id,
+            target,
+        ) {
+            Ok(events) => {
+                self.apply_ai_events(target, events);
+                self.ai_action_points -= 1;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn ai_fill_open_berths_and_assign_cranes(&mut self, target: PlayerId) {
+        if !self.ai_enabled {
+            return;
+        }
+        loop {
+            let dockable_ship = self
+                .target_port(target)
+                .waiting_ships()
+                .into_iter()
+                .find(|ship| self.target_port(target).tide_allows(ship))
+                .map(|ship| ship.id);
+            let free_berth = self
+                .target_port(target)
+                .free_berths()
+                .first()
+                .map(|berth| berth.id);
+
+            let (Some(ship_id), Some(berth_id)) = (dockable_ship, free_berth) else {
+                break;
+            };
+
+            if !self.ai_dock_ship(target, ship_id, berth_id) {
+                break;
+            }
+
+            if let Some(crane_id) = self
+                .target_port(target)
+                .free_cranes()
+                .first()
+                .map(|crane| crane.id)
+            {
+                self.ai_assign_crane(target, crane_id, ship_id);
+            }
+        }
+
+        while let Some(free_crane) = self
+            .target_port_mut(target)
+            .free_cranes()
+            .first()
+            .map(|crane| crane.id)
+        {
+            let target_ship = match self
+                .target_port(target)
+                .docked_ships()
+                .into_iter()
+                .find(|ship| ship.assigned_cranes.is_empty())
+            {
+                Some(ship) => ship.id,
+                None => break,
+            };
+
+            if !self.ai_assign_crane(target, free_crane, target_ship) {
+                break;
+            }
+        }
+    }
+}
+
+/// Combined crane efficiency of a port's active weather effects
+fn efficiency_modifier(active_events: &[ActiveEvent]) -> f64 {
+    active_events
+        .iter()
+        .fold(1.0, |modifier, active| match &active.event {
+            RandomEvent::Storm {
+                efficiency_penalty, ..
+            } => modifier * (1.0 - efficiency_penalty),
+            RandomEvent::GoodWeather {
+                efficiency_bonus, ..
+            } => modifier * (1.0 + efficiency_bonus),
+            _ => modifier,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test double that delegates to the in-memory store and captures each append
+    #[derive(Default)]
+    struct RecordingEventStore {
+        inner: InMemoryEventStore,
+        appends: Vec<Vec<DomainEvent>>,
+    }
+
+    impl EventStore for RecordingEventStore {
+        fn append(&mut self, aggregate_id: Uuid, events: Vec<DomainEvent>) -> Result<(), String> {
+            self.appends.push(events.clone());
+            self.inner.append(aggregate_id, events)
+        }
+
+        fn load(&self, aggregate_id: Uuid) -> Result<Vec<DomainEvent>, String> {
+            self.inner.load(aggregate_id)
+        }
+
+        fn all_events(&self) -> Result<Vec<DomainEvent>, String> {
+            self.inner.all_events()
+        }
+
+        fn event_count(&self, aggregate_id: Uuid) -> usize {
+            self.inner.event_count(aggregate_id)
+        }
+
+        fn aggregate_ids(&self) -> Vec<Uuid> {
+            self.inner.aggregate_ids()
+        }
+    }
+
+    #[test]
+    fn test_game_session_creation() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+
+        let session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+
+        assert_eq!(session.mode, GameMode::VersusAI);
+        assert_eq!(session.current_turn, 0);
+    }
+
+    #[test]
+    fn test_session_with_custom_event_store() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+
+        let mut session = GameSession::with_event_store(
+            GameMode::VersusAI,
+            player_id,
+            ai_id,
+            RecordingEventStore::default(),
+        );
+
+        // GameStarted waits for the first append, once the layout is final
+        assert!(session.event_store.appends.is_empty());
+        session.start_turn();
+        assert_eq!(session.event_store.appends.len(), 1);
+
+        let events = session.event_store.load(session.session_id).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type(), "GameStarted");
+        assert_eq!(events[1].event_type(), "TurnStarted");
+    }
+
+    #[test]
+    fn test_port_events_are_committed_in_batch() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::with_event_store(
+            GameMode::VersusAI,
+            player_id,
+            ai_id,
+            RecordingEventStore::default(),
+        );
+        session.spawn_ships(1);
+        session.commit_events();
+        let appends_before = session.event_store.appends.len();
+
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ShipId::new(0))
+            .unwrap();
+
+        let uncommitted = session.player_port.uncommitted_events();
+        assert_eq!(uncommitted.len(), 2);
+        assert_eq!(uncommitted[0].event_type(), "ShipDocked");
+        assert_eq!(uncommitted[1].event_type(), "CraneAssigned");
+        assert_eq!(session.event_store.appends.len(), appends_before);
+
+        session.commit_events();
+
+        assert!(session.player_port.uncommitted_events().is_empty());
+        assert_eq!(session.event_store.appends.len(), appends_before + 1);
+    }
+
+    #[test]
+    fn test_dock_and_assign_append_exact_event_sequence() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::with_event_store(
+            GameMode::VersusAI,
+            player_id,
+            ai_id,
+            RecordingEventStore::default(),
+        );
+        session.spawn_ships(1);
+        session.commit_events();
+
+        let ship_id = ShipId::new(0);
+        session.player_dock_ship(ship_id, BerthId::new(1)).unwrap();
+        session
+            .player_assign_crane(CraneId::new(1), ship_id)
+            .unwrap();
+        session.commit_events();
+
+        let batch = session.event_store.appends.last().unwrap();
+        assert_eq!(batch.len(), 2);
+        match &batch[0] {
+            DomainEvent::ShipDocked {
+                ship_id: docked,
+                berth_id,
+                player,
+                ..
+            } => {
+                assert_eq!(*docked, ship_id);
+                assert_eq!(*berth_id, BerthId::new(1));
+                assert_eq!(*player, player_id);
+            }
+            other => panic!("expected ShipDocked, got {:?}", other),
+        }
+        match &batch[1] {
+            DomainEvent::CraneAssigned {
+                crane_id,
+                ship_id: assigned,
+                player,
+                ..
+            } => {
+                assert_eq!(*crane_id, CraneId::new(1));
+                assert_eq!(*assigned, ship_id);
+                assert_eq!(*player, player_id);
+            }
+            other => panic!("expected CraneAssigned, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_session_with_boxed_event_store() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let store: Box<dyn EventStore> = Box::new(InMemoryEventStore::new());
+        let mut session =
+            GameSession::with_event_store(GameMode::VersusAI, player_id, ai_id, store);
+
+        session.spawn_ships(1);
+        session.commit_events();
+
+        assert_eq!(session.event_store.event_count(session.session_id), 2);
+    }
+
+    #[test]
+    fn test_game_ended_emitted_once() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+        session.event_generator = EventGenerator::new(0.0);
+
+        session.spawn_ships(1);
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ShipId::new(0))
+            .unwrap();
+        session.process_containers();
+
+        // Reach the turn-30 limit, then keep going
+        while !session.is_game_over() {
+            session.start_turn();
+        }
+        let final_scores = (
+            session.player_port.calculate_score(),
+            session.ai_port.calculate_score(),
+        );
+        session.start_turn();
+        session.start_turn();
+
+        let json = session.export_replay().unwrap();
+        let events = ReplayEnvelope::from_json(&json).unwrap().events;
+        let game_ended: Vec<_> = events
+            .iter()
+            .filter(|event| event.event_type() == "GameEnded")
+            .collect();
+
+        assert_eq!(game_ended.len(), 1);
+        match game_ended[0] {
+            DomainEvent::GameEnded {
+                winner,
+                player_score,
+                ai_score,
+                ..
+            } => {
+                assert_eq!(*winner, Some(player_id));
+                assert_eq!((*player_score, *ai_score), final_scores);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_ai_takes_several_actions_per_turn() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session =
+            GameSession::new(GameMode::VersusAI, player_id, ai_id).with_mcts_config(MCTSConfig {
+                num_simulations: 50,
+                max_actions_per_turn: 3,
+                ..MCTSConfig::default()
+            });
+        session.event_generator = EventGenerator::new(0.0);
+
+        session.spawn_ships(3);
+        let applied = session.ai_take_turn();
+
+        assert!(applied >= 2, "AI applied only {} actions", applied);
+        assert!(!session.ai_port.docked_ships().is_empty());
+        assert!(session
+            .ai_port
+            .cranes
+            .values()
+            .any(|crane| !crane.is_free()));
+    }
+
+    #[test]
+    fn test_session_with_custom_mcts_config() {
+        let config = MCTSConfig {
+            num_simulations: 42,
+            exploration_constant: 0.7,
+            max_depth: 5,
+            max_actions_per_turn: 4,
+            reward: RewardConfig {
+                idle_crane_weight: 3.0,
+                ..RewardConfig::default()
+            },
+            adversarial: true,
+            max_nodes: 10_000,
+            use_rave: false,
+            rave_equivalence: 300.0,
+            exploration_schedule: ExplorationSchedule::Constant,
+            rollout_noise: 5.0,
+            use_priors: false,
+        };
+
+        let session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_mcts_config(config.clone());
+
+        let engine_config = session.mcts_engine.config();
+        assert_eq!(engine_config.num_simulations, 42);
+        assert_eq!(engine_config.exploration_constant, 0.7);
+        assert_eq!(engine_config.max_depth, 5);
+        assert_eq!(engine_config.max_actions_per_turn, 4);
+        assert_eq!(engine_config.reward, config.reward);
+        assert!(engine_config.adversarial);
+    }
+
+    #[test]
+    fn test_spawn_ships() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+        session.spawn_ships(2);
+
+        assert_eq!(session.player_port.ships.len(), 2);
+        assert_eq!(session.ai_port.ships.len(), 2);
+    }
+
+    #[test]
+    fn test_event_export() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+
+        let session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+        let json = session.export_replay();
+
+        assert!(json.is_ok());
+    }
+
+    #[test]
+    fn test_free_completed_ships() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+
+        // 1. Spawn a ship
+        session.spawn_ships(1);
+        let ship_id = ShipId::new(0);
+        let berth_id = BerthId::new(0);
+
+        // 2. Dock it and assign a crane
+        session.player_dock_ship(ship_id, berth_id).unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ship_id)
+            .unwrap();
+
+        // 3. Empty its containers
+        let ship = session.player_port.ships.get_mut(&ship_id).unwrap();
+        ship.containers_remaining = 0;
+
+        // 4. Call free_completed_ships
+        session.free_completed_ships();
+
+        // 5. Verify:
+        // - Ship should be gone
+        assert!(!session.player_port.ships.contains_key(&ship_id));
+        // - Berth should be free
+        let berth = session.player_port.berths.get(&berth_id).unwrap();
+        assert!(berth.is_free());
+        // - Crane should be unassigned
+        let crane = session.player_port.cranes.get(&CraneId::new(0)).unwrap();
+        assert!(crane.is_free());
+    }
+
+    #[test]
+    fn test_ships_completed_survives_ship_removal() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+
+        session.spawn_ships(2);
+        for (ship, berth) in [(0, 0), (1, 1)] {
+            session
+                .player_dock_ship(ShipId::new(ship), BerthId::new(berth))
+                .unwrap();
+            let ship = session
+                .player_port
+                .ships
+                .get_mut(&ShipId::new(ship))
+                .unwrap();
+            ship.containers_remaining = 0;
+        }
+        session.commit_events();
+        session.free_completed_ships();
+
+        assert!(session.player_port.ships.is_empty());
+        assert_eq!(session.ships_completed(player_id), 2);
+        assert_eq!(session.ships_completed(ai_id), 0);
+
+        // Still counted once the undock events are in the store
+        session.commit_events();
+        let stats = session.comparison_stats();
+        assert_eq!(stats.player_ships_processed, 2);
+        assert_eq!(stats.ai_ships_processed, 0);
+    }
+
+    #[test]
+    fn test_emergency_crane_lasts_one_turn() {
+        let player_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 50,
+                max_containers: 50,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.set_events_enabled(false);
+
+        let ship_id = ShipId::new(0);
+        session.spawn_ships(1);
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+        session
+            .player_assign_crane(CraneId::new(1), ship_id)
+            .unwrap();
+
+        let crane_id = session.deploy_emergency_crane(ship_id).unwrap();
+        assert_eq!(crane_id, CraneId::new(2));
+        assert_eq!(session.action_points(), 1); // Deploying is free
+
+        // Two cranes at 10 containers each instead of one
+        session.process_containers();
+        assert_eq!(session.player_port.ships[&ship_id].containers_remaining, 30);
+
+        session.end_turn();
+        assert!(!session.player_port.cranes.contains_key(&crane_id));
+        assert!(!session.player_port.ships[&ship_id]
+            .assigned_cranes
+            .contains(&crane_id));
+    }
+
+    #[test]
+    fn test_emergency_crane_pool_runs_out() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 30,
+                max_containers: 30,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        let ship_id = ShipId::new(0);
+
+        // Only docked ships can get one
+        session.spawn_ships(1);
+        assert!(session.deploy_emergency_crane(ship_id).is_err());
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+
+        for left in (0..DEFAULT_EMERGENCY_CRANES).rev() {
+            session.deploy_emergency_crane(ship_id).unwrap();
+            assert_eq!(session.emergency_cranes_left(), left);
+        }
+
+        let result = session.deploy_emergency_crane(ship_id);
+        assert_eq!(result, Err("No emergency cranes left".to_string()));
+    }
+
+    #[test]
+    fn test_emergency_cranes_are_recorded_as_events() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 50,
+                max_containers: 50,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.set_events_enabled(false);
+        let ship_id = ShipId::new(0);
+        session.spawn_ships(1);
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+
+        // Undo takes the crane back and refunds the pool, not an action point
+        session.deploy_emergency_crane(ship_id).unwrap();
+        session.undo_last_action().unwrap();
+        assert_eq!(session.emergency_cranes_left(), DEFAULT_EMERGENCY_CRANES);
+        assert_eq!(session.action_points(), 2);
+        assert_eq!(session.player_port.cranes.len(), 2);
+
+        // A replay saved mid-turn brings the crane back, and it still leaves
+        let crane_id = session.deploy_emergency_crane(ship_id).unwrap();
+        let mut resumed =
+            GameSession::resume_from_replay(&session.export_replay().unwrap()).unwrap();
+        assert!(resumed.player_port.state_eq(&session.player_port));
+        assert_eq!(
+            resumed.emergency_cranes_left(),
+            DEFAULT_EMERGENCY_CRANES - 1
+        );
+
+        resumed.end_turn();
+        assert!(!resumed.player_port.cranes.contains_key(&crane_id));
+        let events = resumed.session_events().unwrap();
+        assert!(events.iter().any(|event| matches!(
+            event,
+            DomainEvent::EmergencyCraneRemoved { crane_id: removed, .. } if *removed == crane_id
+        )));
+        assert_eq!(
+            resumed.turn_log()[0].player_actions.last().unwrap(),
+            &format!("Deployed emergency {} on {}", crane_id, ship_id)
+        );
+    }
+
+    #[test]
+    fn test_auto_assign_idle_cranes_moves_freed_crane() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        // Enough action points for the whole setup in one turn
+        let mut session =
+            GameSession::new(GameMode::VersusAI, player_id, ai_id).with_rules(GameRules {
+                action_points_per_turn: 6,
+                ..GameRules::default()
+            });
+
+        session.spawn_ships(2);
+        let (first, second) = (ShipId::new(0), ShipId::new(1));
+
+        session.player_dock_ship(first, BerthId::new(0)).unwrap();
+        session.player_dock_ship(second, BerthId::new(1)).unwrap();
+        session.player_assign_crane(CraneId::new(0), first).unwrap();
+        session.player_assign_crane(CraneId::new(1), first).unwrap();
+
+        // First ship completes and releases both cranes
+        session
+            .player_port
+            .ships
+            .get_mut(&first)
+            .unwrap()
+            .containers_remaining = 0;
+        session.free_completed_ships();
+
+        let assignments = session.auto_assign_idle_cranes();
+
+        assert_eq!(assignments.len(), 1);
+        assert_eq!(assignments[0].1, second);
+        let ship = session.player_port.ships.get(&second).unwrap();
+        assert_eq!(ship.assigned_cranes, vec![assignments[0].0]);
+
+        // Nothing left to do on a second call
+        assert!(session.auto_assign_idle_cranes().is_empty());
+    }
+
+    #[test]
+    fn test_spawned_ship_ids_never_collide() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+
+        for _ in 0..5 {
+            session.spawn_ships(3);
+        }
+
+        assert_eq!(session.player_port.ships.len(), 15);
+        assert_eq!(session.ai_port.ships.len(), 15);
+    }
+
+    #[test]
+    fn test_spawn_config_bounds_container_counts() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let config = SpawnConfig {
+            min_containers: 12,
+            max_containers: 18,
+            ..SpawnConfig::default()
+        };
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(config)
+            .unwrap();
+
+        session.spawn_ships(50);
+
+        assert!(session
+            .player_port
+            .ships
+            .values()
+            .all(|ship| (12..=18).contains(&ship.containers)));
+    }
+
+    #[test]
+    fn test_ship_sizes_are_reproducible_with_a_seed() {
+        let config = SpawnConfig {
+            distribution: ShipSizeDistribution::MegaShips {
+                percent: 20,
+                min: 150,
+                max: 200,
+            },
+            ..SpawnConfig::default()
+        };
+        let sizes =
+            |seed| {
+                random::with_seed(seed, || {
+                    let mut session =
+                        GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+                            .with_spawn_config(config)
+                            .unwrap();
+                    session.spawn_ships(20);
+                    assert!(session.player_port.ships.values().all(|ship| {
+                        session.ai_port.ships[&ship.id].containers == ship.containers
+                    }));
+                    (0..20)
+                        .map(|id| session.player_port.ships[&ShipId::new(id)].containers)
+                        .collect::<Vec<_>>()
+                })
+            };
+
+        assert_eq!(sizes(42), sizes(42));
+    }
+
+    #[test]
+    fn test_mega_ship_distribution_spawns_large_ships() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                distribution: ShipSizeDistribution::MegaShips {
+                    percent: 30,
+                    min: 150,
+                    max: 200,
+                },
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+
+        random::with_seed(7, || session.spawn_ships(100));
+
+        let ships: Vec<_> = session.player_port.ships.values().collect();
+        assert!(ships.iter().any(|ship| ship.containers >= 150));
+        assert!(ships
+            .iter()
+            .all(|ship| (20..=50).contains(&ship.containers)
+                || (150..=200).contains(&ship.containers)));
+    }
+
+    #[test]
+    fn test_spawn_config_with_equal_bounds_is_valid() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new());
+        let config = SpawnConfig {
+            min_containers: MAX_SHIP_CONTAINERS,
+            max_containers: MAX_SHIP_CONTAINERS,
+            ..SpawnConfig::default()
+        };
+
+        assert_eq!(session.set_spawn_config(config), Ok(()));
+        session.spawn_ships(3);
+        assert!(session
+            .player_port
+            .ships
+            .values()
+            .all(|ship| ship.containers == MAX_SHIP_CONTAINERS));
+    }
+
+    #[test]
+    fn test_premium_berth_charges_its_fee() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_rules(GameRules {
+                premium_berth_fee: 35,
+                ..GameRules::default()
+            });
+        session.set_events_enabled(false);
+        let costs = |port: &Port| -> Vec<i32> {
+            port.berths
+                .values()
+                .map(|berth| berth.docking_cost)
+                .collect()
+        };
+        assert_eq!(costs(&session.player_port), vec![0, 35]);
+        assert_eq!(costs(&session.ai_port), vec![0, 35]);
+
+        session.spawn_ships(2);
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        assert_eq!(session.player_port.score, 0);
+        session
+            .player_dock_ship(ShipId::new(1), BerthId::new(1))
+            .unwrap();
+        assert_eq!(session.player_port.score, -35);
+    }
+
+    #[test]
+    fn test_spawned_ships_carry_a_cargo_mix() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 40,
+                max_containers: 40,
+                reefer_percent: 25,
+                hazmat_percent: 10,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.spawn_ships(1);
+
+        let mix = CargoMix::new(26, 10, 4);
+        for port in [&session.player_port, &session.ai_port] {
+            assert_eq!(port.ships[&ShipId::new(0)].cargo, mix);
+        }
+        assert!(session
+            .session_events()
+            .unwrap()
+            .iter()
+            .any(|event| matches!(
+                event,
+                DomainEvent::ShipArrived { cargo: Some(cargo), .. } if *cargo == mix
+            )));
+    }
+
+    #[test]
+    fn test_invalid_spawn_configs_are_rejected() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new());
+        let invalid = [
+            SpawnConfig {
+                min_containers: 40,
+                max_containers: 30,
+                ..SpawnConfig::default()
+            },
+            SpawnConfig {
+                min_containers: 0,
+                max_containers: 30,
+                ..SpawnConfig::default()
+            },
+            SpawnConfig {
+                max_containers: MAX_SHIP_CONTAINERS + 1,
+                ..SpawnConfig::default()
+            },
+            SpawnConfig {
+                distribution: ShipSizeDistribution::MegaShips {
+                    percent: 10,
+                    min: 300,
+                    max: 200,
+                },
+                ..SpawnConfig::default()
+            },
+            SpawnConfig {
+                reefer_percent: 70,
+                hazmat_percent: 40,
+                ..SpawnConfig::default()
+            },
+        ];
+
+        for config in invalid {
+            assert!(config.validate().is_err(), "{:?}", config);
+            assert!(session.set_spawn_config(config).is_err());
+            assert!(
+                GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+                    .with_spawn_config(config)
+                    .is_err()
+            );
+        }
+        assert_eq!(session.spawn_config, SpawnConfig::default());
+    }
+
+    #[test]
+    fn test_spawns_across_turns_do_not_overwrite_ships() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+
+        // Initial spawn happens before the first start_turn (turn 0)
+        session.spawn_ships(3);
+        session.spawn_ships(2);
+        session.start_turn();
+        session.spawn_ships(3);
+        session.start_turn();
+        session.spawn_ships(1);
+
+        assert_eq!(session.player_port.ships.len(), 9);
+        assert_eq!(session.ai_port.ships.len(), 9);
+    }
+
+    #[test]
+    fn test_waiting_penalty_accrues_across_turns() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+
+        session.spawn_ships(2);
+        assert_eq!(session.player_port.calculate_score(), 0);
+
+        // Nobody docks anything for a few turns
+        for _ in 0..3 {
+            session.start_turn();
+        }
+
+        assert_eq!(session.player_port.current_time, 3.0);
+        assert_eq!(session.ai_port.current_time, 3.0);
+        assert!(session.player_port.calculate_score() < 0);
+    }
+
+    #[test]
+    fn test_waiting_penalty_is_charged_each_turn() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap()
+            .with_rules(GameRules {
+                idle_crane_penalty: 0,
+                ..GameRules::default()
+            });
+        session.set_events_enabled(false);
+        session.spawn_ships(1);
+        // Normal rate only: priority ships are penalized twice as much
+        session
+            .player_port
+            .ships
+            .get_mut(&ShipId::new(0))
+            .unwrap()
+            .priority = false;
+
+        for _ in 0..3 {
+            session.start_turn();
+        }
+        assert_eq!(session.player_port.waiting_cost, 15);
+        assert_eq!(session.player_port.score, -15);
+
+        // Docking does not refund what the wait already cost
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session.start_turn();
+        assert_eq!(session.player_port.waiting_cost, 15);
+        assert_eq!(session.player_port.calculate_score(), -15);
+
+        // The charges are events, so a resumed game owes the same
+        let charged: i32 = session
+            .session_events()
+            .unwrap()
+            .iter()
+            .filter_map(|event| match event {
+                DomainEvent::WaitingPenaltyCharged { player, amount, .. }
+                    if *player == session.player_port.player_id =>
+                {
+                    Some(*amount)
+                }
+                _ => None,
+            })
+            .sum();
+        assert_eq!(charged, 15);
+        let resumed = GameSession::resume_from_replay(&session.export_replay().unwrap()).unwrap();
+        assert_eq!(resumed.player_port.waiting_cost, 15);
+        assert_eq!(resumed.player_port.score, session.player_port.score);
+    }
+
+    #[test]
+    fn test_idle_cranes_are_charged_after_the_players_moves() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap()
+            .with_rules(GameRules {
+                idle_crane_penalty: 3,
+                ..GameRules::default()
+            });
+        session.set_events_enabled(false);
+        session.set_ai_enabled(false);
+        assert_eq!(session.mcts_engine.config().reward.idle_crane_weight, 3.0);
+
+        // No docked ship: free cranes have nothing to do and cost nothing
+        session.start_turn();
+        session.process_containers();
+        assert_eq!(session.player_port.idle_crane_cost, 0);
+
+        // A docked ship with room for both cranes, only one put on it
+        session.spawn_ships(1);
+        let ship_id = ShipId::new(0);
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ship_id)
+            .unwrap();
+        session.process_containers();
+        assert_eq!(session.player_port.idle_crane_cost, 3);
+
+        // The charge is an event: a resumed game has the same score
+        session.commit_events();
+        let resumed = GameSession::resume_from_replay(&session.export_replay().unwrap()).unwrap();
+        assert_eq!(resumed.player_port.idle_crane_cost, 3);
+        assert_eq!(resumed.player_port.score, session.player_port.score);
+    }
+
+    #[test]
+    fn test_points_per_container_rule() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap()
+            .with_rules(GameRules {
+                points_per_container: 25,
+                idle_crane_penalty: 0,
+                ..GameRules::default()
+            });
+        session.set_events_enabled(false);
+        let ship_id = ShipId::new(0);
+        session.spawn_ships(1);
+        session
+            .player_port
+            .ships
+            .get_mut(&ship_id)
+            .unwrap()
+            .priority = false;
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+        session
+            .player_assign_crane(CraneId::new(1), ship_id)
+            .unwrap();
+
+        // One fresh crane unloads 10 containers
+        session.process_containers();
+
+        assert_eq!(session.player_port.ships[&ship_id].containers_remaining, 10);
+        assert_eq!(session.player_port.score, 250);
+        assert_eq!(session.player_port.calculate_score(), 250);
+        assert_eq!(session.turn_log()[0].score_delta, 250);
+    }
+
+    #[test]
+    fn test_export_throughput_csv() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                reefer_percent: 0,
+                hazmat_percent: 0,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+
+        // Turn 0: one ship docked on each side, nothing processed yet
+        session.spawn_ships(1);
+        let ship_id = ShipId::new(0);
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+        session
+            .player_assign_crane(CraneId::new(1), ship_id)
+            .unwrap();
+        let ai_id = session.ai_port.player_id;
+        assert!(session.ai_dock_ship(ai_id, ship_id, BerthId::new(0)));
+        assert!(session.ai_assign_crane(ai_id, CraneId::new(1), ship_id));
+
+        // Turns 1 and 2: 10 then 9 containers per side (crane wear), turn 3 is idle
+        session.start_turn();
+        session.process_containers();
+        session.start_turn();
+        session.process_containers();
+        session.start_turn();
+
+        let expected = "turn,player_processed,ai_processed,cumulative_score\n\
+                        0,0,0,0\n\
+                        1,10,10,100\n\
+                        2,9,9,190\n\
+                        3,0,0,190\n";
+        assert_eq!(session.export_throughput_csv(), expected);
+    }
+
+    #[test]
+    fn test_random_events_are_committed_with_the_turn() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new());
+        session.event_generator = EventGenerator::new(1.0);
+        session.start_turn();
+        let stored = session.event_store.load(session.session_id).unwrap().len();
+
+        // Both ports roll: the events wait in the ports until the turn closes
+        assert!(!session.process_random_events().is_empty());
+        assert_eq!(
+            session.event_store.load(session.session_id).unwrap().len(),
+            stored
+        );
+        assert!(session
+            .ports()
+            .flat_map(|port| port.uncommitted_events())
+            .any(|event| matches!(event, DomainEvent::RandomEventTriggered { .. })));
+
+        session.start_turn();
+        let resumed = GameSession::resume_from_replay(&session.export_replay().unwrap()).unwrap();
+        assert!(resumed.player_port.state_eq(&session.player_port));
+        assert_eq!(resumed.player_port.version(), session.player_port.version());
+        assert_eq!(resumed.ai_port.version(), session.ai_port.version());
+    }
+
+    #[test]
+    fn test_rush_hour_is_rolled_once_per_turn() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new());
+        session.event_generator = EventGenerator::with_seed(1.0, 7);
+        let turns = 200;
+        let mut rush_hours = 0;
+        for _ in 0..turns {
+            session.start_turn();
+            session.process_random_events();
+            let harbor_wide: Vec<_> = session
+                .player_port
+                .uncommitted_events()
+                .iter()
+                .filter_map(|event| match event {
+                    DomainEvent::RandomEventTriggered {
+                        player: None,
+                        description,
+                        ..
+                    } => Some(description.clone()),
+                    _ => None,
+                })
+                .collect();
+            assert!(harbor_wide.len() <= 1);
+            rush_hours += harbor_wide.len();
+
+            // Per-port rolls never bring a rush hour of their own
+            assert!(session
+                .ai_port
+                .uncommitted_events()
+                .iter()
+                .all(|event| !matches!(
+                    event,
+                    DomainEvent::RandomEventTriggered { player: Some(_), description, .. }
+                        if description.contains("RUSH")
+                )));
+            session.player_port.ships.clear();
+            session.ai_port.ships.clear();
+        }
+
+        // One kind out of five, whatever the number of ports
+        assert!((turns / 10..turns * 3 / 10).contains(&rush_hours));
+    }
+
+    #[test]
+    fn test_turn_log_groups_events_by_turn() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.event_generator = EventGenerator::new(1.0);
+
+        session.spawn_ships(1);
+        let ship_id = ShipId::new(0);
+
+        // Turn 1: dock and assign, turn 2: process
+        session.start_turn();
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+        session
+            .player_assign_crane(CraneId::new(1), ship_id)
+            .unwrap();
+        session.start_turn();
+        session.process_containers();
+        let fired = session.process_random_events();
+
+        let log = session.turn_log();
+
+        assert_eq!(log.len(), 3);
+        assert_eq!(
+            log[1].player_actions,
+            vec!["Docked Ship#0 at Berth#0", "Assigned Crane#1 to Ship#0"]
+        );
+        assert_eq!(log[1].containers_processed, 0);
+        assert!(log[2].player_actions.is_empty());
+        assert_eq!(log[2].containers_processed, 10);
+        assert_eq!(log[2].score_delta, 100);
+        assert_eq!(
+            log[2].random_events,
+            fired.iter().map(|e| e.description()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_ai_reaching_win_score_ends_game() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+
+        session.ai_port.score = 1200;
+
+        assert_eq!(
+            session.game_outcome(),
+            Some(GameOutcome {
+                winner: Some(ai_id),
+                reason: GameOverReason::ScoreTarget,
+            })
+        );
+        assert_eq!(session.get_winner(), Some("ai"));
+    }
+
+    #[test]
+    fn test_deadlocked_ports_end_in_stalemate() {
+        use crate::domain::entities::Ship;
+
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+        session.set_events_enabled(false);
+
+        // One berth taken by a ship no crane can ever serve, one ship waiting behind it
+        for port in [&mut session.player_port, &mut session.ai_port] {
+            *port = Port::new(port.player_id, 1, 0);
+            let mut docked = Ship::new(ShipId::new(0), 20, 0.0);
+            docked.dock(BerthId::new(0));
+            port.berths
+                .get_mut(&BerthId::new(0))
+                .unwrap()
+                .occupy(docked.id);
+            port.ships.insert(docked.id, docked);
+            port.ships
+                .insert(ShipId::new(1), Ship::new(ShipId::new(1), 20, 0.0));
+        }
+
+        for _ in 0..STALEMATE_TURNS - 1 {
+            session.start_turn();
+            assert!(!session.is_game_over());
+        }
+        session.start_turn();
+
+        assert!(session.current_turn < session.rules.max_turns);
+        assert_eq!(
+            session.game_outcome(),
+            Some(GameOutcome {
+                winner: None,
+                reason: GameOverReason::Stalemate,
+            })
+        );
+        assert_eq!(session.get_winner(), Some("tie"));
+    }
+
+    #[test]
+    fn test_turns_remaining_counts_down_to_the_limit() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_rules(GameRules {
+                max_turns: 4,
+                ..GameRules::default()
+            });
+        session.set_events_enabled(false);
+        assert_eq!(session.turns_remaining(), 4);
+        assert_eq!(session.progress(), 0.0);
+
+        for expected in (0..4).rev() {
+            session.start_turn();
+            assert_eq!(session.turns_remaining(), expected);
+        }
+        assert_eq!(session.progress(), 1.0);
+        assert!(session.is_game_over());
+
+        // Playing past the limit does not wrap around
+        session.start_turn();
+        assert_eq!(session.turns_remaining(), 0);
+        assert_eq!(session.progress(), 1.0);
+    }
+
+    #[test]
+    fn test_disabled_ai_port_cannot_win_or_be_compared() {
+        use crate::domain::entities::Ship;
+
+        let player_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, PlayerId::new());
+        session.set_ai_enabled(false);
+        // An idle AI port that would win on score and lose on overflow
+        session.ai_port.score = WIN_SCORE * 2;
+        for id in 0..=MAX_WAITING_SHIPS {
+            session
+                .ai_port
+                .ships
+                .insert(ShipId::new(id), Ship::new(ShipId::new(id), 20, 0.0));
+        }
+        assert!(!session.is_game_over());
+        assert_eq!(session.comparison_stats().ai_score, 0);
+
+        // The turn limit is the player's win, whatever the AI port holds
+        session.current_turn = session.rules.max_turns;
+        assert_eq!(session.outcome(), GameResult::PlayerWins);
+        session.current_turn = 0;
+
+        // Overflowing alone is nobody's win, not the AI's
+        for id in 0..=MAX_WAITING_SHIPS {
+            session
+                .player_port
+                .ships
+                .insert(ShipId::new(id), Ship::new(ShipId::new(id), 20, 0.0));
+        }
+        let outcome = session.game_outcome().unwrap();
+        assert_eq!(outcome.reason, GameOverReason::WaitingOverflow);
+        assert_eq!(outcome.winner, None);
+        assert_ne!(session.outcome(), GameResult::AiWins);
+    }
+
+    #[test]
+    fn test_disabled_ai_port_stays_idle() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.set_ai_enabled(false);
+        let ai_before = session.ai_port.clone();
+
+        session.start_turn();
+        session.spawn_ships(2);
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session
+            .player_assign_crane(CraneId::new(1), ShipId::new(0))
+            .unwrap();
+        for _ in 0..4 {
+            session.end_turn();
+        }
+
+        assert_eq!(session.ai_take_turn(), 0);
+        assert!(session.ai_port.state_eq(&ai_before));
+        assert_eq!(session.ai_port.version(), ai_before.version());
+        assert!(session
+            .turn_log()
+            .iter()
+            .all(|turn| turn.ai_containers_processed == 0));
+
+        assert!(session.ships_completed(session.player_port.player_id) > 0);
+        assert!(session
+            .turn_log()
+            .iter()
+            .any(|turn| turn.containers_processed > 0));
+    }
+
+    #[test]
+    fn test_game_over_callback_runs_once() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_rules(GameRules {
+                max_turns: 2,
+                ..GameRules::default()
+            });
+        session.set_events_enabled(false);
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&calls);
+        session.on_game_over(Box::new(move |session| {
+            assert!(session.is_game_over());
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        session.start_turn();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        for _ in 0..3 {
+            session.end_turn();
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_ai_waiting_overflow_loses() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+
+        // Ships only pile up on the AI side
+        for id in 0..12 {
+            session.ai_port.ships.insert(
+                ShipId::new(id),
+                crate::domain::entities::Ship::new(ShipId::new(id), 20, 0.0),
+            );
+        }
+
+        assert_eq!(
+            session.game_outcome(),
+            Some(GameOutcome {
+                winner: Some(player_id),
+                reason: GameOverReason::WaitingOverflow,
+            })
+        );
+        assert_eq!(session.get_winner(), Some("player"));
+    }
+
+    /// Constant score that counts how often it is consulted
+    #[derive(Default)]
+    struct ConstantScoring {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl ScoringStrategy for ConstantScoring {
+        fn score(&self, _port: &Port) -> i32 {
+            self.calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            42
+        }
+    }
+
+    #[test]
+    fn test_custom_scoring_strategy_is_used_everywhere() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let strategy = Arc::new(ConstantScoring::default());
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_scoring(strategy.clone())
+            .with_mcts_config(MCTSConfig {
+                num_simulations: 10,
+                ..MCTSConfig::default()
+            });
+
+        // MCTS rollouts
+        session.spawn_ships(2);
+        session.ai_take_turn();
+        let rollout_calls = strategy.calls.load(std::sync::atomic::Ordering::Relaxed);
+        assert!(rollout_calls >= 20);
+
+        // The win check reads the strategy, not the raw port score
+        session.ai_port.score = WIN_SCORE + 1;
+        assert!(!session.is_game_over());
+
+        // So do the comparison and port-state read models
+        let stats = session.comparison_stats();
+        assert_eq!((stats.player_score, stats.ai_score), (42, 42));
+        assert_eq!(session.port_state(&session.ai_port).score, 42);
+        assert_eq!(session.snapshot().player_port.score, 42);
+
+        // Game result at the turn limit: equal constant scores are a tie
+        session.current_turn = MAX_TURNS;
+        session.start_turn();
+        assert_eq!(session.score(&session.ai_port), 42);
+        assert_eq!(session.get_winner(), Some("tie"));
+
+        let json = session.export_replay().unwrap();
+        let events = ReplayEnvelope::from_json(&json).unwrap().events;
+        let scores = events.iter().find_map(|event| match event {
+            DomainEvent::GameEnded {
+                player_score,
+                ai_score,
+                ..
+            } => Some((*player_score, *ai_score)),
+            _ => None,
+        });
+        assert_eq!(scores, Some((42, 42)));
+    }
+
+    #[test]
+    fn test_execute_commands_batch() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+        session.spawn_ships(1);
+        let ship_id = ShipId::new(0);
+
+        let events = session
+            .execute_commands(&[
+                Command::DockShip {
+                    player_id,
+                    ship_id,
+                    berth_id: BerthId::new(0),
+                },
+                Command::AssignCrane {
+                    player_id,
+                    crane_id: CraneId::new(0),
+                    ship_id,
+                },
+            ])
+            .unwrap();
+
+        let types: Vec<_> = events.iter().map(|event| event.event_type()).collect();
+        assert_eq!(types, vec!["ShipDocked", "CraneAssigned"]);
+        assert_eq!(session.player_port.ships[&ship_id].assigned_cranes.len(), 1);
+    }
+
+    #[test]
+    fn test_execute_commands_stops_at_first_error() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+        session.spawn_ships(1);
+        let ship_id = ShipId::new(0);
+
+        let result = session.execute_commands(&[
+            Command::DockShip {
+                player_id: ai_id,
+                ship_id,
+                berth_id: BerthId::new(1),
+            },
+            Command::AssignCrane {
+                player_id: ai_id,
+                crane_id: CraneId::new(99),
+                ship_id,
+            },
+            Command::ProcessContainers { player_id: ai_id },
+        ]);
+
+        let (index, error) = result.unwrap_err();
+        assert_eq!(index, 1);
+        assert!(error.contains("Crane not found"));
+
+        // The first command was applied to the AI port only
+        assert_eq!(
+            session.ai_port.ships[&ship_id].docked_at,
+            Some(BerthId::new(1))
+        );
+        assert!(!session.player_port.ships[&ship_id].is_docked());
+    }
+
+    #[test]
+    fn test_can_execute_matches_execute_command() {
+        let player_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap()
+            .with_rules(GameRules {
+                action_points_per_turn: 6,
+                ..GameRules::default()
+            });
+        session.spawn_ships(2);
+        let (ship_a, ship_b) = (ShipId::new(0), ShipId::new(1));
+        let dock = |ship_id, berth| Command::DockShip {
+            player_id,
+            ship_id,
+            berth_id: BerthId::new(berth),
+        };
+        let assign = |crane, ship_id| Command::AssignCrane {
+            player_id,
+            crane_id: CraneId::new(crane),
+            ship_id,
+        };
+        let reassign = |crane, new_ship_id| Command::ReassignCrane {
+            player_id,
+            crane_id: CraneId::new(crane),
+            new_ship_id,
+        };
+        let unassign = |crane, ship_id| Command::UnassignCrane {
+            player_id,
+            crane_id: CraneId::new(crane),
+            ship_id,
+        };
+
+        let commands = [
+            dock(ShipId::new(9), 0), // Unknown ship
+            dock(ship_a, 0),
+            dock(ship_a, 1),   // Already docked
+            dock(ship_b, 0),   // Berth occupied
+            assign(0, ship_b), // Ship not docked
+            assign(0, ship_a),
+            assign(0, ship_a),   // Crane busy
+            reassign(1, ship_a), // Crane not assigned
+            reassign(0, ship_b), // Target not docked
+            dock(ship_b, 1),
+            reassign(0, ship_a), // Already there
+            reassign(0, ship_b),
+            unassign(0, ship_a), // Not on that ship
+            assign(1, ship_a),
+            unassign(1, ship_a),
+            dock(ShipId::new(9), 0), // Out of actions
+            Command::ProcessContainers { player_id },
+            Command::EndTurn { player_id },
+            Command::ProcessContainers {
+                player_id: PlayerId::new(),
+            },
+        ];
+
+        for command in &commands {
+            let checked = session.can_execute(command);
+            let version = session.player_port.version();
+            let executed = session.execute_command(command).map(|_| ());
+            assert_eq!(checked, executed, "{:?}", command);
+            if checked.is_err() {
+                assert_eq!(session.player_port.version(), version);
+            }
+        }
+        assert_eq!(
+            session.player_port.ships[&ship_b].assigned_cranes,
+            vec![CraneId::new(0)]
+        );
+    }
+
+    #[test]
+    fn test_commands_spend_the_issuing_sides_action_points() {
+        let (player_id, ai_id) = (PlayerId::new(), PlayerId::new());
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.spawn_ships(2);
+        session.start_turn();
+        let ship_id = ShipId::new(0);
+
+        session
+            .execute_command(&Command::DockShip {
+                player_id,
+                ship_id,
+                berth_id: BerthId::new(0),
+            })
+            .unwrap();
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS - 1);
+        assert_eq!(session.ai_action_points, DEFAULT_ACTION_POINTS);
+
+        session
+            .execute_command(&Command::Pass { player_id: ai_id })
+            .unwrap();
+        assert_eq!(session.ai_action_points, DEFAULT_ACTION_POINTS - 1);
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS - 1);
+
+        // Unloading is not an action, but only comes once a turn
+        let process = Command::ProcessContainers { player_id };
+        session.execute_command(&process).unwrap();
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS - 1);
+        let version = session.player_port.version();
+        let already_done = session.can_execute(&process).unwrap_err();
+        assert!(already_done.contains("already processed"));
+        assert_eq!(
+            session.execute_command(&process).map(|_| ()),
+            Err(already_done)
+        );
+        assert_eq!(session.player_port.version(), version);
+        // The other side still may
+        session
+            .execute_command(&Command::ProcessContainers { player_id: ai_id })
+            .unwrap();
+
+        session
+            .player_assign_crane(CraneId::new(0), ship_id)
+            .unwrap();
+        session.player_unassign_crane(CraneId::new(0)).unwrap();
+        assert!(session.player_port.cranes[&CraneId::new(0)].is_free());
+        assert_eq!(session.action_points(), 0);
+
+        // The command path is held to the same budget
+        let pass = Command::Pass { player_id };
+        let out_of_actions = session.can_execute(&pass).unwrap_err();
+        assert!(out_of_actions.contains("Out of actions"));
+        assert_eq!(
+            session.execute_command(&pass).map(|_| ()),
+            Err(out_of_actions)
+        );
+        assert!(session.player_unassign_crane(CraneId::new(1)).is_err());
+    }
+
+    #[test]
+    fn test_arrivals_beyond_harbor_capacity_are_rejected() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session =
+            GameSession::new(GameMode::VersusAI, player_id, ai_id).with_rules(GameRules {
+                harbor_capacity: Some(2),
+                ..GameRules::default()
+            });
+
+        session.spawn_ships(5);
+        session.commit_events();
+
+        assert_eq!(session.player_port.waiting_ships().len(), 2);
+        assert_eq!(session.ai_port.waiting_ships().len(), 2);
+
+        let events = session.event_store.load(session.session_id).unwrap();
+        let rejected: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                DomainEvent::ArrivalRejected {
+                    ship_id, player, ..
+                } => Some((*ship_id, *player)),
+                _ => None,
+            })
+            .collect();
+
+        // Each refused ship is reported once per full harbor
+        assert_eq!(rejected.len(), 6);
+        for ship in 2..5 {
+            assert!(rejected.contains(&(ShipId::new(ship), player_id)));
+            assert!(rejected.contains(&(ShipId::new(ship), ai_id)));
+        }
+    }
+
+    #[test]
+    fn test_faster_crane_unloads_more() {
+        let player_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 30,
+                max_containers: 30,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.set_events_enabled(false);
+        session.spawn_ships(2);
+        for (ship, crane) in [(0, 0), (1, 1)] {
+            session.start_turn();
+            session
+                .player_dock_ship(ShipId::new(ship), BerthId::new(ship))
+                .unwrap();
+            session
+                .player_assign_crane(CraneId::new(crane), ShipId::new(ship))
+                .unwrap();
+        }
+        let port = &session.player_port;
+        assert!(
+            port.cranes[&CraneId::new(1)].processing_speed
+                > port.cranes[&CraneId::new(0)].processing_speed
+        );
+
+        // The estimate plans like the real processing: 21 standard, 6 reefer
+        // and 3 hazmat containers, the last two slower to handle
+        assert_eq!(port.estimated_completion(ShipId::new(0)), Some(8));
+        assert_eq!(port.estimated_completion(ShipId::new(1)), Some(4));
+
+        session.process_containers();
+
+        let ships = &session.player_port.ships;
+        assert_eq!(ships[&ShipId::new(0)].containers_remaining, 25);
+        assert_eq!(ships[&ShipId::new(1)].containers_remaining, 20);
+    }
+
+    #[test]
+    fn test_containers_per_crane_rule_drives_processing_and_rollouts() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session =
+            GameSession::new(GameMode::VersusAI, player_id, ai_id).with_rules(GameRules {
+                containers_per_crane_per_turn: 5,
+                ..GameRules::default()
+            });
+        session.spawn_ships(1);
+        let ship_id = ShipId::new(0);
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+        session
+            .player_assign_crane(CraneId::new(1), ship_id)
+            .unwrap();
+        let before = session.player_port.ships[&ship_id].containers_remaining;
+
+        // MCTS rollout step on a copy of the port
+        let mut rollout = session.player_port.clone();
+        MCTSTree::apply_action_to_state(&mut rollout, &MCTSAction::Pass);
+
+        session.process_containers();
+
+        assert_eq!(rollout.ships[&ship_id].containers_remaining, before - 5);
+        assert_eq!(
+            session.player_port.ships[&ship_id].containers_remaining,
+            before - 5
+        );
+    }
+
+    #[test]
+    fn test_state_fingerprint_tracks_divergence() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let play = |berth: usize| {
+            random::with_seed(11, || {
+                let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+                    .with_spawn_config(SpawnConfig {
+                        min_containers: 20,
+                        max_containers: 30,
+                        ..SpawnConfig::default()
+                    })
+                    .unwrap();
+                session.spawn_ships(2);
+                session.start_turn();
+                session
+                    .execute_commands(&[
+                        Command::DockShip {
+                            player_id,
+                            ship_id: ShipId::new(0),
+                            berth_id: BerthId::new(berth),
+                        },
+                        Command::AssignCrane {
+                            player_id,
+                            crane_id: CraneId::new(0),
+                            ship_id: ShipId::new(0),
+                        },
+                    ])
+                    .unwrap();
+                session.process_containers();
+                session.process_random_events();
+                session.state_fingerprint()
+            })
+        };
+
+        assert_eq!(play(0), play(0));
+        assert_ne!(play(0), play(1));
+    }
+
+    #[test]
+    fn test_coop_ai_plays_on_the_shared_port() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::Coop, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                reefer_percent: 0,
+                hazmat_percent: 0,
+                ..SpawnConfig::default()
+            })
+            .unwrap()
+            .with_rules(GameRules {
+                coop_target_score: 1,
+                ..GameRules::default()
+            });
+        session.set_events_enabled(false);
+        session.start_turn();
+        session.spawn_ships(2);
+        assert!(session.ai_port.ships.is_empty());
+
+        // The human takes the first ship, the AI helps with the second one
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ShipId::new(0))
+            .unwrap();
+        assert!(session.ai_take_turn() > 0);
+
+        // The AI docked the second ship and put its crane to work on the same port
+        let port = &session.player_port;
+        assert_eq!(port.docked_ships().len(), 2);
+        assert!(port.cranes.values().all(|crane| !crane.is_free()));
+        assert!(session.ai_port.docked_ships().is_empty());
+        assert!(session.ai_port.cranes.values().all(|crane| crane.is_free()));
+
+        // Both cranes unload, credited to the one shared score
+        // (Crane#0 runs at half the default speed)
+        session.process_containers();
+        let unloaded: u32 = [0, 1]
+            .map(|ship| 20 - session.player_port.ships[&ShipId::new(ship)].containers_remaining)
+            .iter()
+            .sum();
+        assert_eq!(
+            unloaded,
+            DEFAULT_CONTAINERS_PER_CRANE / 2 + DEFAULT_CONTAINERS_PER_CRANE
+        );
+        assert!(session.score(&session.player_port) > 0);
+        assert_eq!(session.ai_port.score, 0);
+        assert_eq!(
+            session.game_outcome(),
+            Some(GameOutcome {
+                winner: Some(player_id),
+                reason: GameOverReason::ScoreTarget,
+            })
+        );
+        assert_eq!(session.outcome(), GameResult::PlayerWins);
+    }
+
+    #[test]
+    fn test_save_mid_game_loads_equivalent_session() {
+        let player_id = PlayerId::new();
+        let rules = GameRules {
+            action_points_per_turn: 4,
+            points_per_container: 7,
+            ..GameRules::default()
+        };
+        let session = random::with_seed(5, || {
+            let mut session = GameSession::new(GameMode::VersusAI, player_id, PlayerId::new())
+                .with_spawn_config(SpawnConfig {
+                    min_containers: 20,
+                    max_containers: 20,
+                    ..SpawnConfig::default()
+                })
+                .unwrap()
+                .with_rules(rules);
+            session.spawn_ships(3);
+            session.start_turn();
+            session
+                .player_dock_ship(ShipId::new(0), BerthId::new(0))
+                .unwrap();
+            session
+                .player_assign_crane(CraneId::new(0), ShipId::new(0))
+                .unwrap();
+            session.end_turn();
+            session.end_turn();
+            // Quit in the middle of turn 3, one action spent
+            session
+                .player_dock_ship(ShipId::new(1), BerthId::new(1))
+                .unwrap();
+            session
+        });
+
+        let loaded = GameSession::load_from_json(&session.save_to_json().unwrap()).unwrap();
+
+        assert_eq!(loaded.session_id, session.session_id);
+        assert_eq!(loaded.current_turn, 3);
+        assert_eq!(loaded.rules, rules);
+        assert_eq!(loaded.action_points(), session.action_points());
+        assert_eq!(loaded.player_port.score, session.player_port.score);
+        assert_eq!(loaded.state_fingerprint(), session.state_fingerprint());
+    }
+
+    #[test]
+    fn test_container_batches_split_processing_events() {
+        let player_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 30,
+                max_containers: 30,
+                reefer_percent: 0,
+                hazmat_percent: 0,
+                ..SpawnConfig::default()
+            })
+            .unwrap()
+            .with_rules(GameRules {
+                containers_per_crane_per_turn: 100,
+                container_batch_size: Some(10),
+                ..GameRules::default()
+            });
+        session.set_events_enabled(false);
+        session.spawn_ships(1);
+        let ship_id = ShipId::new(0);
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ship_id)
+            .unwrap();
+
+        session.process_containers();
+
+        let remaining: Vec<u32> = session
+            .player_port
+            .uncommitted_events()
+            .iter()
+            .filter_map(|event| match event {
+                DomainEvent::ContainerProcessed {
+                    containers_remaining,
+                    ..
+                } => Some(*containers_remaining),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(remaining, vec![20, 10, 0]);
+    }
+
+    #[test]
+    fn test_legal_actions_for_one_ship_port() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        assert!(session.legal_actions().is_empty());
+
+        session.spawn_ships(1);
+        let ship_id = ShipId::new(0);
+        let actions = session.legal_actions();
+        assert_eq!(
+            actions,
+            vec![
+                MCTSAction::DockShip {
+                    ship_id,
+                    berth_id: BerthId::new(0)
+                },
+                MCTSAction::DockShip {
+                    ship_id,
+                    berth_id: BerthId::new(1)
+                },
+            ]
+        );
+
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+        let actions = session.legal_actions();
+        assert!(actions.contains(&MCTSAction::AssignCrane {
+            crane_id: CraneId::new(0),
+            ship_id
+        }));
+        assert!(!actions
+            .iter()
+            .any(|action| matches!(action, MCTSAction::DockShip { .. })));
+    }
+
+    #[test]
+    fn test_snapshot_matches_session_state() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        // Small ships: turn 1 is at low tide
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.spawn_ships(2);
+        session.start_turn();
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ShipId::new(0))
+            .unwrap();
+        session.process_containers();
+
+        let snapshot = session.snapshot();
+
+        assert_eq!(snapshot.current_turn, 1);
+        assert!(!snapshot.is_game_over);
+        assert_eq!(snapshot.winner, None);
+        assert_eq!(snapshot.crane_efficiency, session.crane_efficiency_modifier);
+        assert_eq!(snapshot.player_port.player_id, player_id);
+        assert_eq!(snapshot.ai_port.player_id, ai_id);
+        assert_eq!(snapshot.player_port.ships.len(), 2);
+        assert_eq!(
+            snapshot.player_port.score,
+            session.player_port.calculate_score()
+        );
+
+        let docked = snapshot
+            .player_port
+            .ships
+            .iter()
+            .find(|ship| ship.id == 0)
+            .unwrap();
+        assert_eq!(docked.docked_at, Some(0));
+        assert_eq!(
+            docked.containers_remaining,
+            session.player_port.ships[&ShipId::new(0)].containers_remaining
+        );
+    }
+
+    #[test]
+    fn test_assign_crane_checks_berth_compatibility() {
+        // Small ships: turn 1 is at low tide
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.spawn_ships(2);
+        session.start_turn();
+        for id in 0..2 {
+            session
+                .player_dock_ship(ShipId::new(id), BerthId::new(id))
+                .unwrap();
+        }
+        session
+            .player_port
+            .cranes
+            .get_mut(&CraneId::new(0))
+            .unwrap()
+            .compatible_berths = Some([BerthId::new(0)].into_iter().collect());
+
+        let result = session.player_assign_crane(CraneId::new(0), ShipId::new(1));
+        assert_eq!(
+            result,
+            Err("Crane Crane#0 is incompatible with Berth#1".to_string())
+        );
+
+        session
+            .player_assign_crane(CraneId::new(0), ShipId::new(0))
+            .unwrap();
+        assert_eq!(
+            session.player_port.cranes[&CraneId::new(0)].assigned_to,
+            Some(ShipId::new(0))
+        );
+    }
+
+    #[test]
+    fn test_reefer_crane_cannot_unload_standard_cargo() {
+        use crate::domain::value_objects::ContainerType;
+
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                reefer_percent: 0,
+                hazmat_percent: 0,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.set_events_enabled(false);
+        session.spawn_ships(1);
+        session.start_turn();
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        let reefer_only = Some([ContainerType::Reefer].into_iter().collect());
+        session
+            .player_port
+            .cranes
+            .get_mut(&CraneId::new(0))
+            .unwrap()
+            .handled_types = reefer_only;
+
+        let result = session.player_assign_crane(CraneId::new(0), ShipId::new(0));
+        assert_eq!(
+            result,
+            Err("Crane Crane#0 cannot handle the cargo left on Ship#0".to_string())
+        );
+
+        // Forced onto the ship anyway, it unloads nothing
+        session
+            .player_port
+            .cranes
+            .get_mut(&CraneId::new(0))
+            .unwrap()
+            .assign(ShipId::new(0));
+        session
+            .player_port
+            .ships
+            .get_mut(&ShipId::new(0))
+            .unwrap()
+            .assign_crane(CraneId::new(0));
+        session.process_containers();
+        assert_eq!(
+            session.player_port.ships[&ShipId::new(0)].containers_remaining,
+            20
+        );
+    }
 
-        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
-        session.spawn_ships(2);
+    #[test]
+    fn test_random_layout_is_mirrored() {
+        let layout = |seed| {
+            let session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+                .with_random_layout(seed, LayoutConstraints::default());
+            let describe = |port: &Port| {
+                let speeds: Vec<f64> = port
+                    .cranes
+                    .values()
+                    .map(|crane| crane.processing_speed)
+                    .collect();
+                (port.berths.len(), speeds)
+            };
+            assert_eq!(describe(&session.player_port), describe(&session.ai_port));
+            assert_ne!(session.player_port.player_id, session.ai_port.player_id);
+            describe(&session.player_port)
+        };
 
-        assert_eq!(session.player_port.ships.len(), 2);
-        assert_eq!(session.ai_port.ships.len(), 2);
+        assert_eq!(layout(42), layout(42));
+        assert!((43..53).any(|seed| layout(seed) != layout(42)));
     }
 
     #[test]
-    fn test_event_export() {
+    fn test_random_layout_survives_replay_and_save() {
+        let constraints = LayoutConstraints {
+            min_berths: 3,
+            max_berths: 3,
+            min_cranes: 4,
+            max_cranes: 4,
+            ..LayoutConstraints::default()
+        };
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_random_layout(7, constraints);
+        session.set_events_enabled(false);
+        session.start_turn();
+        session.spawn_ships(1);
+
+        let events = session.session_events().unwrap();
+        let speeds: Vec<f64> = session
+            .player_port
+            .cranes
+            .values()
+            .map(|crane| crane.processing_speed)
+            .collect();
+        assert!(matches!(
+            &events[0],
+            DomainEvent::GameStarted { num_berths: 3, num_cranes: 4, crane_speeds, .. }
+                if *crane_speeds == speeds
+        ));
+
+        let resumed = GameSession::resume_from_replay(&session.export_replay().unwrap()).unwrap();
+        let loaded = GameSession::load_from_json(&session.save_to_json().unwrap()).unwrap();
+        for restored in [&resumed, &loaded] {
+            assert!(restored.player_port.state_eq(&session.player_port));
+            assert!(restored.ai_port.state_eq(&session.ai_port));
+        }
+    }
+
+    #[test]
+    fn test_resume_from_replay() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.set_events_enabled(false);
+        session.spawn_ships(3);
+        session.start_turn();
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ShipId::new(0))
+            .unwrap();
+        session.end_turn();
+
+        let json = session.export_replay().unwrap();
+        let history_len = session.session_events().unwrap().len();
+        let mut resumed = GameSession::resume_from_replay(&json).unwrap();
+
+        assert_eq!(resumed.session_id, session.session_id);
+        assert_eq!(resumed.current_turn, session.current_turn);
+        // Crane wear comes back with the history
+        let crane = CraneId::new(0);
+        assert!(session.player_port.cranes[&crane].wear > 0.0);
+        assert_eq!(
+            resumed.player_port.cranes[&crane].wear,
+            session.player_port.cranes[&crane].wear
+        );
+        assert!(resumed.player_port.state_eq(&session.player_port));
+        assert!(resumed.ai_port.state_eq(&session.ai_port));
+        assert_eq!(resumed.player_port.version(), session.player_port.version());
+
+        // The next action continues the history where it stopped
+        let next_version = resumed.player_port.version() + 1;
+        resumed
+            .player_dock_ship(ShipId::new(1), BerthId::new(1))
+            .unwrap();
+        resumed.commit_events();
+
+        let events = resumed.session_events().unwrap();
+        assert_eq!(events.len(), history_len + 1);
+        match events.last().unwrap() {
+            DomainEvent::ShipDocked {
+                metadata, ship_id, ..
+            } => {
+                assert_eq!(*ship_id, ShipId::new(1));
+                assert_eq!(metadata.version, next_version);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_three_port_session_mirrors_ships_and_routes_commands() {
         let player_id = PlayerId::new();
         let ai_id = PlayerId::new();
+        let third_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap()
+            .with_extra_port(third_id);
+        session.set_events_enabled(false);
+        session.spawn_ships(3);
 
-        let session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
-        let json = session.export_replay();
+        assert_eq!(session.ports().count(), 3);
+        for port in session.ports() {
+            assert!(port.ships.keys().eq(session.player_port.ships.keys()));
+        }
 
-        assert!(json.is_ok());
+        session
+            .execute_commands(&[
+                Command::DockShip {
+                    player_id: third_id,
+                    ship_id: ShipId::new(2),
+                    berth_id: BerthId::new(1),
+                },
+                Command::AssignCrane {
+                    player_id: third_id,
+                    crane_id: CraneId::new(0),
+                    ship_id: ShipId::new(2),
+                },
+            ])
+            .unwrap();
+        let third = session.port(third_id).unwrap();
+        assert!(third.ships[&ShipId::new(2)].is_docked());
+        assert!(!session.player_port.ships[&ShipId::new(2)].is_docked());
+        assert!(!session.ai_port.ships[&ShipId::new(2)].is_docked());
+
+        // Only the third port unloads, so it leads the standings
+        session.process_containers();
+        assert!(session.port(third_id).unwrap().ships[&ShipId::new(2)].containers_remaining < 20);
+        assert_eq!(session.standings()[0].0, third_id);
+        assert_eq!(session.leader_by_score(), Some(third_id));
+
+        let unknown = Command::Pass {
+            player_id: PlayerId::new(),
+        };
+        assert!(session.execute_command(&unknown).is_err());
     }
 
     #[test]
-    fn test_free_completed_ships() {
+    fn test_player_port_rebuild_ignores_ai_events() {
         let player_id = PlayerId::new();
         let ai_id = PlayerId::new();
-        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.set_events_enabled(false);
+        let layout = session.player_port.clone();
+        session.spawn_ships(2);
+        session.start_turn();
 
-        // 1. Spawn a ship
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ShipId::new(0))
+            .unwrap();
+        session
+            .execute_commands(&[
+                Command::DockShip {
+                    player_id: ai_id,
+                    ship_id: ShipId::new(1),
+                    berth_id: BerthId::new(0),
+                },
+                Command::AssignCrane {
+                    player_id: ai_id,
+                    crane_id: CraneId::new(0),
+                    ship_id: ShipId::new(1),
+                },
+            ])
+            .unwrap();
+        session.process_containers();
+        // Ship 1 keeps waiting on the player port: clock, tide and charges move on
+        for _ in 0..2 {
+            session.start_turn();
+            session.process_containers();
+        }
+        assert!(session.player_port.waiting_cost > 0);
+        assert!(session.player_port.idle_crane_cost > 0);
+
+        let events = session.session_events().unwrap();
+        assert!(events
+            .iter()
+            .any(|e| e.concerns_port(ai_id) && !e.concerns_port(player_id)));
+
+        let rebuilt = Port::from_events(layout, &events);
+        assert!(!rebuilt.ships[&ShipId::new(1)].is_docked());
+        assert_eq!(rebuilt.current_time, 3.0);
+        assert_eq!(rebuilt.tide, session.player_port.tide);
+        assert_eq!(rebuilt.score, session.player_port.score);
+        assert!(rebuilt.state_eq(&session.player_port));
+    }
+
+    #[test]
+    fn test_resume_restores_mode_and_extra_ports() {
+        let (player_id, ai_id, third_id) = (PlayerId::new(), PlayerId::new(), PlayerId::new());
+        let mut session = GameSession::new(GameMode::Sandbox, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap()
+            .with_extra_port(third_id);
+        session.set_events_enabled(false);
+        session.spawn_ships(1);
+        session.start_turn();
+        session
+            .execute_command(&Command::DockShip {
+                player_id: third_id,
+                ship_id: ShipId::new(0),
+                berth_id: BerthId::new(1),
+            })
+            .unwrap();
+
+        let resumed = GameSession::resume_from_replay(&session.export_replay().unwrap()).unwrap();
+        assert_eq!(resumed.mode, GameMode::Sandbox);
+        let third = resumed.port(third_id).unwrap();
+        assert!(third.state_eq(session.port(third_id).unwrap()));
+        assert_eq!(
+            third.ships[&ShipId::new(0)].docked_at,
+            Some(BerthId::new(1))
+        );
+    }
+
+    #[test]
+    fn test_resume_rejects_out_of_order_versions() {
+        // Small ships: turn 1 is at low tide
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.set_events_enabled(false);
+        session.spawn_ships(1);
+        session.start_turn();
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ShipId::new(0))
+            .unwrap();
+        session.commit_events();
+
+        let mut replay = ReplayEnvelope::from_json(&session.export_replay().unwrap()).unwrap();
+        let docked = replay
+            .events
+            .iter()
+            .position(|e| matches!(e, DomainEvent::ShipDocked { .. }))
+            .unwrap();
+        let assigned = replay
+            .events
+            .iter()
+            .position(|e| matches!(e, DomainEvent::CraneAssigned { .. }))
+            .unwrap();
+        replay.events.swap(docked, assigned);
+
+        let Err(error) = GameSession::resume_from_replay(&replay.to_json().unwrap()) else {
+            panic!("out-of-order replay was accepted");
+        };
+        assert!(error.contains("ShipDocked"), "{}", error);
+    }
+
+    #[test]
+    fn test_port_events_share_session_aggregate_id() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 10,
+                max_containers: 10,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.set_events_enabled(false);
+        session.spawn_ships(1);
+        session.start_turn();
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ShipId::new(0))
+            .unwrap();
+        while session.player_port.ships.contains_key(&ShipId::new(0)) {
+            session.end_turn();
+            session.start_turn();
+        }
+
+        let events = session.session_events().unwrap();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, DomainEvent::CraneUnassigned { .. })));
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, DomainEvent::ShipUndocked { .. })));
+        for event in &events {
+            assert_eq!(
+                event.metadata().aggregate_id,
+                session.session_id,
+                "{}",
+                event.event_type()
+            );
+        }
+    }
+
+    #[test]
+    fn test_undo_last_action() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        // Small ships: turn 1 is at low tide
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
         session.spawn_ships(1);
+        session.start_turn();
         let ship_id = ShipId::new(0);
-        let berth_id = BerthId::new(0);
 
-        // 2. Dock it and assign a crane
-        session.player_dock_ship(ship_id, berth_id).unwrap();
+        assert!(session.undo_last_action().is_err());
+
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
         session
             .player_assign_crane(CraneId::new(0), ship_id)
             .unwrap();
 
-        // 3. Empty its containers
-        let ship = session.player_port.ships.get_mut(&ship_id).unwrap();
-        ship.containers_remaining = 0;
+        session.undo_last_action().unwrap();
+        assert!(session.player_port.ships[&ship_id]
+            .assigned_cranes
+            .is_empty());
+        assert!(session.player_port.cranes[&CraneId::new(0)].is_free());
 
-        // 4. Call free_completed_ships
-        session.free_completed_ships();
+        session.undo_last_action().unwrap();
+        assert!(!session.player_port.ships[&ship_id].is_docked());
+        assert_eq!(session.player_port.waiting_ships().len(), 1);
+        assert!(session.player_port.berths[&BerthId::new(0)].is_free());
+        assert!(session.player_port.uncommitted_events().is_empty());
 
-        // 5. Verify:
-        // - Ship should be gone
-        assert!(!session.player_port.ships.contains_key(&ship_id));
-        // - Berth should be free
-        let berth = session.player_port.berths.get(&berth_id).unwrap();
-        assert!(berth.is_free());
-        // - Crane should be unassigned
-        let crane = session.player_port.cranes.get(&CraneId::new(0)).unwrap();
-        assert!(crane.is_free());
+        // Nothing left, and last turn's moves are out of reach
+        assert!(session.undo_last_action().is_err());
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+        session.start_turn();
+        assert!(session.undo_last_action().is_err());
+        assert!(session.player_port.ships[&ship_id].is_docked());
+    }
+
+    #[test]
+    fn test_undo_takes_back_a_multi_crane_assignment_whole() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.spawn_ships(1);
+        session.start_turn();
+        let ship_id = ShipId::new(0);
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+        let cranes = [CraneId::new(0), CraneId::new(1)];
+        session.player_assign_cranes(ship_id, &cranes).unwrap();
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS - 2);
+
+        // One undo frees both cranes and refunds the single action
+        session.undo_last_action().unwrap();
+        for crane in cranes {
+            assert!(session.player_port.cranes[&crane].is_free());
+        }
+        assert!(session.player_port.ships[&ship_id]
+            .assigned_cranes
+            .is_empty());
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS - 1);
+
+        // The next undo is the docking, not half of the assignment
+        session.undo_last_action().unwrap();
+        assert!(!session.player_port.ships[&ship_id].is_docked());
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS);
+        assert!(session.undo_last_action().is_err());
+    }
+
+    #[test]
+    fn test_ai_turn_records_search_events_around_its_moves() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.spawn_ships(2);
+        session.start_turn();
+        let committed = session.event_store.event_count(session.session_id);
+
+        session.ai_take_turn();
+        session.commit_events();
+
+        let events = session.event_store.load(session.session_id).unwrap();
+        let turn_events = &events[committed..];
+        let types: Vec<_> = turn_events.iter().map(|event| event.event_type()).collect();
+        assert_eq!(types[0], "MCTSSearchStarted");
+        assert_eq!(types[1], "MCTSSearchCompleted");
+        assert!(matches!(types[2], "ShipDocked" | "CraneAssigned"));
+
+        match &turn_events[1] {
+            DomainEvent::MCTSSearchCompleted {
+                player,
+                best_action,
+                confidence,
+                simulations_performed,
+                ..
+            } => {
+                assert_eq!(*player, ai_id);
+                let action: MCTSAction = serde_json::from_str(best_action).unwrap();
+                assert_ne!(action, MCTSAction::Pass);
+                assert!((0.0..=1.0).contains(confidence));
+                assert_eq!(*simulations_performed, 100);
+            }
+            other => panic!("expected MCTSSearchCompleted, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_spawned_ships_are_mirrored_in_both_ports() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+        session.event_generator = EventGenerator::new(0.0);
+
+        session.spawn_ships(3);
+        session.start_turn();
+        session.spawn_ships(2);
+        session.end_turn();
+        session.spawn_ships(4);
+
+        let fleet = |port: &Port| -> Vec<(ShipId, u32, bool, f64)> {
+            port.ships
+                .values()
+                .map(|ship| (ship.id, ship.containers, ship.priority, ship.arrival_time))
+                .collect()
+        };
+        assert_eq!(session.player_port.ships.len(), 9);
+        assert!(session
+            .player_port
+            .ships
+            .keys()
+            .eq(session.ai_port.ships.keys()));
+        assert_eq!(fleet(&session.player_port), fleet(&session.ai_port));
+    }
+
+    #[test]
+    fn test_action_points_budget_per_turn() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.event_generator = EventGenerator::new(0.0);
+        session.spawn_ships(3);
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS);
+
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        session
+            .player_dock_ship(ShipId::new(1), BerthId::new(1))
+            .unwrap();
+        session
+            .player_assign_crane(CraneId::new(0), ShipId::new(0))
+            .unwrap();
+        assert_eq!(session.action_points(), 0);
+
+        let error = session
+            .player_assign_crane(CraneId::new(1), ShipId::new(1))
+            .unwrap_err();
+        assert!(error.contains("Out of actions"));
+        assert!(session.player_port.cranes[&CraneId::new(1)].is_free());
+
+        // Undo gives the point back
+        session.undo_last_action().unwrap();
+        assert_eq!(session.action_points(), 1);
+
+        session.end_turn();
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS);
+        session
+            .player_assign_crane(CraneId::new(1), ShipId::new(1))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_pass_records_intent_and_spends_action() {
+        let player_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, PlayerId::new());
+        session.event_generator = EventGenerator::new(0.0);
+        session.spawn_ships(2);
+        session
+            .player_dock_ship(ShipId::new(0), BerthId::new(0))
+            .unwrap();
+        let before = session.port_state(&session.player_port);
+
+        session.player_pass().unwrap();
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS - 2);
+
+        let after = session.port_state(&session.player_port);
+        assert_eq!(
+            serde_json::to_value(&before).unwrap(),
+            serde_json::to_value(&after).unwrap()
+        );
+        let last = session.player_port.uncommitted_events().last().unwrap();
+        assert!(matches!(last, DomainEvent::PlayerPassed { player, .. } if *player == player_id));
+
+        // Same path through the command API
+        let events = session
+            .execute_command(&Command::Pass { player_id })
+            .unwrap();
+        assert_eq!(events[0].event_type(), "PlayerPassed");
+        assert_eq!(session.action_points(), 0);
+
+        // ... and held to the same budget
+        assert!(session
+            .execute_command(&Command::Pass { player_id })
+            .is_err());
+    }
+
+    #[test]
+    fn test_ai_respects_action_budget() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session =
+            GameSession::new(GameMode::VersusAI, player_id, ai_id).with_rules(GameRules {
+                action_points_per_turn: 1,
+                ..GameRules::default()
+            });
+        session.spawn_ships(2);
+
+        session.ai_take_turn();
+
+        let ai_actions = session
+            .ai_port
+            .uncommitted_events()
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    DomainEvent::ShipDocked { .. } | DomainEvent::CraneAssigned { .. }
+                )
+            })
+            .count();
+        assert_eq!(ai_actions, 1);
+    }
+
+    #[test]
+    fn test_session_cranes_have_varied_speeds() {
+        let session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new());
+
+        for port in [&session.player_port, &session.ai_port] {
+            let speeds: Vec<f64> = port
+                .free_cranes()
+                .iter()
+                .map(|crane| crane.processing_speed)
+                .collect();
+            assert_eq!(speeds, vec![1.0, 2.0]);
+        }
+    }
+
+    #[test]
+    fn test_player_reassign_crane() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.spawn_ships(3);
+        session.start_turn();
+        let (a, b, waiting) = (ShipId::new(0), ShipId::new(1), ShipId::new(2));
+        let crane = CraneId::new(0);
+        session.player_dock_ship(a, BerthId::new(0)).unwrap();
+        session.player_dock_ship(b, BerthId::new(1)).unwrap();
+        session.player_assign_crane(crane, a).unwrap();
+        session.start_turn();
+
+        // Reassigning to a ship that is not docked fails cleanly
+        assert!(session.player_reassign_crane(crane, waiting).is_err());
+        assert_eq!(session.player_port.cranes[&crane].assigned_to, Some(a));
+        assert_eq!(session.player_port.ships[&a].assigned_cranes, vec![crane]);
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS);
+
+        session.player_reassign_crane(crane, b).unwrap();
+
+        assert!(session.player_port.ships[&a].assigned_cranes.is_empty());
+        assert_eq!(session.player_port.ships[&b].assigned_cranes, vec![crane]);
+        assert_eq!(session.player_port.cranes[&crane].assigned_to, Some(b));
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS - 1);
+    }
+
+    #[test]
+    fn test_undo_reverts_a_reassignment_as_one_action() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.spawn_ships(2);
+        session.start_turn();
+        let (a, b) = (ShipId::new(0), ShipId::new(1));
+        let crane = CraneId::new(0);
+        session.player_dock_ship(a, BerthId::new(0)).unwrap();
+        session.player_dock_ship(b, BerthId::new(1)).unwrap();
+        session.player_assign_crane(crane, a).unwrap();
+        session.start_turn();
+
+        session.player_reassign_crane(crane, b).unwrap();
+        session.undo_last_action().unwrap();
+
+        // Back on its first ship in one step, for one action point
+        assert_eq!(session.player_port.cranes[&crane].assigned_to, Some(a));
+        assert_eq!(session.player_port.ships[&a].assigned_cranes, vec![crane]);
+        assert!(session.player_port.ships[&b].assigned_cranes.is_empty());
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS);
+        assert!(session.player_port.uncommitted_events().is_empty());
+        assert!(session.undo_last_action().is_err());
+    }
+
+    #[test]
+    fn test_player_assign_cranes_at_once() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.spawn_ships(1);
+        session.start_turn();
+        let ship_id = ShipId::new(0);
+        session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+
+        let cranes = [CraneId::new(0), CraneId::new(1)];
+        session.player_assign_cranes(ship_id, &cranes).unwrap();
+
+        assert_eq!(
+            session.player_port.ships[&ship_id].assigned_cranes,
+            cranes.to_vec()
+        );
+        for crane in cranes {
+            assert_eq!(
+                session.player_port.cranes[&crane].assigned_to,
+                Some(ship_id)
+            );
+        }
+        // Docking plus one action for both cranes
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS - 2);
+    }
+
+    #[test]
+    fn test_player_assign_cranes_rolls_back_when_one_is_busy() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
+        session.spawn_ships(2);
+        session.start_turn();
+        let (a, b) = (ShipId::new(0), ShipId::new(1));
+        session.player_dock_ship(a, BerthId::new(0)).unwrap();
+        session.player_dock_ship(b, BerthId::new(1)).unwrap();
+        session.player_assign_crane(CraneId::new(1), b).unwrap();
+        session.start_turn();
+        let events_before = session.player_port.uncommitted_events().len();
+
+        let err = session
+            .player_assign_cranes(a, &[CraneId::new(0), CraneId::new(1)])
+            .unwrap_err();
+
+        assert!(err.contains("Crane#1 is already assigned"), "{}", err);
+        assert!(err.contains("no crane was assigned"), "{}", err);
+        assert!(session.player_port.ships[&a].assigned_cranes.is_empty());
+        assert!(session.player_port.cranes[&CraneId::new(0)].is_free());
+        assert_eq!(
+            session.player_port.cranes[&CraneId::new(1)].assigned_to,
+            Some(b)
+        );
+        assert_eq!(
+            session.player_port.uncommitted_events().len(),
+            events_before
+        );
+        assert_eq!(session.action_points(), DEFAULT_ACTION_POINTS);
+    }
+
+    #[test]
+    fn test_outcome_variants() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let new_session = || GameSession::new(GameMode::VersusAI, player_id, ai_id);
+
+        let session = new_session();
+        assert_eq!(session.outcome(), GameResult::InProgress);
+        assert_eq!(session.get_winner(), None);
+
+        let mut session = new_session();
+        session.player_port.score = WIN_SCORE + 1;
+        assert_eq!(session.outcome(), GameResult::PlayerWins);
+        assert_eq!(session.get_winner(), Some("player"));
+
+        let mut session = new_session();
+        session.ai_port.score = WIN_SCORE + 1;
+        assert_eq!(session.outcome(), GameResult::AiWins);
+        assert_eq!(session.get_winner(), Some("ai"));
+
+        let mut session = new_session();
+        session.current_turn = MAX_TURNS;
+        assert_eq!(session.outcome(), GameResult::Tie);
+        assert_eq!(session.get_winner(), Some("tie"));
     }
 }
@@ -0,0 +1,29 @@
+// Save files - A replay plus the session state its events do not carry
+// (action points, weather), so a game can be picked up mid-turn
+
+use serde::{Deserialize, Serialize};
+
+use super::{ActiveEvent, GameMode, GameRules, SpawnConfig};
+use crate::infrastructure::ReplayEnvelope;
+
+/// Default file the CLI saves to when the player quits mid-game
+pub const AUTOSAVE_FILE: &str = "port_game_autosave.json";
+
+/// Content of a save file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub replay: ReplayEnvelope,
+    pub mode: GameMode,
+    pub rules: GameRules,
+    pub spawn_config: SpawnConfig,
+    pub active_events: Vec<ActiveEvent>,
+    pub crane_efficiency_modifier: f64,
+    pub ai_active_events: Vec<ActiveEvent>,
+    pub ai_crane_efficiency_modifier: f64,
+    pub action_points: u32,
+    pub ai_action_points: u32,
+    pub emergency_cranes_left: u32, // Cranes deployed this turn are handed back
+    pub events_enabled: bool,
+    pub ai_enabled: bool,
+    pub idle_turns: u32,
+}
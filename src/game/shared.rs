@@ -0,0 +1,148 @@
+// Shared session - One game mutated from several threads (e.g. web server workers)
+// Every call locks the session for its whole duration, so the MCTS engine and
+// its tree are only ever touched by one thread at a time.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::GameSession;
+use crate::application::queries::PortStateView;
+use crate::domain::value_objects::{BerthId, CraneId, ShipId};
+use crate::infrastructure::{EventStore, InMemoryEventStore};
+
+/// Cloneable handle on a `GameSession` behind a mutex
+pub struct SharedSession<E: EventStore = InMemoryEventStore> {
+    inner: Arc<Mutex<GameSession<E>>>,
+}
+
+impl<E: EventStore> Clone for SharedSession<E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<E: EventStore> SharedSession<E> {
+    pub fn new(session: GameSession<E>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(session)),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, GameSession<E>>, String> {
+        self.inner.lock().map_err(|e| e.to_string())
+    }
+
+    /// Run `f` with exclusive access to the session
+    pub fn with<T>(&self, f: impl FnOnce(&mut GameSession<E>) -> T) -> Result<T, String> {
+        Ok(f(&mut *self.lock()?))
+    }
+
+    /// Player port after docking `ship_id` at `berth_id`
+    pub fn dock_ship(&self, ship_id: ShipId, berth_id: BerthId) -> Result<PortStateView, String> {
+        let mut session = self.lock()?;
+        session.player_dock_ship(ship_id, berth_id)?;
+        Ok(session.port_state(&session.player_port))
+    }
+
+    /// Player port after assigning `crane_id` to `ship_id`
+    pub fn assign_crane(
+        &self,
+        crane_id: CraneId,
+        ship_id: ShipId,
+    ) -> Result<PortStateView, String> {
+        let mut session = self.lock()?;
+        session.player_assign_crane(crane_id, ship_id)?;
+        Ok(session.port_state(&session.player_port))
+    }
+
+    /// Player port after moving `crane_id` to `new_ship_id`
+    pub fn reassign_crane(
+        &self,
+        crane_id: CraneId,
+        new_ship_id: ShipId,
+    ) -> Result<PortStateView, String> {
+        let mut session = self.lock()?;
+        session.player_reassign_crane(crane_id, new_ship_id)?;
+        Ok(session.port_state(&session.player_port))
+    }
+
+    /// Player port once the turn is over (AI search included)
+    pub fn end_turn(&self) -> Result<PortStateView, String> {
+        let mut session = self.lock()?;
+        session.end_turn();
+        Ok(session.port_state(&session.player_port))
+    }
+
+    pub fn player_port_state(&self) -> Result<PortStateView, String> {
+        let session = self.lock()?;
+        Ok(session.port_state(&session.player_port))
+    }
+
+    pub fn ai_port_state(&self) -> Result<PortStateView, String> {
+        let session = self.lock()?;
+        Ok(session.port_state(&session.ai_port))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::domain::events::DomainEvent;
+    use crate::domain::value_objects::PlayerId;
+    use crate::game::{GameMode, GameRules, SpawnConfig};
+
+    #[test]
+    fn test_concurrent_actions_on_one_session() {
+        let mut session = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new())
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: 20,
+                ..SpawnConfig::default()
+            })
+            .unwrap()
+            .with_rules(GameRules {
+                action_points_per_turn: 10,
+                ..GameRules::default()
+            });
+        session.set_events_enabled(false);
+        session.start_turn();
+        session.spawn_ships(2);
+        let shared = SharedSession::new(session);
+
+        // Each thread works its own ship, berth and crane
+        let workers: Vec<_> = (0..2)
+            .map(|i| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    shared.dock_ship(ShipId::new(i), BerthId::new(i))?;
+                    shared.assign_crane(CraneId::new(i), ShipId::new(i))
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap().unwrap();
+        }
+
+        let state = shared.player_port_state().unwrap();
+        assert!(state.berths.iter().all(|berth| berth.occupied_by.is_some()));
+        assert!(state.cranes.iter().all(|crane| crane.assigned_to.is_some()));
+
+        let (docked, assigned, action_points) = shared
+            .with(|session| {
+                session.commit_events();
+                let events = session.event_store.load(session.session_id).unwrap();
+                let count = |f: fn(&DomainEvent) -> bool| events.iter().filter(|e| f(e)).count();
+                (
+                    count(|e| matches!(e, DomainEvent::ShipDocked { .. })),
+                    count(|e| matches!(e, DomainEvent::CraneAssigned { .. })),
+                    session.action_points(),
+                )
+            })
+            .unwrap();
+        assert_eq!((docked, assigned), (2, 2));
+        assert_eq!(action_points, 6);
+    }
+}
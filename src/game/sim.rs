@@ -0,0 +1,134 @@
+// Headless simulation - AI-vs-AI games without any UI
+// Both ports are played by MCTS; a seed makes the whole game reproducible.
+
+use uuid::Uuid;
+
+use super::{GameMode, GameOutcome, GameResult, GameRules, GameSession, SpawnConfig, MAX_TURNS};
+use crate::domain::value_objects::PlayerId;
+use crate::mcts::{MCTSConfig, MCTSEngine};
+use crate::utils::random;
+
+/// Parameters of a headless game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationRules {
+    pub max_turns: u32,
+    pub initial_ships: usize,  // Ships waiting in each port before turn 1
+    pub ships_per_turn: usize, // Arrivals in each port after every turn
+    pub spawn: SpawnConfig,
+}
+
+impl Default for SimulationRules {
+    fn default() -> Self {
+        Self {
+            max_turns: MAX_TURNS,
+            initial_ships: 3,
+            ships_per_turn: 1,
+            spawn: SpawnConfig::default(),
+        }
+    }
+}
+
+/// Final state of a headless game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationResult {
+    pub outcome: GameOutcome,
+    pub result: GameResult, // From the player port's point of view
+    pub player_score: i32,
+    pub ai_score: i32,
+    pub turns: u32,
+}
+
+/// Play a full game where MCTS drives both ports.
+/// Player ids are fixed so two runs with the same seed compare equal.
+pub fn run_ai_vs_ai(
+    seed: u64,
+    rules: SimulationRules,
+    mcts_config: MCTSConfig,
+) -> SimulationResult {
+    run_match(seed, rules, mcts_config.clone(), mcts_config)
+}
+
+/// Headless game between two engine configurations: `player_config` plays
+/// the player port, `ai_config` the AI port. Panics if `rules.spawn` fails
+/// `SpawnConfig::validate`.
+pub fn run_match(
+    seed: u64,
+    rules: SimulationRules,
+    player_config: MCTSConfig,
+    ai_config: MCTSConfig,
+) -> SimulationResult {
+    random::with_seed(seed, || {
+        let player_id = PlayerId::from_uuid(Uuid::from_u128(1));
+        let ai_id = PlayerId::from_uuid(Uuid::from_u128(2));
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_mcts_config(ai_config)
+            .with_spawn_config(rules.spawn)
+            .expect("simulation rules carry a valid spawn config")
+            .with_rules(GameRules {
+                max_turns: rules.max_turns,
+                ..GameRules::default()
+            });
+        let mut player_engine = MCTSEngine::new(player_config);
+
+        session.spawn_ships(rules.initial_ships);
+        session.start_turn();
+
+        while !session.is_game_over() {
+            std::mem::swap(&mut session.mcts_engine, &mut player_engine);
+            session.player_take_ai_turn();
+            std::mem::swap(&mut session.mcts_engine, &mut player_engine);
+            // end_turn plays the AI port and starts the next turn
+            session.end_turn();
+            session.spawn_ships(rules.ships_per_turn);
+        }
+
+        SimulationResult {
+            outcome: session
+                .game_outcome()
+                .expect("simulation only stops once the game is over"),
+            result: session.outcome(),
+            player_score: session.score(&session.player_port),
+            ai_score: session.score(&session.ai_port),
+            turns: session.current_turn,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcts::{ExplorationSchedule, RewardConfig};
+
+    fn fast_config() -> MCTSConfig {
+        MCTSConfig {
+            num_simulations: 10,
+            exploration_constant: 1.41,
+            max_depth: 5,
+            max_actions_per_turn: 1,
+            reward: RewardConfig::default(),
+            adversarial: false,
+            max_nodes: 10_000,
+            use_rave: false,
+            rave_equivalence: 300.0,
+            exploration_schedule: ExplorationSchedule::Constant,
+            rollout_noise: 5.0,
+            use_priors: false,
+        }
+    }
+
+    #[test]
+    fn test_seeded_games_are_reproducible() {
+        let rules = SimulationRules {
+            max_turns: 10,
+            ..SimulationRules::default()
+        };
+
+        for seed in 0..10 {
+            let first = run_ai_vs_ai(seed, rules, fast_config());
+            let second = run_ai_vs_ai(seed, rules, fast_config());
+
+            assert_eq!(first, second, "seed {} diverged", seed);
+            assert!(first.turns <= rules.max_turns);
+        }
+    }
+}
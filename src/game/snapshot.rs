@@ -0,0 +1,19 @@
+// Session snapshot - Everything a frontend needs to draw one frame
+
+use serde::{Deserialize, Serialize};
+
+use crate::application::queries::PortStateView;
+
+/// Consistent view of a session at one point in time
+/// (one call instead of a getter per field from WASM or a JSON dump)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    pub player_port: PortStateView,
+    pub ai_port: PortStateView,
+    pub current_turn: u32,
+    pub is_game_over: bool,
+    pub winner: Option<String>, // "player", "ai" or "tie"
+    pub active_effects: Vec<String>,
+    pub crane_efficiency: f64,
+    pub action_points: u32, // Player actions left this turn
+}
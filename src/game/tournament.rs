@@ -0,0 +1,176 @@
+// Tournament - Round-robin of AI configurations played headless
+// Every pair meets twice, once on each side, so neither gets the first move.
+
+use super::sim::{run_match, SimulationRules};
+use super::GameResult;
+use crate::mcts::MCTSConfig;
+
+/// Results of one configuration over the tournament
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+    pub total_score: i64,
+}
+
+impl Standing {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            wins: 0,
+            losses: 0,
+            ties: 0,
+            total_score: 0,
+        }
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.losses + self.ties
+    }
+
+    /// Mean final score per game (0.0 before any game)
+    pub fn average_score(&self) -> f64 {
+        match self.games() {
+            0 => 0.0,
+            games => self.total_score as f64 / games as f64,
+        }
+    }
+
+    fn record(&mut self, result: GameResult, score: i32) {
+        match result {
+            GameResult::PlayerWins => self.wins += 1,
+            GameResult::AiWins => self.losses += 1,
+            GameResult::Tie | GameResult::InProgress => self.ties += 1,
+        }
+        self.total_score += score as i64;
+    }
+}
+
+/// Final table, best first (most wins, then best average score)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentResult {
+    pub games_played: usize,
+    pub standings: Vec<Standing>,
+}
+
+/// Round-robin between named engine configurations
+#[derive(Debug, Clone)]
+pub struct Tournament {
+    entries: Vec<(String, MCTSConfig)>,
+    seed: u64,
+    rules: SimulationRules,
+}
+
+impl Tournament {
+    pub fn new(entries: Vec<(String, MCTSConfig)>, seed: u64) -> Self {
+        Self {
+            entries,
+            seed,
+            rules: SimulationRules::default(),
+        }
+    }
+
+    /// Play every game under these rules instead of the defaults
+    pub fn with_rules(mut self, rules: SimulationRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Play all games. Game `n` is seeded with `seed + n`, so a tournament
+    /// is as reproducible as a single headless game.
+    pub fn run(&self) -> TournamentResult {
+        let mut standings: Vec<Standing> = self
+            .entries
+            .iter()
+            .map(|(name, _)| Standing::new(name))
+            .collect();
+        let mut games_played = 0;
+
+        for first in 0..self.entries.len() {
+            for second in first + 1..self.entries.len() {
+                for (home, away) in [(first, second), (second, first)] {
+                    let result = run_match(
+                        self.seed.wrapping_add(games_played as u64),
+                        self.rules,
+                        self.entries[home].1.clone(),
+                        self.entries[away].1.clone(),
+                    );
+                    games_played += 1;
+
+                    let away_result = match result.result {
+                        GameResult::PlayerWins => GameResult::AiWins,
+                        GameResult::AiWins => GameResult::PlayerWins,
+                        other => other,
+                    };
+                    standings[home].record(result.result, result.player_score);
+                    standings[away].record(away_result, result.ai_score);
+                }
+            }
+        }
+
+        standings.sort_by(|a, b| {
+            b.wins
+                .cmp(&a.wins)
+                .then_with(|| b.average_score().total_cmp(&a.average_score()))
+        });
+
+        TournamentResult {
+            games_played,
+            standings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcts::{ExplorationSchedule, RewardConfig};
+
+    fn config(num_simulations: usize) -> MCTSConfig {
+        MCTSConfig {
+            num_simulations,
+            exploration_constant: 1.41,
+            max_depth: 5,
+            max_actions_per_turn: 1,
+            reward: RewardConfig::default(),
+            adversarial: false,
+            max_nodes: 10_000,
+            use_rave: false,
+            rave_equivalence: 300.0,
+            exploration_schedule: ExplorationSchedule::Constant,
+            rollout_noise: 5.0,
+            use_priors: false,
+        }
+    }
+
+    #[test]
+    fn test_two_config_tournament_standings() {
+        let tournament = Tournament::new(
+            vec![
+                ("quick".to_string(), config(5)),
+                ("deeper".to_string(), config(20)),
+            ],
+            7,
+        )
+        .with_rules(SimulationRules {
+            max_turns: 5,
+            ..SimulationRules::default()
+        });
+
+        let result = tournament.run();
+
+        // One game on each side
+        assert_eq!(result.games_played, 2);
+        assert_eq!(result.standings.len(), 2);
+
+        let games: u32 = result.standings.iter().map(Standing::games).sum();
+        assert_eq!(games as usize, 2 * result.games_played);
+        let wins: u32 = result.standings.iter().map(|s| s.wins).sum();
+        let losses: u32 = result.standings.iter().map(|s| s.losses).sum();
+        assert_eq!(wins, losses);
+
+        assert_eq!(result, tournament.run());
+    }
+}
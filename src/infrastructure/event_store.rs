@@ -2,16 +2,56 @@
 // In-memory implementation for MVP, can be replaced with DB later
 
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
 use uuid::Uuid;
 
+use super::replay::ReplayEnvelope;
 use crate::domain::events::DomainEvent;
 
 /// Event store trait for dependency inversion
 pub trait EventStore: Send + Sync {
     fn append(&mut self, aggregate_id: Uuid, events: Vec<DomainEvent>) -> Result<(), String>;
     fn load(&self, aggregate_id: Uuid) -> Result<Vec<DomainEvent>, String>;
-    fn all_events(&self) -> Vec<DomainEvent>;
+    /// Events of an aggregate one at a time, for projections that do not
+    /// need the whole history in memory (defaults to a loaded vector)
+    fn stream(&self, aggregate_id: Uuid) -> Result<EventStream<'_>, String> {
+        Ok(Box::new(self.load(aggregate_id)?.into_iter()))
+    }
+    fn all_events(&self) -> Result<Vec<DomainEvent>, String>;
+    /// Number of events recorded for an aggregate, without loading them
+    fn event_count(&self, aggregate_id: Uuid) -> usize;
+    /// Aggregates that have at least one recorded event, sorted
+    fn aggregate_ids(&self) -> Vec<Uuid>;
+}
+
+/// Lazily produced events of one aggregate
+pub type EventStream<'a> = Box<dyn Iterator<Item = DomainEvent> + 'a>;
+
+/// Boxed stores let a session pick its store at runtime
+impl<S: EventStore + ?Sized> EventStore for Box<S> {
+    fn append(&mut self, aggregate_id: Uuid, events: Vec<DomainEvent>) -> Result<(), String> {
+        (**self).append(aggregate_id, events)
+    }
+
+    fn load(&self, aggregate_id: Uuid) -> Result<Vec<DomainEvent>, String> {
+        (**self).load(aggregate_id)
+    }
+
+    fn stream(&self, aggregate_id: Uuid) -> Result<EventStream<'_>, String> {
+        (**self).stream(aggregate_id)
+    }
+
+    fn all_events(&self) -> Result<Vec<DomainEvent>, String> {
+        (**self).all_events()
+    }
+
+    fn event_count(&self, aggregate_id: Uuid) -> usize {
+        (**self).event_count(aggregate_id)
+    }
+
+    fn aggregate_ids(&self) -> Vec<Uuid> {
+        (**self).aggregate_ids()
+    }
 }
 
 /// In-memory event store for MVP
@@ -27,16 +67,16 @@ impl InMemoryEventStore {
         }
     }
 
-    /// Export events to JSON (for replay/debugging)
+    /// Export events to a versioned replay JSON (for replay/debugging)
     pub fn export_to_json(&self, aggregate_id: Uuid) -> Result<String, String> {
         let events = self.load(aggregate_id)?;
-        serde_json::to_string_pretty(&events).map_err(|e| e.to_string())
+        ReplayEnvelope::new(aggregate_id, events).to_json()
     }
 
-    /// Import events from JSON
+    /// Import events from replay JSON (envelope or legacy bare array)
     pub fn import_from_json(&mut self, aggregate_id: Uuid, json: &str) -> Result<(), String> {
-        let events: Vec<DomainEvent> = serde_json::from_str(json).map_err(|e| e.to_string())?;
-        self.append(aggregate_id, events)
+        let replay = ReplayEnvelope::from_json(json)?;
+        self.append(aggregate_id, replay.events)
     }
 }
 
@@ -64,9 +104,48 @@ impl EventStore for InMemoryEventStore {
         Ok(store.get(&aggregate_id).cloned().unwrap_or_default())
     }
 
-    fn all_events(&self) -> Vec<DomainEvent> {
+    /// Clones one event at a time; the read lock is held until the stream is dropped
+    fn stream(&self, aggregate_id: Uuid) -> Result<EventStream<'_>, String> {
+        let store = self.events.read().map_err(|e| e.to_string())?;
+        Ok(Box::new(LockedEvents {
+            store,
+            aggregate_id,
+            next: 0,
+        }))
+    }
+
+    fn all_events(&self) -> Result<Vec<DomainEvent>, String> {
+        let store = self.events.read().map_err(|e| e.to_string())?;
+        Ok(store.values().flat_map(|events| events.clone()).collect())
+    }
+
+    fn event_count(&self, aggregate_id: Uuid) -> usize {
         let store = self.events.read().unwrap();
-        store.values().flat_map(|events| events.clone()).collect()
+        store.get(&aggregate_id).map_or(0, Vec::len)
+    }
+
+    fn aggregate_ids(&self) -> Vec<Uuid> {
+        let store = self.events.read().unwrap();
+        let mut ids: Vec<Uuid> = store.keys().copied().collect();
+        ids.sort();
+        ids
+    }
+}
+
+/// Iterator over the events of one aggregate behind the store's read lock
+struct LockedEvents<'a> {
+    store: RwLockReadGuard<'a, HashMap<Uuid, Vec<DomainEvent>>>,
+    aggregate_id: Uuid,
+    next: usize,
+}
+
+impl Iterator for LockedEvents<'_> {
+    type Item = DomainEvent;
+
+    fn next(&mut self) -> Option<DomainEvent> {
+        let event = self.store.get(&self.aggregate_id)?.get(self.next)?.clone();
+        self.next += 1;
+        Some(event)
     }
 }
 
@@ -86,6 +165,9 @@ mod tests {
             ship_id: ShipId::new(1),
             container_count: 50,
             arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
         };
 
         store.append(aggregate_id, vec![event.clone()]).unwrap();
@@ -107,6 +189,9 @@ mod tests {
             ship_id: ShipId::new(1),
             container_count: 50,
             arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
         };
 
         let event2 = DomainEvent::ShipArrived {
@@ -114,6 +199,9 @@ mod tests {
             ship_id: ShipId::new(2),
             container_count: 30,
             arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
         };
 
         store.append(agg1, vec![event1]).unwrap();
@@ -121,7 +209,81 @@ mod tests {
 
         assert_eq!(store.load(agg1).unwrap().len(), 1);
         assert_eq!(store.load(agg2).unwrap().len(), 1);
-        assert_eq!(store.all_events().len(), 2);
+        assert_eq!(store.all_events().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_stream_matches_event_count() {
+        let mut store = InMemoryEventStore::new();
+        let aggregate_id = Uuid::new_v4();
+        let arrival = |aggregate_id, ship| DomainEvent::ShipArrived {
+            metadata: EventMetadata::new(aggregate_id, ship as u64 + 1),
+            ship_id: ShipId::new(ship),
+            container_count: 20,
+            arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
+        };
+        for ship in 0..5 {
+            store
+                .append(aggregate_id, vec![arrival(aggregate_id, ship)])
+                .unwrap();
+        }
+        let other = Uuid::new_v4();
+        store.append(other, vec![arrival(other, 9)]).unwrap();
+
+        let streamed = store.stream(aggregate_id).unwrap().count();
+        assert_eq!(streamed, store.event_count(aggregate_id));
+        assert_eq!(store.stream(Uuid::new_v4()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_all_events_survives_poisoned_lock() {
+        let store = InMemoryEventStore::new();
+
+        // A panicking writer (e.g. a subscriber handler) poisons the shared lock
+        let shared = store.clone();
+        let result = std::thread::spawn(move || {
+            let _guard = shared.events.write().unwrap();
+            panic!("handler failed while holding the lock");
+        })
+        .join();
+        assert!(result.is_err());
+
+        assert!(store.all_events().is_err());
+        assert!(store.load(Uuid::new_v4()).is_err());
+    }
+
+    #[test]
+    fn test_event_counts_and_aggregate_ids() {
+        let mut store = InMemoryEventStore::new();
+        let agg1 = Uuid::new_v4();
+        let agg2 = Uuid::new_v4();
+
+        let arrival = |aggregate_id, version, ship| DomainEvent::ShipArrived {
+            metadata: EventMetadata::new(aggregate_id, version),
+            ship_id: ShipId::new(ship),
+            container_count: 20,
+            arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
+        };
+
+        store
+            .append(agg1, vec![arrival(agg1, 1, 1), arrival(agg1, 2, 2)])
+            .unwrap();
+        store.append(agg1, vec![arrival(agg1, 3, 3)]).unwrap();
+        store.append(agg2, vec![arrival(agg2, 1, 4)]).unwrap();
+
+        assert_eq!(store.event_count(agg1), 3);
+        assert_eq!(store.event_count(agg2), 1);
+        assert_eq!(store.event_count(Uuid::new_v4()), 0);
+
+        let mut expected = vec![agg1, agg2];
+        expected.sort();
+        assert_eq!(store.aggregate_ids(), expected);
     }
 
     #[test]
@@ -134,6 +296,9 @@ mod tests {
             ship_id: ShipId::new(1),
             container_count: 50,
             arrival_time: 0.0,
+            priority: false,
+            cargo: None,
+            player: None,
         };
 
         store.append(aggregate_id, vec![event]).unwrap();
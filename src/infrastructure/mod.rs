@@ -1,5 +1,7 @@
 // Infrastructure layer - Technical concerns (persistence, I/O)
 
 pub mod event_store;
+pub mod replay;
 
-pub use event_store::{EventStore, InMemoryEventStore};
+pub use event_store::{EventStore, EventStream, InMemoryEventStore};
+pub use replay::{diff_replays, ReplayEnvelope, ReplayError, REPLAY_FORMAT_VERSION};
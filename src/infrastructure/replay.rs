@@ -0,0 +1,455 @@
+// Replay files - Versioned envelope around a session's event stream
+// v1: bare JSON array of events (no envelope)
+// v2: envelope, `ContainerProcessed` carries the acting player
+// v3: `ShipUndocked` carries the player too
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::domain::events::DomainEvent;
+
+/// Replay format written by this build
+pub const REPLAY_FORMAT_VERSION: u32 = 3;
+
+/// Why a replay could not be loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// Not JSON at all (e.g. a truncated file), position of the first error
+    InvalidJson {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// JSON, but neither an event array nor an envelope
+    InvalidEnvelope(String),
+    /// An event whose `type` tag this build does not know
+    UnknownEventType(String),
+    /// A known event type with missing or mistyped fields
+    InvalidEvent { index: usize, message: String },
+    /// Written by a newer build
+    VersionMismatch { found: u32, supported: u32 },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::InvalidJson {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "Replay is not valid JSON (line {}, column {}): {}",
+                line, column, message
+            ),
+            ReplayError::InvalidEnvelope(message) => write!(f, "Invalid replay: {}", message),
+            ReplayError::UnknownEventType(event_type) => {
+                write!(f, "Replay contains an unknown event type: {}", event_type)
+            }
+            ReplayError::InvalidEvent { index, message } => {
+                write!(f, "Replay event #{} is invalid: {}", index, message)
+            }
+            ReplayError::VersionMismatch { found, supported } => write!(
+                f,
+                "Replay format v{} is newer than supported v{}",
+                found, supported
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<ReplayError> for String {
+    fn from(error: ReplayError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Versioned replay file content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayEnvelope {
+    pub format_version: u32,
+    pub session_id: Uuid,
+    pub events: Vec<DomainEvent>,
+}
+
+impl ReplayEnvelope {
+    pub fn new(session_id: Uuid, events: Vec<DomainEvent>) -> Self {
+        Self {
+            format_version: REPLAY_FORMAT_VERSION,
+            session_id,
+            events,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    /// Parse a replay of any known version, migrating it to the current format.
+    /// A bare event array is treated as v1.
+    pub fn from_json(json: &str) -> Result<Self, ReplayError> {
+        let value: Value = serde_json::from_str(json).map_err(|e| ReplayError::InvalidJson {
+            line: e.line(),
+            column: e.column(),
+            message: e.to_string(),
+        })?;
+        let invalid = |message: &str| ReplayError::InvalidEnvelope(message.to_string());
+
+        let (format_version, session_id, mut events) = match value {
+            Value::Array(events) => {
+                // Legacy replays only know the session through event metadata
+                let session_id = events
+                    .first()
+                    .and_then(|event| event.pointer("/data/metadata/aggregate_id"))
+                    .and_then(Value::as_str)
+                    .and_then(|id| Uuid::parse_str(id).ok())
+                    .unwrap_or_else(Uuid::nil);
+                (1, session_id, events)
+            }
+            Value::Object(mut envelope) => {
+                let format_version = envelope
+                    .get("format_version")
+                    .and_then(Value::as_u64)
+                    .ok_or_else(|| invalid("envelope is missing format_version"))?
+                    as u32;
+                let session_id = envelope
+                    .get("session_id")
+                    .and_then(Value::as_str)
+                    .and_then(|id| Uuid::parse_str(id).ok())
+                    .ok_or_else(|| invalid("envelope has an invalid session_id"))?;
+                let events = match envelope.remove("events") {
+                    Some(Value::Array(events)) => events,
+                    _ => return Err(invalid("envelope is missing its events")),
+                };
+                (format_version, session_id, events)
+            }
+            _ => return Err(invalid("expected an event array or an envelope")),
+        };
+
+        if format_version > REPLAY_FORMAT_VERSION {
+            return Err(ReplayError::VersionMismatch {
+                found: format_version,
+                supported: REPLAY_FORMAT_VERSION,
+            });
+        }
+
+        if format_version < 2 {
+            migrate_v1_to_v2(&mut events);
+        }
+        if format_version < 3 {
+            migrate_v2_to_v3(&mut events);
+        }
+
+        let events = events
+            .into_iter()
+            .enumerate()
+            .map(|(index, event)| parse_event(index, event))
+            .collect::<Result<Vec<DomainEvent>, _>>()?;
+
+        Ok(Self::new(session_id, events))
+    }
+}
+
+/// Index of the first event where two replays part ways, None if they match.
+/// Events are compared by type and content, ignoring what differs between two
+/// runs of the same game: event ids, timestamps, session ids, and the player
+/// ids themselves (numbered by first appearance in each replay). A replay that
+/// stops early diverges where it ends.
+pub fn diff_replays(a: &[DomainEvent], b: &[DomainEvent]) -> Option<usize> {
+    let (a, b) = (comparable_events(a), comparable_events(b));
+    (0..a.len().max(b.len())).find(|&index| a.get(index) != b.get(index))
+}
+
+fn comparable_events(events: &[DomainEvent]) -> Vec<Value> {
+    let mut players = HashMap::new();
+    events
+        .iter()
+        .map(|event| {
+            let mut value = serde_json::to_value(event).unwrap_or(Value::Null);
+            if let Some(metadata) = value
+                .pointer_mut("/data/metadata")
+                .and_then(Value::as_object_mut)
+            {
+                for field in ["event_id", "aggregate_id", "timestamp"] {
+                    metadata.remove(field);
+                }
+            }
+            number_players(&mut value, &mut players);
+            value
+        })
+        .collect()
+}
+
+/// Replace every id left in `value` by its order of first appearance
+fn number_players(value: &mut Value, players: &mut HashMap<String, usize>) {
+    match value {
+        Value::String(text) if Uuid::parse_str(text).is_ok() => {
+            let next = players.len();
+            let seat = *players.entry(text.clone()).or_insert(next);
+            *value = Value::from(seat);
+        }
+        Value::Array(items) => {
+            for item in items {
+                number_players(item, players);
+            }
+        }
+        Value::Object(fields) => {
+            for field in fields.values_mut() {
+                number_players(field, players);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_event(index: usize, event: Value) -> Result<DomainEvent, ReplayError> {
+    let event_type = event
+        .get("type")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    serde_json::from_value(event).map_err(|e| match event_type {
+        // serde reports tags missing from `DomainEvent` as an unknown variant
+        Some(event_type) if e.to_string().starts_with("unknown variant") => {
+            ReplayError::UnknownEventType(event_type)
+        }
+        _ => ReplayError::InvalidEvent {
+            index,
+            message: e.to_string(),
+        },
+    })
+}
+
+/// v1 `ContainerProcessed` events had no player: mark them with the nil id
+fn migrate_v1_to_v2(events: &mut [Value]) {
+    add_nil_player(events, "ContainerProcessed");
+}
+
+/// v2 `ShipUndocked` events had no player: mark them with the nil id
+fn migrate_v2_to_v3(events: &mut [Value]) {
+    add_nil_player(events, "ShipUndocked");
+}
+
+fn add_nil_player(events: &mut [Value], event_type: &str) {
+    for event in events.iter_mut() {
+        if event.get("type").and_then(Value::as_str) != Some(event_type) {
+            continue;
+        }
+        if let Some(data) = event.get_mut("data").and_then(Value::as_object_mut) {
+            data.entry("player")
+                .or_insert_with(|| Value::String(Uuid::nil().to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::events::EventMetadata;
+    use crate::domain::value_objects::{BerthId, CraneId, GameMode, PlayerId, ShipId};
+
+    /// Short game of one docked and unloaded ship, played by `player`
+    fn sample_game(player: PlayerId) -> Vec<DomainEvent> {
+        let session_id = Uuid::new_v4();
+        let metadata = |version| EventMetadata::new(session_id, version);
+        let (ship_id, berth_id, crane_id) = (ShipId::new(1), BerthId::new(0), CraneId::new(0));
+        vec![
+            DomainEvent::GameStarted {
+                metadata: metadata(1),
+                player_id: player,
+                ai_player_id: PlayerId::new(),
+                num_berths: 2,
+                num_cranes: 2,
+                crane_speeds: vec![1.0, 2.0],
+                mode: GameMode::VersusAI,
+                extra_players: Vec::new(),
+            },
+            DomainEvent::ShipArrived {
+                metadata: metadata(1),
+                ship_id,
+                container_count: 20,
+                arrival_time: 0.0,
+                priority: false,
+                cargo: None,
+                player: None,
+            },
+            DomainEvent::ShipDocked {
+                metadata: metadata(2),
+                ship_id,
+                berth_id,
+                player,
+                docking_time: 1.0,
+            },
+            DomainEvent::CraneAssigned {
+                metadata: metadata(3),
+                crane_id,
+                ship_id,
+                player,
+                assignment_time: 1.0,
+            },
+            DomainEvent::ContainerProcessed {
+                metadata: metadata(4),
+                crane_id,
+                ship_id,
+                player,
+                containers_remaining: 10,
+                container_type: Default::default(),
+            },
+            DomainEvent::ContainerProcessed {
+                metadata: metadata(5),
+                crane_id,
+                ship_id,
+                player,
+                containers_remaining: 0,
+                container_type: Default::default(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_envelope_round_trip() {
+        let session_id = Uuid::new_v4();
+        let player = PlayerId::new();
+        let events = vec![DomainEvent::ContainerProcessed {
+            metadata: EventMetadata::new(session_id, 1),
+            crane_id: CraneId::new(0),
+            ship_id: ShipId::new(1),
+            player,
+            containers_remaining: 10,
+            container_type: Default::default(),
+        }];
+
+        let json = ReplayEnvelope::new(session_id, events).to_json().unwrap();
+        let replay = ReplayEnvelope::from_json(&json).unwrap();
+
+        assert_eq!(replay.format_version, REPLAY_FORMAT_VERSION);
+        assert_eq!(replay.session_id, session_id);
+        match &replay.events[0] {
+            DomainEvent::ContainerProcessed { player: loaded, .. } => assert_eq!(*loaded, player),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_legacy_bare_array_is_migrated() {
+        let session_id = Uuid::new_v4();
+        let json = format!(
+            r#"[{{"type":"ContainerProcessed","data":{{
+                "metadata":{{"event_id":"{}","aggregate_id":"{}",
+                             "timestamp":"2024-01-01T00:00:00Z","version":1}},
+                "crane_id":0,"ship_id":1,"containers_remaining":10}}}}]"#,
+            Uuid::new_v4(),
+            session_id
+        );
+
+        let replay = ReplayEnvelope::from_json(&json).unwrap();
+
+        assert_eq!(replay.format_version, REPLAY_FORMAT_VERSION);
+        assert_eq!(replay.session_id, session_id);
+        match &replay.events[0] {
+            DomainEvent::ContainerProcessed { player, .. } => {
+                assert_eq!(*player, PlayerId(Uuid::nil()))
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_v2_ship_undocked_is_migrated() {
+        let session_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"format_version":2,"session_id":"{}","events":[{{"type":"ShipUndocked","data":{{
+                "metadata":{{"event_id":"{}","aggregate_id":"{}",
+                             "timestamp":"2024-01-01T00:00:00Z","version":1}},
+                "ship_id":1,"berth_id":0,"completion_time":4.0,"containers_processed":20}}}}]}}"#,
+            session_id,
+            Uuid::new_v4(),
+            session_id
+        );
+
+        let replay = ReplayEnvelope::from_json(&json).unwrap();
+
+        match &replay.events[0] {
+            DomainEvent::ShipUndocked { player, .. } => assert_eq!(*player, PlayerId(Uuid::nil())),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_newer_format_is_rejected() {
+        let json = format!(
+            r#"{{"format_version":99,"session_id":"{}","events":[]}}"#,
+            Uuid::new_v4()
+        );
+
+        assert_eq!(
+            ReplayEnvelope::from_json(&json).unwrap_err(),
+            ReplayError::VersionMismatch {
+                found: 99,
+                supported: REPLAY_FORMAT_VERSION,
+            }
+        );
+    }
+
+    #[test]
+    fn test_truncated_json_reports_its_position() {
+        let json = ReplayEnvelope::new(Uuid::new_v4(), Vec::new())
+            .to_json()
+            .unwrap();
+        let truncated = &json[..json.len() - 2];
+
+        match ReplayEnvelope::from_json(truncated) {
+            Err(ReplayError::InvalidJson { line, column, .. }) => {
+                assert_eq!(line, truncated.lines().count());
+                assert!(column > 0);
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_event_type_is_named() {
+        let session_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"format_version":{},"session_id":"{}","events":[{{"type":"ShipTeleported","data":{{}}}}]}}"#,
+            REPLAY_FORMAT_VERSION, session_id
+        );
+
+        assert_eq!(
+            ReplayEnvelope::from_json(&json).unwrap_err(),
+            ReplayError::UnknownEventType("ShipTeleported".to_string())
+        );
+    }
+
+    #[test]
+    fn test_replays_differing_only_in_ids_and_timestamps_match() {
+        // Separate runs: new session, event ids, timestamps and players
+        let a = sample_game(PlayerId::new());
+        let b = sample_game(PlayerId::new());
+
+        assert_eq!(diff_replays(&a, &b), None);
+    }
+
+    #[test]
+    fn test_first_divergence_is_reported() {
+        let player = PlayerId::new();
+        let a = sample_game(player);
+        let mut b = sample_game(player);
+        if let DomainEvent::ContainerProcessed {
+            containers_remaining,
+            ..
+        } = &mut b[4]
+        {
+            *containers_remaining = 12;
+        }
+
+        assert_eq!(diff_replays(&a, &b), Some(4));
+        assert_eq!(diff_replays(&a, &a[..3]), Some(3));
+    }
+}
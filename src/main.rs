@@ -1,11 +1,33 @@
 // Port Game - Interactive MCTS Strategy Game
 // Phase 1: Turn-based gameplay with CLI
 
+use std::{env, fs};
+
 use port_game::cli::*;
 use port_game::domain::value_objects::PlayerId;
-use port_game::game::{GameMode, GameSession};
+use port_game::game::{GameMode, GameRules, GameSession};
+use port_game::infrastructure::ReplayEnvelope;
+
+/// `port_game --replay <file>`: print the timeline of a saved replay
+fn view_replay(path: &str) {
+    match fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| ReplayEnvelope::from_json(&json).map_err(String::from))
+    {
+        Ok(replay) => println!("{}", display_event_log(&replay.events)),
+        Err(e) => println!("❌ Failed to read replay {}: {}", path, e),
+    }
+}
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    if let [_, flag, path] = args.as_slice() {
+        if flag == "--replay" {
+            view_replay(path);
+            return;
+        }
+    }
+
     println!("╔════════════════════════════════════════════════════════════╗");
     println!("║         🚢 PORT TERMINAL MANAGER 🚢                         ║");
     println!("║              MCTS Strategy Game                            ║");
@@ -13,37 +35,64 @@ fn main() {
     println!("║  Manage your port efficiently and beat the AI!            ║");
     println!("╚════════════════════════════════════════════════════════════╝\n");
 
-    // Initialize game
-    let player_id = PlayerId::new();
-    let ai_id = PlayerId::new();
-    let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+    // Pick up a game left mid-way, or start a new one
+    let autosave = GameSession::autosave_path();
+    let saved = if autosave.exists() && confirm("💾 Resume your saved game?") {
+        match fs::read_to_string(&autosave)
+            .map_err(|e| e.to_string())
+            .and_then(|json| GameSession::load_from_json(&json))
+        {
+            Ok(session) => Some(session),
+            Err(e) => {
+                println!("❌ Failed to load saved game: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let resumed = saved.is_some();
+    let mut session = saved.unwrap_or_else(|| {
+        GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new()).with_rules(
+            GameRules {
+                max_turns: 10,
+                ..GameRules::default()
+            },
+        )
+    });
 
-    println!("👤 Your Port ID: {}", player_id);
-    println!("🤖 AI Port ID: {}\n", ai_id);
+    println!("👤 Your Port ID: {}", session.player_port.player_id);
+    println!("🤖 AI Port ID: {}\n", session.ai_port.player_id);
 
-    // Initial ships
-    println!("📦 Spawning initial ships...");
-    session.spawn_ships(3);
-    println!("✅ 3 ships have arrived and are waiting to dock!\n");
+    if resumed {
+        println!("✅ Resuming at turn {}\n", session.current_turn);
+    } else {
+        // Initial ships
+        println!("📦 Spawning initial ships...");
+        session.spawn_ships(3);
+        println!("✅ 3 ships have arrived and are waiting to dock!\n");
+    }
 
     wait_for_enter();
 
     // Main game loop
-    let max_turns = 10;
+    let max_turns = session.rules.max_turns;
 
-    for turn in 1..=max_turns {
-        clear_screen();
-        display_header(turn);
+    for turn in session.current_turn.max(1)..=max_turns {
+        // Start turn (a resumed game continues the turn it was saved in)
+        if turn > session.current_turn {
+            session.start_turn();
+        }
 
-        // Start turn
-        session.start_turn();
+        clear_screen();
+        display_header(turn, session.turns_remaining());
 
         // Show current state
         display_port_status(&session.player_port, "📊 YOUR PORT");
 
         // Player actions loop
         loop {
-            display_menu();
+            display_menu(session.action_points());
 
             match get_menu_choice() {
                 Ok(choice) => match process_player_choice(choice, &session) {
@@ -78,6 +127,26 @@ fn main() {
                         }
                         wait_for_enter();
                     }
+                    Ok(PlayerAction::AssignCranes { ship_id, crane_ids }) => {
+                        match session.player_assign_cranes(ship_id, &crane_ids) {
+                            Ok(_) => {
+                                let cranes: Vec<String> =
+                                    crane_ids.iter().map(|c| format!("#{}", c.0)).collect();
+                                display_action_result(
+                                    true,
+                                    &format!(
+                                        "Cranes {} assigned to Ship #{}",
+                                        cranes.join(", "),
+                                        ship_id.0
+                                    ),
+                                );
+                            }
+                            Err(e) => {
+                                display_action_result(false, &e);
+                            }
+                        }
+                        wait_for_enter();
+                    }
                     Ok(PlayerAction::ViewState) => {
                         clear_screen();
                         display_port_status(&session.player_port, "📊 YOUR PORT");
@@ -88,13 +157,40 @@ fn main() {
                         display_comparison(&session);
                         wait_for_enter();
                     }
+                    Ok(PlayerAction::ViewStatistics) => {
+                        clear_screen();
+                        println!("{}", display_statistics(&session));
+                        wait_for_enter();
+                    }
+                    Ok(PlayerAction::Pass) => {
+                        match session.player_pass() {
+                            Ok(_) => display_action_result(true, "You passed this action"),
+                            Err(e) => display_action_result(false, &e),
+                        }
+                        wait_for_enter();
+                    }
                     Ok(PlayerAction::EndTurn) => {
+                        if let Some(warning) = session.idle_docked_warning() {
+                            println!("\n⚠️  {}", warning);
+                        }
                         println!("\n⏭️  Ending your turn...");
                         break;
                     }
+                    Ok(PlayerAction::Cancel) => {
+                        println!("\n↩️  Cancelled");
+                    }
                     Ok(PlayerAction::Quit) => {
                         if confirm("Are you sure you want to quit?") {
-                            println!("\n👋 Thanks for playing!");
+                            match session.save_to_json().and_then(|json| {
+                                fs::write(&autosave, json).map_err(|e| e.to_string())
+                            }) {
+                                Ok(_) => println!(
+                                    "\n💾 Game saved to {}, resume it next time",
+                                    autosave.display()
+                                ),
+                                Err(e) => println!("\n❌ Failed to save game: {}", e),
+                            }
+                            println!("👋 Thanks for playing!");
                             return;
                         }
                     }
@@ -142,7 +238,12 @@ fn main() {
         // AI turn
         println!("🤖 AI is thinking...");
         session.ai_take_turn();
-        println!("✅ AI completed its turn");
+        let stats = session.mcts_engine.get_statistics();
+        println!(
+            "✅ AI completed its turn ({} ms of search, {:.0} nodes/s)",
+            stats.elapsed.as_millis(),
+            stats.nodes_per_second
+        );
 
         // Show AI port
         display_port_status(&session.ai_port, "🤖 AI PORT");
@@ -166,19 +267,21 @@ fn main() {
         wait_for_enter();
     }
 
-    // Game end
+    // Game end: nothing left to resume
+    fs::remove_file(&autosave).ok();
     clear_screen();
-    let winner = session.get_winner();
-    display_game_end(&session, winner);
+    display_game_end(&session, session.outcome());
 
     // Export replay
     if confirm("\n💾 Save game replay to file?") {
         match session.export_replay() {
             Ok(json) => {
-                use std::fs;
                 let filename = format!("replay_{}.json", session.session_id);
                 match fs::write(&filename, json) {
-                    Ok(_) => println!("✅ Replay saved to {}", filename),
+                    Ok(_) => println!(
+                        "✅ Replay saved to {} (view it with --replay {})",
+                        filename, filename
+                    ),
                     Err(e) => println!("❌ Failed to save replay: {}", e),
                 }
             }
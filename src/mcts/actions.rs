@@ -1,11 +1,14 @@
 // MCTS Actions - Possible moves in the game
 
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
 use crate::domain::value_objects::{BerthId, CraneId, ShipId};
 
 /// All possible actions in the game (for MCTS simulation)
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Ordered by variant, then ids: used to break ties deterministically
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum MCTSAction {
     /// Dock a ship to a berth
     DockShip { ship_id: ShipId, berth_id: BerthId },
@@ -31,6 +34,21 @@ impl MCTSAction {
     }
 }
 
+impl fmt::Display for MCTSAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MCTSAction::DockShip { ship_id, berth_id } => {
+                write!(f, "Dock {} at {}", ship_id, berth_id)
+            }
+            MCTSAction::AssignCrane { crane_id, ship_id } => {
+                write!(f, "Assign {} to {}", crane_id, ship_id)
+            }
+            MCTSAction::UnassignCrane { crane_id } => write!(f, "Unassign {}", crane_id),
+            MCTSAction::Pass => write!(f, "Pass"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5,11 +5,16 @@ pub mod actions;
 pub mod simulation;
 pub mod tree;
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use crate::domain::aggregates::Port;
-use crate::utils::random;
+use crate::domain::scoring::ScoringStrategy;
+use crate::utils::{clock, random};
 
 pub use actions::MCTSAction;
-pub use tree::{MCTSNode, MCTSTree};
+pub use simulation::RewardConfig;
+pub use tree::{MCTSNode, MCTSTree, NodeDump, RaveStat, StateDump, TreeDump};
 
 /// MCTS engine configuration
 #[derive(Debug, Clone)]
@@ -18,6 +23,36 @@ pub struct MCTSConfig {
     pub exploration_constant: f64, // UCB1 constant (√2 is standard)
     pub max_depth: usize,
     pub max_actions_per_turn: usize,
+    pub reward: RewardConfig,
+    pub adversarial: bool, // Negamax-style backpropagation (sign flips per ply)
+    pub max_nodes: usize,  // Tree size cap, keeps memory bounded (WASM)
+    pub use_rave: bool,    // Blend AMAF statistics into selection
+    pub rave_equivalence: f64, // RAVE k: visits at which beta falls to 1/2
+    pub exploration_schedule: ExplorationSchedule,
+    pub rollout_noise: f64, // Rollout scores get ±noise added, 0.0 = deterministic
+    pub use_priors: bool,   // PUCT selection weighted by `tree::action_prior`
+}
+
+/// How the UCB1 exploration constant evolves as the game goes on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExplorationSchedule {
+    /// `exploration_constant` on every turn
+    Constant,
+    /// Multiplied by `rate` each turn after the first, never below `min`:
+    /// explore early, exploit late
+    Decay { rate: f64, min: f64 },
+}
+
+impl ExplorationSchedule {
+    /// Exploration constant to use on the given turn
+    pub fn constant_at(&self, base: f64, turn: u32) -> f64 {
+        match *self {
+            ExplorationSchedule::Constant => base,
+            ExplorationSchedule::Decay { rate, min } => {
+                (base * rate.powi(turn.saturating_sub(1) as i32)).max(min)
+            }
+        }
+    }
 }
 
 impl Default for MCTSConfig {
@@ -27,6 +62,14 @@ impl Default for MCTSConfig {
             exploration_constant: 1.41, // √2
             max_depth: 50,
             max_actions_per_turn: 1,
+            reward: RewardConfig::default(),
+            adversarial: false,
+            max_nodes: 10_000,
+            use_rave: false,
+            rave_equivalence: 300.0,
+            exploration_schedule: ExplorationSchedule::Constant,
+            rollout_noise: 5.0,
+            use_priors: false,
         }
     }
 }
@@ -35,6 +78,19 @@ impl Default for MCTSConfig {
 pub struct MCTSEngine {
     config: MCTSConfig,
     tree: MCTSTree,
+    scoring: Option<Arc<dyn ScoringStrategy>>, // None = `config.reward`
+    turn: u32, // Game turn of the next search, drives the exploration schedule
+    last_search: Option<SearchSummary>, // None before any search or when nothing was playable
+    last_elapsed: Duration, // Wall time of the last search
+}
+
+/// What the last search chose and how clearly (see `MCTSEngine::explain_last_search`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchSummary {
+    pub best_action: MCTSAction,
+    pub confidence: f64,    // Share of root visits spent on the chosen action
+    pub average_score: f64, // Mean rollout score of the chosen action
+    pub runner_up: Option<(MCTSAction, f64)>, // Next most visited action and its mean score
 }
 
 impl MCTSEngine {
@@ -42,11 +98,39 @@ impl MCTSEngine {
         Self {
             config,
             tree: MCTSTree::new(),
+            scoring: None,
+            turn: 0,
+            last_search: None,
+            last_elapsed: Duration::ZERO,
         }
     }
 
+    /// Evaluate rollouts with the given strategy instead of `config.reward`
+    pub fn set_scoring(&mut self, strategy: Arc<dyn ScoringStrategy>) {
+        self.scoring = Some(strategy);
+    }
+
+    /// Score rollouts with new weights (ignored while a scoring strategy is set)
+    pub fn set_reward(&mut self, reward: RewardConfig) {
+        self.config.reward = reward;
+    }
+
+    /// Game turn the next searches are played on
+    pub fn set_turn(&mut self, turn: u32) {
+        self.turn = turn;
+    }
+
+    /// Exploration constant of the current turn under the configured schedule
+    pub fn exploration_constant(&self) -> f64 {
+        self.config
+            .exploration_schedule
+            .constant_at(self.config.exploration_constant, self.turn)
+    }
+
     /// Run MCTS search and return best action
     pub fn search(&mut self, port: &Port) -> Option<MCTSAction> {
+        let started_ms = clock::now_ms();
+
         // Initialize root node with current state
         self.tree.init_root(port.clone());
 
@@ -58,25 +142,95 @@ impl MCTSEngine {
             let expand_id = self.expand(node_id, port);
 
             // 3. Simulation: play out randomly to get a score
-            let score = self.simulate(expand_id);
+            let mut rollout = Vec::new();
+            let score = self.simulate(expand_id, &mut rollout);
 
             // 4. Backpropagation: update node statistics
-            self.backpropagate(expand_id, score);
+            self.backpropagate(expand_id, score, &rollout);
         }
 
+        self.last_search = self.summarize_search();
+        self.last_elapsed =
+            Duration::from_secs_f64((clock::now_ms() - started_ms).max(0.0) / 1000.0);
+
         // Return best action from root
         self.tree.best_action()
     }
 
+    fn summarize_search(&self) -> Option<SearchSummary> {
+        let ranked = self.tree.ranked_root_children();
+        let (best, rest) = ranked.split_first()?;
+        let best = self.tree.get_node(*best);
+        let runner_up = rest
+            .first()
+            .map(|&id| self.tree.get_node(id))
+            .and_then(|node| {
+                let action = node.action.clone()?;
+                Some((action, node.average_score()))
+            });
+
+        Some(SearchSummary {
+            best_action: best.action.clone()?,
+            confidence: self.tree.best_action_confidence(),
+            average_score: best.average_score(),
+            runner_up,
+        })
+    }
+
+    /// Outcome of the last search, if it found an action
+    pub fn last_search(&self) -> Option<&SearchSummary> {
+        self.last_search.as_ref()
+    }
+
+    /// One-line "why this move" for the UI: chosen action, its visit share,
+    /// and how far ahead of the runner-up it scored
+    pub fn explain_last_search(&self) -> String {
+        let Some(summary) = &self.last_search else {
+            return "No search has chosen an action yet".to_string();
+        };
+
+        let mut explanation = format!(
+            "Chose {} ({:.0}% of visits, average score {:.1})",
+            summary.best_action,
+            summary.confidence * 100.0,
+            summary.average_score
+        );
+        match &summary.runner_up {
+            Some((action, score)) => explanation.push_str(&format!(
+                "; runner-up {} scored {:.1} ({:+.1} gap)",
+                action,
+                score,
+                summary.average_score - score
+            )),
+            None => explanation.push_str("; no other action was considered"),
+        }
+        explanation
+    }
+
     fn select(&self) -> usize {
-        self.tree.select_ucb1(self.config.exploration_constant)
+        if self.config.use_priors {
+            self.tree.select_puct(self.exploration_constant())
+        } else if self.config.use_rave {
+            self.tree
+                .select_ucb1_rave(self.exploration_constant(), self.config.rave_equivalence)
+        } else {
+            self.tree.select_ucb1(self.exploration_constant())
+        }
     }
 
     fn expand(&mut self, node_id: usize, _port: &Port) -> usize {
-        self.tree.expand(node_id, self.config.max_depth)
+        let child_id = self
+            .tree
+            .expand(node_id, self.config.max_depth, self.config.max_nodes);
+        if self.config.use_priors && child_id != node_id {
+            // Simulate the most promising action first
+            return self.tree.most_promising_child(node_id).unwrap_or(child_id);
+        }
+        child_id
     }
 
-    fn simulate(&self, node_id: usize) -> f64 {
+    /// Actions of the playout are pushed to `rollout` (used by RAVE)
+    fn simulate(&self, node_id: usize, rollout: &mut Vec<MCTSAction>) -> f64 {
         // Random playout simulation with depth limit
         let mut simulated_state = self.tree.get_state(node_id).clone();
         let mut depth = self.tree.node_depth(node_id);
@@ -90,6 +244,7 @@ impl MCTSEngine {
             let action_index = random::range_usize(0, actions.len());
             if let Some(action) = actions.get(action_index).cloned() {
                 MCTSTree::apply_action_to_state(&mut simulated_state, &action);
+                rollout.push(action);
             } else {
                 break;
             }
@@ -97,28 +252,65 @@ impl MCTSEngine {
             depth += 1;
         }
 
-        let mut score = simulated_state.calculate_score() as f64;
-        score += random::range_f64(-5.0, 5.0);
+        let root_state = self.tree.root_state().expect("Tree not initialized");
+        let mut score = match &self.scoring {
+            Some(strategy) => {
+                (strategy.score(&simulated_state) - strategy.score(root_state)) as f64
+            }
+            None => self.config.reward.evaluate(root_state, &simulated_state),
+        };
+        if self.config.rollout_noise > 0.0 {
+            score += random::range_f64(-self.config.rollout_noise, self.config.rollout_noise);
+        }
         score
     }
 
-    fn backpropagate(&mut self, node_id: usize, score: f64) {
-        self.tree.backpropagate(node_id, score);
+    fn backpropagate(&mut self, node_id: usize, score: f64, rollout: &[MCTSAction]) {
+        if self.config.adversarial {
+            self.tree.backpropagate_negamax(node_id, score);
+        } else {
+            self.tree.backpropagate(node_id, score);
+        }
+        if self.config.use_rave {
+            self.tree
+                .backpropagate_rave(node_id, score, rollout, self.config.adversarial);
+        }
     }
 
     pub fn get_tree(&self) -> &MCTSTree {
         &self.tree
     }
 
+    /// Last search tree as JSON (see `TreeDump`)
+    pub fn dump_tree_json(&self) -> Result<String, String> {
+        serde_json::to_string(&self.tree.to_dump()).map_err(|e| e.to_string())
+    }
+
+    /// Replace the current tree with one written by `dump_tree_json`
+    pub fn load_tree_json(&mut self, json: &str) -> Result<(), String> {
+        let dump: TreeDump = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        self.tree = MCTSTree::from_dump(dump)?;
+        Ok(())
+    }
+
     pub fn config(&self) -> &MCTSConfig {
         &self.config
     }
 
     pub fn get_statistics(&self) -> MCTSStatistics {
+        let seconds = self.last_elapsed.as_secs_f64();
         MCTSStatistics {
             simulations_performed: self.config.num_simulations,
             total_nodes: self.tree.node_count(),
             max_depth_reached: self.tree.max_depth(),
+            confidence: self.tree.best_action_confidence(),
+            truncated: self.tree.is_truncated(),
+            elapsed: self.last_elapsed,
+            nodes_per_second: if seconds > 0.0 {
+                self.tree.node_count() as f64 / seconds
+            } else {
+                0.0
+            },
         }
     }
 }
@@ -128,6 +320,10 @@ pub struct MCTSStatistics {
     pub simulations_performed: usize,
     pub total_nodes: usize,
     pub max_depth_reached: usize,
+    pub confidence: f64,       // Share of root visits spent on the chosen action
+    pub truncated: bool,       // The search hit `max_nodes`
+    pub elapsed: Duration,     // Wall time of the last search, to tune `num_simulations`
+    pub nodes_per_second: f64, // Tree nodes built per second of search (0.0 before any)
 }
 
 #[cfg(test)]
@@ -150,6 +346,14 @@ mod tests {
             exploration_constant: 1.41,
             max_depth: 10,
             max_actions_per_turn: 2,
+            reward: RewardConfig::default(),
+            adversarial: false,
+            max_nodes: 10_000,
+            use_rave: false,
+            rave_equivalence: 300.0,
+            exploration_schedule: ExplorationSchedule::Constant,
+            rollout_noise: 5.0,
+            use_priors: false,
         };
 
         let mut engine = MCTSEngine::new(config);
@@ -158,4 +362,331 @@ mod tests {
         // Should not crash even with empty port
         let _action = engine.search(&port);
     }
+
+    #[test]
+    fn test_statistics_report_search_time() {
+        use crate::domain::entities::Ship;
+        use crate::domain::value_objects::ShipId;
+
+        let mut port = Port::new(PlayerId::new(), 2, 2);
+        for id in 0..3 {
+            port.ships
+                .insert(ShipId::new(id), Ship::new(ShipId::new(id), 30, 0.0));
+        }
+        let mut engine = MCTSEngine::new(MCTSConfig {
+            num_simulations: 500,
+            max_depth: 10,
+            ..MCTSConfig::default()
+        });
+        assert_eq!(engine.get_statistics().elapsed, Duration::ZERO);
+
+        engine.search(&port);
+        let stats = engine.get_statistics();
+        assert!(stats.elapsed > Duration::ZERO);
+        let expected = stats.total_nodes as f64 / stats.elapsed.as_secs_f64();
+        assert!((stats.nodes_per_second - expected).abs() < 1e-6 * expected);
+    }
+
+    #[test]
+    fn test_decaying_exploration_schedule() {
+        let mut engine = MCTSEngine::new(MCTSConfig {
+            exploration_schedule: ExplorationSchedule::Decay {
+                rate: 0.9,
+                min: 0.2,
+            },
+            ..MCTSConfig::default()
+        });
+
+        engine.set_turn(1);
+        let early = engine.exploration_constant();
+        engine.set_turn(20);
+        let late = engine.exploration_constant();
+
+        assert_eq!(early, 1.41);
+        assert!(late < early);
+        assert!(late >= 0.2);
+
+        // The default schedule keeps the constant flat
+        let mut flat = MCTSEngine::new(MCTSConfig::default());
+        flat.set_turn(20);
+        assert_eq!(flat.exploration_constant(), 1.41);
+    }
+
+    #[test]
+    fn test_explain_last_search() {
+        use crate::domain::entities::Ship;
+        use crate::domain::value_objects::ShipId;
+
+        let mut engine = MCTSEngine::new(MCTSConfig {
+            num_simulations: 200,
+            max_depth: 5,
+            ..MCTSConfig::default()
+        });
+        assert!(engine.explain_last_search().contains("No search"));
+
+        // Two ships for one free berth: docking one of them is the clear choice
+        let mut port = Port::new(PlayerId::new(), 1, 1);
+        for id in 1..=2 {
+            port.ships
+                .insert(ShipId::new(id), Ship::new(ShipId::new(id), 30, 0.0));
+        }
+        let best = engine.search(&port).unwrap();
+        assert!(matches!(best, MCTSAction::DockShip { .. }));
+
+        let explanation = engine.explain_last_search();
+        assert!(explanation.contains(&best.to_string()), "{}", explanation);
+        assert!(explanation.contains("runner-up Dock"), "{}", explanation);
+        let confidence = engine.last_search().unwrap().confidence;
+        assert!(explanation.contains(&format!("{:.0}%", confidence * 100.0)));
+    }
+
+    #[test]
+    fn test_noiseless_rollouts_are_reproducible() {
+        use crate::domain::entities::Ship;
+        use crate::domain::value_objects::ShipId;
+
+        let mut port = Port::new(PlayerId::new(), 2, 2);
+        for id in 1..=3 {
+            port.ships
+                .insert(ShipId::new(id), Ship::new(ShipId::new(id), 20, 0.0));
+        }
+        let mut engine = MCTSEngine::new(MCTSConfig {
+            rollout_noise: 0.0,
+            use_priors: false,
+            ..MCTSConfig::default()
+        });
+        engine.tree.init_root(port);
+
+        let scores: Vec<f64> = (0..5)
+            .map(|_| random::with_seed(7, || engine.simulate(0, &mut Vec::new())))
+            .collect();
+        assert!(scores.iter().all(|&score| score == scores[0]));
+    }
+
+    #[test]
+    fn test_node_cap_bounds_tree_size() {
+        use crate::domain::entities::Ship;
+        use crate::domain::value_objects::ShipId;
+
+        let mut port = Port::new(PlayerId::new(), 2, 2);
+        for id in 1..=3 {
+            port.ships
+                .insert(ShipId::new(id), Ship::new(ShipId::new(id), 20, 0.0));
+        }
+
+        let mut engine = MCTSEngine::new(MCTSConfig {
+            num_simulations: 50,
+            max_nodes: 8,
+            ..MCTSConfig::default()
+        });
+        let action = engine.search(&port).expect("root still gets children");
+
+        assert!(engine.get_tree().node_count() <= 8);
+        assert!(engine.get_statistics().truncated);
+        assert!(engine.get_tree().generate_actions(&port).contains(&action));
+    }
+
+    #[test]
+    fn test_rave_finds_best_dock_in_fewer_simulations() {
+        use crate::domain::entities::Ship;
+        use crate::domain::value_objects::ShipId;
+
+        // 3 berths, 9 waiting ships: docking the oldest (ship 9) is best,
+        // and it is also the last action in tie-break order
+        let mut port = Port::new(PlayerId::new(), 3, 0);
+        port.current_time = 100.0;
+        for id in 1..=9 {
+            let arrival = (9 - id) as f64 * 5.0;
+            port.ships
+                .insert(ShipId::new(id), Ship::new(ShipId::new(id), 30, arrival));
+        }
+
+        let docks_oldest = |use_rave: bool, seed: u64| {
+            random::with_seed(seed, || {
+                let mut engine = MCTSEngine::new(MCTSConfig {
+                    num_simulations: 40,
+                    max_depth: 3,
+                    use_rave,
+                    ..MCTSConfig::default()
+                });
+                matches!(
+                    engine.search(&port),
+                    Some(MCTSAction::DockShip { ship_id, .. }) if ship_id == ShipId::new(9)
+                )
+            })
+        };
+
+        let plain = (0..20).filter(|&seed| docks_oldest(false, seed)).count();
+        let rave = (0..20).filter(|&seed| docks_oldest(true, seed)).count();
+
+        assert_eq!(rave, 20);
+        assert!(plain < rave, "plain UCB1 found it {} times", plain);
+    }
+
+    #[test]
+    fn test_tree_dump_round_trip() {
+        use crate::domain::entities::Ship;
+        use crate::domain::value_objects::ShipId;
+
+        let mut port = Port::new(PlayerId::new(), 2, 2);
+        for id in 1..=3 {
+            port.ships
+                .insert(ShipId::new(id), Ship::new(ShipId::new(id), 20, 0.0));
+        }
+
+        let mut engine = MCTSEngine::new(MCTSConfig {
+            num_simulations: 50,
+            max_depth: 5,
+            use_rave: true,
+            ..MCTSConfig::default()
+        });
+        let best = engine.search(&port);
+        let json = engine.dump_tree_json().unwrap();
+
+        let mut reloaded = MCTSEngine::new(MCTSConfig::default());
+        reloaded.load_tree_json(&json).unwrap();
+
+        assert!(best.is_some());
+        assert_eq!(reloaded.get_tree().best_action(), best);
+        assert_eq!(
+            reloaded.get_tree().node_count(),
+            engine.get_tree().node_count()
+        );
+        assert_eq!(
+            reloaded.get_statistics().confidence,
+            engine.get_statistics().confidence
+        );
+    }
+
+    #[test]
+    fn test_idle_crane_penalty_favours_crane_assignment() {
+        use crate::domain::entities::Ship;
+        use crate::domain::value_objects::{BerthId, ShipId};
+
+        // One docked ship without crane, one ship waiting for the free berth
+        let mut port = Port::new(PlayerId::new(), 2, 2);
+        let docked = ShipId::new(1);
+        let mut ship = Ship::new(docked, 30, 0.0);
+        ship.dock(BerthId::new(0));
+        port.ships.insert(docked, ship);
+        port.berths
+            .get_mut(&BerthId::new(0))
+            .unwrap()
+            .occupy(docked);
+        port.ships
+            .insert(ShipId::new(2), Ship::new(ShipId::new(2), 30, 0.0));
+
+        let search = |reward: RewardConfig| {
+            let mut engine = MCTSEngine::new(MCTSConfig {
+                num_simulations: 200,
+                exploration_constant: 1.41,
+                max_depth: 1,
+                max_actions_per_turn: 1,
+                reward,
+                adversarial: false,
+                max_nodes: 10_000,
+                use_rave: false,
+                rave_equivalence: 300.0,
+                exploration_schedule: ExplorationSchedule::Constant,
+                rollout_noise: 5.0,
+                use_priors: false,
+            });
+            engine.search(&port).unwrap()
+        };
+
+        let baseline = search(RewardConfig {
+            container_weight: 0.0,
+            waiting_weight: 50.0,
+            idle_berth_weight: 0.0,
+            idle_crane_weight: 0.0,
+        });
+        assert_eq!(baseline.action_type(), "DockShip");
+
+        let idle_averse = search(RewardConfig {
+            container_weight: 0.0,
+            waiting_weight: 50.0,
+            idle_berth_weight: 0.0,
+            idle_crane_weight: 1000.0,
+        });
+        assert_eq!(idle_averse.action_type(), "AssignCrane");
+    }
+
+    #[test]
+    fn test_prefers_cheaper_berth_of_equal_throughput() {
+        use crate::domain::entities::Ship;
+        use crate::domain::value_objects::{BerthId, ShipId};
+
+        // Two identical berths, only the first one charges a fee
+        let mut port = Port::new(PlayerId::new(), 2, 1);
+        port.berths.get_mut(&BerthId::new(0)).unwrap().docking_cost = 50;
+        port.ships
+            .insert(ShipId::new(1), Ship::new(ShipId::new(1), 30, 0.0));
+
+        for seed in 0..5 {
+            let action = random::with_seed(seed, || {
+                MCTSEngine::new(MCTSConfig {
+                    num_simulations: 100,
+                    max_depth: 3,
+                    ..MCTSConfig::default()
+                })
+                .search(&port)
+            });
+            assert_eq!(
+                action,
+                Some(MCTSAction::DockShip {
+                    ship_id: ShipId::new(1),
+                    berth_id: BerthId::new(1),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_priors_visit_fullest_ship_first() {
+        use crate::domain::entities::Ship;
+        use crate::domain::value_objects::{BerthId, CraneId, ShipId};
+
+        // One free crane, a nearly unloaded ship and a full one
+        let mut port = Port::new(PlayerId::new(), 2, 1);
+        for (index, containers) in [(0, 5), (1, 200)] {
+            let ship_id = ShipId::new(index);
+            let mut ship = Ship::new(ship_id, containers, 0.0);
+            ship.dock(BerthId::new(index));
+            port.ships.insert(ship_id, ship);
+            port.berths
+                .get_mut(&BerthId::new(index))
+                .unwrap()
+                .occupy(ship_id);
+        }
+
+        let visits = |use_priors: bool| {
+            let mut engine = MCTSEngine::new(MCTSConfig {
+                num_simulations: 6,
+                max_depth: 1,
+                rollout_noise: 0.0,
+                use_priors,
+                ..MCTSConfig::default()
+            });
+            engine.search(&port);
+            let tree = engine.get_tree();
+            let visits_of = |ship: usize| {
+                tree.ranked_root_children()
+                    .into_iter()
+                    .map(|id| tree.get_node(id))
+                    .find(|node| {
+                        node.action
+                            == Some(MCTSAction::AssignCrane {
+                                crane_id: CraneId::new(0),
+                                ship_id: ShipId::new(ship),
+                            })
+                    })
+                    .map_or(0, |node| node.visits)
+            };
+            (visits_of(1), visits_of(0))
+        };
+
+        let (good, bad) = visits(true);
+        assert!(good > bad, "full ship {} visits, empty ship {}", good, bad);
+        assert!(good > visits(false).0);
+    }
 }
@@ -1,5 +1,7 @@
 // MCTS Simulation helpers
 
+use crate::domain::aggregates::Port;
+
 // Placeholder for future simulation logic
 // Currently simulation is handled in the main MCTS engine
 
@@ -16,3 +18,52 @@ impl Default for Simulator {
         Self::new()
     }
 }
+
+/// Weights used to score the final state of a rollout
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewardConfig {
+    pub container_weight: f64, // reward per container unloaded during the rollout
+    pub waiting_weight: f64,   // penalty per time unit per waiting ship
+    pub idle_berth_weight: f64, // penalty per free berth
+    pub idle_crane_weight: f64, // penalty per idle crane (see `Port::idle_crane_count`)
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        Self {
+            container_weight: 10.0, // Same as the live score
+            waiting_weight: 5.0,
+            idle_berth_weight: 0.0,
+            idle_crane_weight: 0.0,
+        }
+    }
+}
+
+impl RewardConfig {
+    /// Reward of a rollout that started at `root` and ended at `state`
+    pub fn evaluate(&self, root: &Port, state: &Port) -> f64 {
+        let remaining = |port: &Port| -> u32 {
+            port.ships
+                .values()
+                .map(|ship| ship.containers_remaining)
+                .sum()
+        };
+        // Completed ships leave the port, so compare totals against the root
+        let containers_processed = remaining(root).saturating_sub(remaining(state)) as f64;
+
+        let waiting_time: f64 = state
+            .waiting_ships()
+            .into_iter()
+            .map(|ship| ship.waiting_time(state.current_time))
+            .sum();
+
+        // Rollouts only move the score directly through docking fees
+        let fees_paid = (root.score - state.score) as f64;
+
+        self.container_weight * containers_processed
+            - fees_paid
+            - self.waiting_weight * waiting_time
+            - self.idle_berth_weight * state.free_berths().len() as f64
+            - self.idle_crane_weight * state.idle_crane_count() as f64
+    }
+}
@@ -1,7 +1,13 @@
 // MCTS Tree structure
 
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
 use super::actions::MCTSAction;
-use crate::domain::aggregates::Port;
+use crate::domain::aggregates::{Port, DEFAULT_IDLE_CRANE_PENALTY, DEFAULT_POINTS_PER_CONTAINER};
+use crate::domain::entities::{Berth, Crane, Ship};
+use crate::domain::value_objects::{PlayerId, Tide};
 
 /// MCTS tree node
 #[derive(Debug, Clone)]
@@ -13,6 +19,25 @@ pub struct MCTSNode {
     pub visits: usize,
     pub total_score: f64,
     pub depth: usize,
+    pub rave_stats: BTreeMap<MCTSAction, RaveStat>, // AMAF stats of the actions played below
+    pub prior: f64, // Heuristic share among siblings (see `action_prior`), 1.0 at the root
+}
+
+/// All-moves-as-first statistics of one action
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RaveStat {
+    pub visits: usize,
+    pub total_score: f64,
+}
+
+impl RaveStat {
+    pub fn average_score(&self) -> f64 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            self.total_score / self.visits as f64
+        }
+    }
 }
 
 impl MCTSNode {
@@ -30,6 +55,8 @@ impl MCTSNode {
             visits: 0,
             total_score: 0.0,
             depth,
+            rave_stats: BTreeMap::new(),
+            prior: 1.0,
         }
     }
 
@@ -52,6 +79,67 @@ impl MCTSNode {
             exploitation + exploration
         }
     }
+
+    /// UCB1 where the exploitation term blends in the parent's AMAF value:
+    /// beta = sqrt(k / (3n + k)), so RAVE dominates while `visits` is low.
+    /// Without AMAF data this is plain `ucb1`.
+    pub fn ucb1_rave(
+        &self,
+        parent_visits: usize,
+        exploration_constant: f64,
+        rave: Option<&RaveStat>,
+        rave_equivalence: f64,
+    ) -> f64 {
+        let Some(rave) = rave.filter(|rave| rave.visits > 0) else {
+            return self.ucb1(parent_visits, exploration_constant);
+        };
+
+        // An unvisited child is valued by RAVE alone (beta = 1)
+        let beta = (rave_equivalence / (3.0 * self.visits as f64 + rave_equivalence)).sqrt();
+        let exploitation = (1.0 - beta) * self.average_score() + beta * rave.average_score();
+        let exploration =
+            exploration_constant * ((parent_visits as f64).ln() / self.visits.max(1) as f64).sqrt();
+        exploitation + exploration
+    }
+
+    /// PUCT (AlphaZero-style): exploration is scaled by the prior, so likely
+    /// good actions are tried first. An unvisited child is valued at its
+    /// parent's average score instead of infinity.
+    pub fn puct(&self, parent: &MCTSNode, exploration_constant: f64) -> f64 {
+        let exploitation = if self.visits == 0 {
+            parent.average_score()
+        } else {
+            self.average_score()
+        };
+        let exploration = exploration_constant * self.prior * (parent.visits as f64).sqrt()
+            / (1.0 + self.visits as f64);
+        exploitation + exploration
+    }
+}
+
+/// Weight of `action` in `port` before any simulation: the more containers
+/// an action puts to work, the higher. Docking a priority ship counts double
+/// and passes or unassignments get a minimal weight.
+pub fn action_prior(port: &Port, action: &MCTSAction) -> f64 {
+    let remaining = |ship_id| {
+        port.ships
+            .get(ship_id)
+            .map_or(0.0, |ship| ship.containers_remaining as f64)
+    };
+    match action {
+        MCTSAction::DockShip { ship_id, .. } => {
+            let priority = port.ships.get(ship_id).is_some_and(|ship| ship.priority);
+            1.0 + remaining(ship_id) * if priority { 2.0 } else { 1.0 }
+        }
+        MCTSAction::AssignCrane { crane_id, ship_id } => {
+            let speed = port
+                .cranes
+                .get(crane_id)
+                .map_or(1.0, |crane| crane.processing_speed);
+            1.0 + remaining(ship_id) * speed
+        }
+        MCTSAction::UnassignCrane { .. } | MCTSAction::Pass => 1.0,
+    }
 }
 
 /// MCTS tree
@@ -59,6 +147,7 @@ impl MCTSNode {
 pub struct MCTSTree {
     nodes: Vec<MCTSNode>,
     root_id: Option<usize>,
+    truncated: bool, // An expansion was cut short by the node cap
 }
 
 impl MCTSTree {
@@ -66,6 +155,7 @@ impl MCTSTree {
         Self {
             nodes: Vec::new(),
             root_id: None,
+            truncated: false,
         }
     }
 
@@ -74,33 +164,53 @@ impl MCTSTree {
         self.nodes.clear();
         self.nodes.push(root);
         self.root_id = Some(0);
+        self.truncated = false;
     }
 
     pub fn select_ucb1(&self, exploration_constant: f64) -> usize {
+        self.select_by(|_, child, parent_visits| child.ucb1(parent_visits, exploration_constant))
+    }
+
+    /// Same descent as `select_ucb1`, scoring children with `ucb1_rave`
+    pub fn select_ucb1_rave(&self, exploration_constant: f64, rave_equivalence: f64) -> usize {
+        self.select_by(|parent, child, parent_visits| {
+            let rave = child
+                .action
+                .as_ref()
+                .and_then(|action| parent.rave_stats.get(action));
+            child.ucb1_rave(parent_visits, exploration_constant, rave, rave_equivalence)
+        })
+    }
+
+    /// Same descent as `select_ucb1`, scoring children with `puct`
+    pub fn select_puct(&self, exploration_constant: f64) -> usize {
+        self.select_by(|parent, child, _| child.puct(parent, exploration_constant))
+    }
+
+    fn select_by(&self, value: impl Fn(&MCTSNode, &MCTSNode, usize) -> f64) -> usize {
         let mut current_id = self.root_id.expect("Tree not initialized");
 
         loop {
             let node = &self.nodes[current_id];
 
-            if node.children.is_empty() {
-                return current_id;
-            }
-
-            // Select child with highest UCB1
+            // Select child with highest value (NaN ranks lowest, never panics)
             let parent_visits = node.visits;
-            current_id = *node
-                .children
-                .iter()
-                .max_by(|&&a, &&b| {
-                    let ucb_a = self.nodes[a].ucb1(parent_visits, exploration_constant);
-                    let ucb_b = self.nodes[b].ucb1(parent_visits, exploration_constant);
-                    ucb_a.partial_cmp(&ucb_b).unwrap()
-                })
-                .expect("Children exist but none selected");
+            let best_child = node.children.iter().copied().max_by(|&a, &b| {
+                let value_a = value(node, &self.nodes[a], parent_visits);
+                let value_b = value(node, &self.nodes[b], parent_visits);
+                nan_as_lowest(value_a).total_cmp(&nan_as_lowest(value_b))
+            });
+
+            match best_child {
+                Some(child_id) => current_id = child_id,
+                None => return current_id,
+            }
         }
     }
 
-    pub fn expand(&mut self, node_id: usize, max_depth: usize) -> usize {
+    /// Add children for the node's actions, never growing past `max_nodes`.
+    /// Once the cap is hit the node stays a leaf and is simulated as is.
+    pub fn expand(&mut self, node_id: usize, max_depth: usize, max_nodes: usize) -> usize {
         // Clone necessary data before modifying self.nodes
         let (state, depth) = {
             let node = &self.nodes[node_id];
@@ -112,18 +222,28 @@ impl MCTSTree {
         }
 
         // Generate possible actions (simplified for MVP)
-        let actions = self.generate_actions(&state);
+        let mut actions = self.generate_actions_with_priors(&state);
 
         if actions.is_empty() {
             return node_id; // No expansion possible
         }
 
+        let room = max_nodes.saturating_sub(self.nodes.len());
+        if actions.len() > room {
+            self.truncated = true;
+            actions.truncate(room);
+            if actions.is_empty() {
+                return node_id;
+            }
+        }
+
         // Create child nodes for each action
         let mut child_ids = Vec::new();
-        for action in actions {
+        for (action, prior) in actions {
             let mut new_state = state.clone();
             Self::apply_action_to_state(&mut new_state, &action);
-            let child = MCTSNode::new(new_state, Some(action), Some(node_id), depth + 1);
+            let mut child = MCTSNode::new(new_state, Some(action), Some(node_id), depth + 1);
+            child.prior = prior;
             let child_id = self.nodes.len();
             self.nodes.push(child);
             child_ids.push(child_id);
@@ -148,31 +268,117 @@ impl MCTSTree {
         }
     }
 
+    /// Negamax-style backpropagation: `score` is from the root player's point
+    /// of view, nodes at odd depth (root player's moves) accumulate it as-is
+    /// and nodes at even depth (opponent plies) accumulate its negation
+    pub fn backpropagate_negamax(&mut self, mut node_id: usize, score: f64) {
+        loop {
+            let node = &mut self.nodes[node_id];
+            node.visits += 1;
+            node.total_score += if node.depth % 2 == 1 { score } else { -score };
+
+            match node.parent {
+                Some(parent_id) => node_id = parent_id,
+                None => break,
+            }
+        }
+    }
+
+    /// Credit `score` to every action played after each node on the path:
+    /// the rollout's actions plus the tree actions below that node.
+    /// Each action counts once per simulation; with `adversarial` the sign
+    /// follows the ply of the child the statistic stands for.
+    pub fn backpropagate_rave(
+        &mut self,
+        mut node_id: usize,
+        score: f64,
+        rollout: &[MCTSAction],
+        adversarial: bool,
+    ) {
+        let mut played: BTreeSet<MCTSAction> = rollout.iter().cloned().collect();
+
+        loop {
+            let node = &mut self.nodes[node_id];
+            let signed = if adversarial && node.depth % 2 == 1 {
+                -score
+            } else {
+                score
+            };
+            for action in &played {
+                let stat = node.rave_stats.entry(action.clone()).or_default();
+                stat.visits += 1;
+                stat.total_score += signed;
+            }
+            if let Some(action) = &node.action {
+                played.insert(action.clone());
+            }
+
+            match node.parent {
+                Some(parent_id) => node_id = parent_id,
+                None => break,
+            }
+        }
+    }
+
     pub fn best_action(&self) -> Option<MCTSAction> {
-        let root_id = self.root_id?;
-        let root = &self.nodes[root_id];
+        let best_child_id = self.best_child()?;
+        self.nodes[best_child_id].action.clone()
+    }
 
-        if root.children.is_empty() {
-            return None;
+    /// Share of root visits that went to the best action (0.0 before any search)
+    pub fn best_action_confidence(&self) -> f64 {
+        let (Some(root_id), Some(best_child_id)) = (self.root_id, self.best_child()) else {
+            return 0.0;
+        };
+        let root_visits = self.nodes[root_id].visits;
+        if root_visits == 0 {
+            return 0.0;
         }
+        self.nodes[best_child_id].visits as f64 / root_visits as f64
+    }
 
-        // Select child with highest visit count (most explored)
-        let best_child_id = root
-            .children
-            .iter()
-            .max_by_key(|&&id| self.nodes[id].visits)?;
+    fn best_child(&self) -> Option<usize> {
+        self.ranked_root_children().first().copied()
+    }
 
-        self.nodes[*best_child_id].action.clone()
+    /// Root children from best to worst: highest visit count (most explored)
+    /// first, ties go to the smallest action so the choice is deterministic
+    pub fn ranked_root_children(&self) -> Vec<usize> {
+        let Some(root_id) = self.root_id else {
+            return Vec::new();
+        };
+        let mut children = self.nodes[root_id].children.clone();
+        children.sort_by(|&a, &b| {
+            let (node_a, node_b) = (&self.nodes[a], &self.nodes[b]);
+            node_b
+                .visits
+                .cmp(&node_a.visits)
+                .then_with(|| node_a.action.cmp(&node_b.action))
+        });
+        children
+    }
+
+    pub fn get_node(&self, node_id: usize) -> &MCTSNode {
+        &self.nodes[node_id]
     }
 
     pub fn get_state(&self, node_id: usize) -> &Port {
         &self.nodes[node_id].state
     }
 
+    pub fn root_state(&self) -> Option<&Port> {
+        self.root_id.map(|root_id| &self.nodes[root_id].state)
+    }
+
     pub fn node_count(&self) -> usize {
         self.nodes.len()
     }
 
+    /// Whether the node cap stopped an expansion since the last `init_root`
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
     pub fn max_depth(&self) -> usize {
         self.nodes.iter().map(|n| n.depth).max().unwrap_or(0)
     }
@@ -189,6 +395,9 @@ impl MCTSTree {
 
         // Generate DockShip actions
         for ship in port.waiting_ships() {
+            if !port.tide_allows(ship) {
+                continue;
+            }
             for berth in port.free_berths() {
                 actions.push(MCTSAction::DockShip {
                     ship_id: ship.id,
@@ -200,6 +409,14 @@ impl MCTSTree {
         // Generate AssignCrane actions
         for crane in port.free_cranes() {
             for ship in port.docked_ships() {
+                if ship.assigned_cranes.len() >= port.max_cranes_per_ship {
+                    continue;
+                }
+                if ship.docked_at.is_some_and(|berth| !crane.can_serve(berth))
+                    || !crane.can_unload(ship)
+                {
+                    continue;
+                }
                 actions.push(MCTSAction::AssignCrane {
                     crane_id: crane.id,
                     ship_id: ship.id,
@@ -215,6 +432,39 @@ impl MCTSTree {
         actions
     }
 
+    /// `generate_actions` with each action's `action_prior`, normalized so
+    /// the priors of a node's children sum to 1
+    pub(crate) fn generate_actions_with_priors(&self, port: &Port) -> Vec<(MCTSAction, f64)> {
+        let weighted: Vec<_> = self
+            .generate_actions(port)
+            .into_iter()
+            .map(|action| {
+                let weight = action_prior(port, &action);
+                (action, weight)
+            })
+            .collect();
+        let total: f64 = weighted.iter().map(|(_, weight)| weight).sum();
+        weighted
+            .into_iter()
+            .map(|(action, weight)| (action, weight / total))
+            .collect()
+    }
+
+    /// Child of `node_id` with the highest prior (the first one on ties)
+    pub fn most_promising_child(&self, node_id: usize) -> Option<usize> {
+        self.nodes[node_id]
+            .children
+            .iter()
+            .copied()
+            .reduce(|best, id| {
+                if self.nodes[id].prior > self.nodes[best].prior {
+                    id
+                } else {
+                    best
+                }
+            })
+    }
+
     pub(crate) fn apply_action_to_state(state: &mut Port, action: &MCTSAction) {
         match action {
             MCTSAction::DockShip { ship_id, berth_id } => {
@@ -223,6 +473,7 @@ impl MCTSTree {
                 }
                 if let Some(berth) = state.berths.get_mut(berth_id) {
                     berth.occupy(*ship_id);
+                    state.score -= berth.docking_cost;
                 }
                 state.current_time += 1.0;
             }
@@ -256,11 +507,15 @@ impl MCTSTree {
         let mut ships_to_remove = Vec::new();
 
         for ship_id in ship_ids {
+            let capacity = match state.ships.get(&ship_id) {
+                Some(ship) => state.crane_capacity(ship),
+                None => continue,
+            };
             if let Some(ship) = state.ships.get_mut(&ship_id) {
                 if ship.is_docked() && !ship.assigned_cranes.is_empty() {
-                    let crane_count = ship.assigned_cranes.len() as u32;
-                    let processed = 10 * crane_count;
-                    ship.process_containers(processed);
+                    for (container_type, count) in ship.plan_processing(capacity) {
+                        ship.process_container_type(container_type, count);
+                    }
 
                     if ship.is_completed() {
                         // Free cranes assigned to the ship
@@ -296,10 +551,196 @@ impl Default for MCTSTree {
     }
 }
 
+/// Serializable projection of a tree, for offline analysis of AI decisions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeDump {
+    pub root_id: Option<usize>,
+    pub truncated: bool,
+    pub nodes: Vec<NodeDump>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeDump {
+    pub state: StateDump,
+    pub action: Option<MCTSAction>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    pub visits: usize,
+    pub total_score: f64,
+    pub depth: usize,
+    pub rave_stats: Vec<(MCTSAction, RaveStat)>, // JSON maps need string keys
+    #[serde(default = "default_prior")]
+    pub prior: f64,
+}
+
+/// Simulated port state of a node (Port keeps its event log private,
+/// tree states never have one)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateDump {
+    pub player_id: PlayerId,
+    pub ships: Vec<Ship>,
+    pub berths: Vec<Berth>,
+    pub cranes: Vec<Crane>,
+    pub current_time: f64,
+    pub score: i32,
+    pub max_cranes_per_ship: usize,
+    pub containers_per_crane: u32,
+    #[serde(default = "default_points_per_container")]
+    pub points_per_container: u32,
+    pub tide: Tide,
+    #[serde(default)]
+    pub waiting_cost: i32,
+    #[serde(default)]
+    pub waiting_charged_until: f64,
+    #[serde(default = "default_idle_crane_penalty")]
+    pub idle_crane_penalty: u32,
+    #[serde(default)]
+    pub idle_crane_cost: i32,
+}
+
+fn default_prior() -> f64 {
+    1.0
+}
+
+fn default_idle_crane_penalty() -> u32 {
+    DEFAULT_IDLE_CRANE_PENALTY
+}
+
+fn default_points_per_container() -> u32 {
+    DEFAULT_POINTS_PER_CONTAINER
+}
+
+impl From<&Port> for StateDump {
+    fn from(port: &Port) -> Self {
+        Self {
+            player_id: port.player_id,
+            ships: port.ships.values().cloned().collect(),
+            berths: port.berths.values().cloned().collect(),
+            cranes: port.cranes.values().cloned().collect(),
+            current_time: port.current_time,
+            score: port.score,
+            max_cranes_per_ship: port.max_cranes_per_ship,
+            containers_per_crane: port.containers_per_crane,
+            points_per_container: port.points_per_container,
+            tide: port.tide,
+            waiting_cost: port.waiting_cost,
+            waiting_charged_until: port.waiting_charged_until,
+            idle_crane_penalty: port.idle_crane_penalty,
+            idle_crane_cost: port.idle_crane_cost,
+        }
+    }
+}
+
+impl From<StateDump> for Port {
+    fn from(dump: StateDump) -> Self {
+        let mut port = Port::new(dump.player_id, 0, 0);
+        port.ships = dump.ships.into_iter().map(|ship| (ship.id, ship)).collect();
+        port.berths = dump
+            .berths
+            .into_iter()
+            .map(|berth| (berth.id, berth))
+            .collect();
+        port.cranes = dump
+            .cranes
+            .into_iter()
+            .map(|crane| (crane.id, crane))
+            .collect();
+        port.current_time = dump.current_time;
+        port.score = dump.score;
+        port.max_cranes_per_ship = dump.max_cranes_per_ship;
+        port.containers_per_crane = dump.containers_per_crane;
+        port.points_per_container = dump.points_per_container;
+        port.tide = dump.tide;
+        port.waiting_cost = dump.waiting_cost;
+        port.waiting_charged_until = dump.waiting_charged_until;
+        port.idle_crane_penalty = dump.idle_crane_penalty;
+        port.idle_crane_cost = dump.idle_crane_cost;
+        port
+    }
+}
+
+impl MCTSTree {
+    pub fn to_dump(&self) -> TreeDump {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| NodeDump {
+                state: StateDump::from(&node.state),
+                action: node.action.clone(),
+                parent: node.parent,
+                children: node.children.clone(),
+                visits: node.visits,
+                total_score: node.total_score,
+                depth: node.depth,
+                rave_stats: node
+                    .rave_stats
+                    .iter()
+                    .map(|(action, stat)| (action.clone(), *stat))
+                    .collect(),
+                prior: node.prior,
+            })
+            .collect();
+
+        TreeDump {
+            root_id: self.root_id,
+            truncated: self.truncated,
+            nodes,
+        }
+    }
+
+    /// Rebuild a tree, rejecting node links that point outside the dump
+    pub fn from_dump(dump: TreeDump) -> Result<Self, String> {
+        let len = dump.nodes.len();
+        let in_range = |id: usize| id < len;
+
+        if dump.root_id.is_some_and(|root_id| !in_range(root_id)) {
+            return Err(format!("Root node {:?} is out of range", dump.root_id));
+        }
+        for (id, node) in dump.nodes.iter().enumerate() {
+            if node.parent.is_some_and(|parent| !in_range(parent))
+                || node.children.iter().any(|&child| !in_range(child))
+            {
+                return Err(format!("Node {} links to a missing node", id));
+            }
+        }
+
+        let nodes = dump
+            .nodes
+            .into_iter()
+            .map(|node| MCTSNode {
+                state: Port::from(node.state),
+                action: node.action,
+                parent: node.parent,
+                children: node.children,
+                visits: node.visits,
+                total_score: node.total_score,
+                depth: node.depth,
+                rave_stats: node.rave_stats.into_iter().collect(),
+                prior: node.prior,
+            })
+            .collect();
+
+        Ok(Self {
+            nodes,
+            root_id: dump.root_id,
+            truncated: dump.truncated,
+        })
+    }
+}
+
+fn nan_as_lowest(value: f64) -> f64 {
+    if value.is_nan() {
+        f64::NEG_INFINITY
+    } else {
+        value
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::value_objects::PlayerId;
+    use crate::domain::entities::Ship;
+    use crate::domain::value_objects::{BerthId, CraneId, PlayerId, ShipId, Tide};
 
     #[test]
     fn test_tree_initialization() {
@@ -322,6 +763,116 @@ mod tests {
         assert_eq!(ucb, f64::INFINITY);
     }
 
+    #[test]
+    fn test_generate_actions_respects_crane_cap() {
+        let tree = MCTSTree::new();
+        let mut port = Port::new(PlayerId::new(), 2, 3);
+        port.max_cranes_per_ship = 1;
+
+        let ship_id = ShipId::new(1);
+        let mut ship = Ship::new(ship_id, 50, 0.0);
+        ship.dock(BerthId::new(0));
+        ship.assign_crane(CraneId::new(0));
+        port.ships.insert(ship_id, ship);
+        port.berths
+            .get_mut(&BerthId::new(0))
+            .unwrap()
+            .occupy(ship_id);
+        port.cranes
+            .get_mut(&CraneId::new(0))
+            .unwrap()
+            .assign(ship_id);
+
+        let actions = tree.generate_actions(&port);
+
+        assert!(actions
+            .iter()
+            .all(|action| !matches!(action, MCTSAction::AssignCrane { .. })));
+    }
+
+    #[test]
+    fn test_generate_actions_respects_crane_berth_compatibility() {
+        let tree = MCTSTree::new();
+        let mut port = Port::new(PlayerId::new(), 2, 2);
+        for berth in 0..2 {
+            let ship_id = ShipId::new(berth);
+            let mut ship = Ship::new(ship_id, 50, 0.0);
+            ship.dock(BerthId::new(berth));
+            port.ships.insert(ship_id, ship);
+            port.berths
+                .get_mut(&BerthId::new(berth))
+                .unwrap()
+                .occupy(ship_id);
+        }
+        // Crane 0 is rail-mounted next to berth 0
+        port.cranes
+            .get_mut(&CraneId::new(0))
+            .unwrap()
+            .compatible_berths = Some([BerthId::new(0)].into_iter().collect());
+
+        let assignments: Vec<_> = tree
+            .generate_actions(&port)
+            .into_iter()
+            .filter_map(|action| match action {
+                MCTSAction::AssignCrane { crane_id, ship_id } => Some((crane_id.0, ship_id.0)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(assignments, vec![(0, 0), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn test_generate_actions_skips_cranes_that_cannot_handle_the_cargo() {
+        use crate::domain::value_objects::ContainerType;
+
+        let tree = MCTSTree::new();
+        let mut port = Port::new(PlayerId::new(), 1, 2);
+        let mut ship = Ship::new(ShipId::new(1), 30, 0.0);
+        ship.dock(BerthId::new(0));
+        port.ships.insert(ship.id, ship);
+        port.berths
+            .get_mut(&BerthId::new(0))
+            .unwrap()
+            .occupy(ShipId::new(1));
+        // Crane 0 only handles refrigerated containers
+        port.cranes.get_mut(&CraneId::new(0)).unwrap().handled_types =
+            Some([ContainerType::Reefer].into_iter().collect());
+
+        let cranes: Vec<_> = tree
+            .generate_actions(&port)
+            .into_iter()
+            .filter_map(|action| match action {
+                MCTSAction::AssignCrane { crane_id, .. } => Some(crane_id.0),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(cranes, vec![1]);
+    }
+
+    #[test]
+    fn test_generate_actions_skips_tide_blocked_docks() {
+        let tree = MCTSTree::new();
+        let mut port = Port::new(PlayerId::new(), 1, 1);
+        port.tide = Tide::Low;
+        port.ships
+            .insert(ShipId::new(1), Ship::new(ShipId::new(1), 50, 0.0));
+        port.ships
+            .insert(ShipId::new(2), Ship::new(ShipId::new(2), 20, 0.0));
+
+        let docked: Vec<_> = tree
+            .generate_actions(&port)
+            .into_iter()
+            .filter_map(|action| match action {
+                MCTSAction::DockShip { ship_id, .. } => Some(ship_id),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(docked, vec![ShipId::new(2)]);
+    }
+
     #[test]
     fn test_backpropagation() {
         let mut tree = MCTSTree::new();
@@ -334,4 +885,110 @@ mod tests {
         assert_eq!(root.visits, 1);
         assert_eq!(root.total_score, 100.0);
     }
+
+    #[test]
+    fn test_best_action_breaks_visit_ties_deterministically() {
+        let dock = MCTSAction::DockShip {
+            ship_id: ShipId::new(0),
+            berth_id: BerthId::new(1),
+        };
+
+        for actions in [
+            [MCTSAction::Pass, dock.clone()],
+            [dock.clone(), MCTSAction::Pass],
+        ] {
+            let mut tree = MCTSTree::new();
+            let port = Port::new(PlayerId::new(), 2, 2);
+            tree.init_root(port.clone());
+
+            for action in actions {
+                let child_id = tree.nodes.len();
+                let mut child = MCTSNode::new(port.clone(), Some(action), Some(0), 1);
+                child.visits = 5;
+                tree.nodes.push(child);
+                tree.nodes[0].children.push(child_id);
+            }
+
+            assert_eq!(tree.best_action(), Some(dock.clone()));
+        }
+    }
+
+    #[test]
+    fn test_select_ucb1_ignores_nan_scores() {
+        let mut tree = MCTSTree::new();
+        let port = Port::new(PlayerId::new(), 2, 2);
+        tree.init_root(port.clone());
+
+        for child_id in 1..=2 {
+            tree.nodes.push(MCTSNode::new(
+                port.clone(),
+                Some(MCTSAction::Pass),
+                Some(0),
+                1,
+            ));
+            tree.nodes[0].children.push(child_id);
+            tree.nodes[child_id].visits = 1;
+        }
+        tree.nodes[0].visits = 2;
+        tree.nodes[1].total_score = f64::NAN;
+        tree.nodes[2].total_score = 1.0;
+
+        assert_eq!(tree.select_ucb1(1.41), 2);
+    }
+
+    #[test]
+    fn test_negamax_backpropagation_alternates_sign() {
+        let mut tree = MCTSTree::new();
+        let port = Port::new(PlayerId::new(), 2, 2);
+        tree.init_root(port.clone());
+
+        // root (depth 0) -> child (depth 1) -> grandchild (depth 2)
+        tree.nodes.push(MCTSNode::new(
+            port.clone(),
+            Some(MCTSAction::Pass),
+            Some(0),
+            1,
+        ));
+        tree.nodes[0].children.push(1);
+        tree.nodes
+            .push(MCTSNode::new(port, Some(MCTSAction::Pass), Some(1), 2));
+        tree.nodes[1].children.push(2);
+
+        tree.backpropagate_negamax(2, 10.0);
+        tree.backpropagate_negamax(2, 4.0);
+
+        assert_eq!(tree.nodes[2].total_score, -14.0);
+        assert_eq!(tree.nodes[1].total_score, 14.0);
+        assert_eq!(tree.nodes[0].total_score, -14.0);
+        assert!(tree.nodes.iter().all(|node| node.visits == 2));
+    }
+
+    #[test]
+    fn test_rave_credits_actions_played_below_each_node() {
+        let mut tree = MCTSTree::new();
+        let port = Port::new(PlayerId::new(), 2, 2);
+        tree.init_root(port.clone());
+
+        let dock = MCTSAction::DockShip {
+            ship_id: ShipId::new(1),
+            berth_id: BerthId::new(0),
+        };
+        let assign = MCTSAction::AssignCrane {
+            crane_id: CraneId::new(0),
+            ship_id: ShipId::new(1),
+        };
+        tree.nodes
+            .push(MCTSNode::new(port, Some(dock.clone()), Some(0), 1));
+        tree.nodes[0].children.push(1);
+
+        // The rollout repeats `assign`: still one AMAF update per simulation
+        tree.backpropagate_rave(1, 8.0, &[assign.clone(), assign.clone()], false);
+
+        let root_stats = &tree.nodes[0].rave_stats;
+        assert_eq!(root_stats[&dock].visits, 1);
+        assert_eq!(root_stats[&assign].visits, 1);
+        assert_eq!(root_stats[&assign].total_score, 8.0);
+        assert!(!tree.nodes[1].rave_stats.contains_key(&dock));
+        assert_eq!(tree.nodes[1].rave_stats[&assign].visits, 1);
+    }
 }
@@ -1,15 +1,56 @@
 // Shared utility helpers across the project.
 
 pub mod random {
+    use std::cell::RefCell;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
     #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
     use js_sys::Math;
 
-    #[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
-    use rand::Rng;
+    thread_local! {
+        /// Deterministic generator installed by `with_seed` (None = entropy)
+        static SEEDED: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+    }
+
+    /// Restores the previous generator, even if the seeded closure panics
+    struct SeedGuard(Option<StdRng>);
+
+    impl Drop for SeedGuard {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SEEDED.with(|rng| *rng.borrow_mut() = previous);
+        }
+    }
+
+    /// Run `f` with every sampler of this module drawing from a generator
+    /// seeded with `seed` (current thread only).
+    pub fn with_seed<T>(seed: u64, f: impl FnOnce() -> T) -> T {
+        with_rng(&mut StdRng::seed_from_u64(seed), f)
+    }
+
+    /// Like `with_seed`, drawing from `rng` and leaving it advanced past
+    /// every value `f` sampled.
+    pub fn with_rng<T>(rng: &mut StdRng, f: impl FnOnce() -> T) -> T {
+        let previous = SEEDED.with(|seeded| seeded.replace(Some(rng.clone())));
+        let _guard = SeedGuard(previous);
+        let result = f();
+        if let Some(advanced) = SEEDED.with(|seeded| seeded.borrow_mut().take()) {
+            *rng = advanced;
+        }
+        result
+    }
 
     /// Generate a floating-point number in [0.0, 1.0).
     #[inline]
     fn unit_f64() -> f64 {
+        if let Some(value) =
+            SEEDED.with(|rng| rng.borrow_mut().as_mut().map(|rng| rng.gen::<f64>()))
+        {
+            return value;
+        }
+
         #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
         {
             Math::random()
@@ -72,3 +113,115 @@ pub mod random {
         start + (unit_f64() * span).floor() as u32
     }
 }
+
+pub mod clock {
+    /// Milliseconds from an arbitrary origin, only meaningful as a difference
+    /// between two calls. Native builds use the monotonic `std::time::Instant`;
+    /// it panics on wasm32-unknown-unknown, where the browser clock stands in.
+    pub fn now_ms() -> f64 {
+        #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+        {
+            js_sys::Date::now()
+        }
+
+        #[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+        {
+            use std::sync::OnceLock;
+            use std::time::Instant;
+
+            static ORIGIN: OnceLock<Instant> = OnceLock::new();
+            ORIGIN.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_now_ms_measures_elapsed_time() {
+            let start = now_ms();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            let end = now_ms();
+            assert!(end - start >= 2.0);
+        }
+    }
+}
+
+pub mod hash {
+    use std::hash::Hasher;
+
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    /// 64-bit FNV-1a with integers written little-endian: unlike
+    /// `std::hash::DefaultHasher`, its output is fixed by the algorithm, so
+    /// any toolchain and target produce the same hash.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Fnv1a(u64);
+
+    impl Default for Fnv1a {
+        fn default() -> Self {
+            Self(FNV_OFFSET_BASIS)
+        }
+    }
+
+    impl Hasher for Fnv1a {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 ^= *byte as u64;
+                self.0 = self.0.wrapping_mul(FNV_PRIME);
+            }
+        }
+
+        fn write_u8(&mut self, i: u8) {
+            self.write(&[i]);
+        }
+
+        fn write_u16(&mut self, i: u16) {
+            self.write(&i.to_le_bytes());
+        }
+
+        fn write_u32(&mut self, i: u32) {
+            self.write(&i.to_le_bytes());
+        }
+
+        fn write_u64(&mut self, i: u64) {
+            self.write(&i.to_le_bytes());
+        }
+
+        fn write_u128(&mut self, i: u128) {
+            self.write(&i.to_le_bytes());
+        }
+
+        fn write_usize(&mut self, i: usize) {
+            self.write_u64(i as u64);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_fnv1a_matches_reference_values() {
+            let hash = |bytes: &[u8]| {
+                let mut hasher = Fnv1a::default();
+                hasher.write(bytes);
+                hasher.finish()
+            };
+            assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+            assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+            assert_eq!(hash(b"foobar"), 0x8594_4171_f739_67e8);
+
+            // Integers hash as their little-endian bytes
+            let mut hasher = Fnv1a::default();
+            hasher.write_u32(0x6162_6364);
+            assert_eq!(hasher.finish(), hash(b"dcba"));
+        }
+    }
+}
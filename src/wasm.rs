@@ -94,6 +94,22 @@ impl WasmGame {
         }
     }
 
+    /// Player takes a crane off its ship (one action)
+    #[wasm_bindgen(js_name = unassignCrane)]
+    pub fn unassign_crane(&mut self, crane_id: usize) -> Result<(), JsValue> {
+        self.session
+            .player_unassign_crane(CraneId::new(crane_id))
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Take back the last dock or crane assignment of this turn
+    #[wasm_bindgen(js_name = undoLastAction)]
+    pub fn undo_last_action(&mut self) -> Result<(), JsValue> {
+        self.session
+            .undo_last_action()
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
     /// Process containers
     #[wasm_bindgen(js_name = "processContainers")]
     pub fn process_containers(&mut self) {
@@ -138,25 +154,61 @@ impl WasmGame {
     /// Get player port state as JSON
     #[wasm_bindgen(js_name = getPlayerPort)]
     pub fn get_player_port(&self) -> JsValue {
-        use crate::application::handlers::query_port_state;
-        let view = query_port_state(&self.session.player_port);
+        let view = self.session.port_state(&self.session.player_port);
         serde_wasm_bindgen::to_value(&view).unwrap_or(JsValue::NULL)
     }
 
     /// Get AI port state as JSON
     #[wasm_bindgen(js_name = getAiPort)]
     pub fn get_ai_port(&self) -> JsValue {
-        use crate::application::handlers::query_port_state;
-        let view = query_port_state(&self.session.ai_port);
+        let view = self.session.port_state(&self.session.ai_port);
         serde_wasm_bindgen::to_value(&view).unwrap_or(JsValue::NULL)
     }
 
+    /// Actions currently legal for the player (empty array = nothing to do but end the turn)
+    #[wasm_bindgen(js_name = legalActions)]
+    pub fn legal_actions(&self) -> JsValue {
+        let actions = self.session.legal_actions();
+        serde_wasm_bindgen::to_value(&actions).unwrap_or(JsValue::NULL)
+    }
+
+    /// Everything needed to render a frame in one call
+    #[wasm_bindgen(js_name = snapshot)]
+    pub fn snapshot(&self) -> JsValue {
+        let snapshot = self.session.snapshot();
+        serde_wasm_bindgen::to_value(&snapshot).unwrap_or(JsValue::NULL)
+    }
+
+    /// Warning about docked ships without a crane (null if there are none)
+    #[wasm_bindgen(js_name = getIdleDockedWarning)]
+    pub fn get_idle_docked_warning(&self) -> Option<String> {
+        self.session.idle_docked_warning()
+    }
+
     /// Get current turn number
     #[wasm_bindgen(js_name = getCurrentTurn)]
     pub fn get_current_turn(&self) -> u32 {
         self.session.current_turn
     }
 
+    /// Turns left before the turn limit
+    #[wasm_bindgen(js_name = getTurnsRemaining)]
+    pub fn get_turns_remaining(&self) -> u32 {
+        self.session.turns_remaining()
+    }
+
+    /// Fingerprint of the whole game state, as hex (compare client and server copies)
+    #[wasm_bindgen(js_name = getStateFingerprint)]
+    pub fn get_state_fingerprint(&self) -> String {
+        format!("{:016x}", self.session.state_fingerprint())
+    }
+
+    /// Share of the turn limit already played (0.0 to 1.0)
+    #[wasm_bindgen(js_name = getProgress)]
+    pub fn get_progress(&self) -> f64 {
+        self.session.progress()
+    }
+
     /// Check if game is over
     #[wasm_bindgen(js_name = isGameOver)]
     pub fn is_game_over(&self) -> bool {
@@ -166,7 +218,7 @@ impl WasmGame {
     /// Get winner (null if not game over)
     #[wasm_bindgen(js_name = getWinner)]
     pub fn get_winner(&self) -> Option<String> {
-        self.session.get_winner().map(|s| s.to_string())
+        self.session.outcome().winner_label().map(str::to_string)
     }
 
     /// Export replay as JSON
@@ -177,6 +229,20 @@ impl WasmGame {
             .map_err(|e| JsValue::from_str(&e))
     }
 
+    /// Get the turn-by-turn summary of the game
+    #[wasm_bindgen(js_name = getTurnLog)]
+    pub fn get_turn_log(&self) -> JsValue {
+        let log = self.session.turn_log();
+        serde_wasm_bindgen::to_value(&log).unwrap_or(JsValue::NULL)
+    }
+
+    /// Get the game's events as plain-language lines, oldest first
+    #[wasm_bindgen(js_name = getEventLog)]
+    pub fn get_event_log(&self) -> JsValue {
+        let log = self.session.event_log();
+        serde_wasm_bindgen::to_value(&log).unwrap_or(JsValue::NULL)
+    }
+
     /// Get active effects description
     #[wasm_bindgen(js_name = getActiveEffects)]
     pub fn get_active_effects(&self) -> JsValue {
@@ -2,8 +2,9 @@
 
 use port_game::application::commands::Command;
 use port_game::application::handlers::*;
-use port_game::domain::aggregates::Port;
+use port_game::domain::aggregates::{Port, DEFAULT_CRANE_SPEED};
 use port_game::domain::entities::Ship;
+use port_game::domain::events::DomainEvent;
 use port_game::domain::value_objects::*;
 use uuid::Uuid;
 
@@ -22,6 +23,12 @@ fn test_command_serialization() {
             crane_id: CraneId::new(0),
             ship_id: ShipId::new(1),
         },
+        Command::ReassignCrane {
+            player_id,
+            crane_id: CraneId::new(0),
+            new_ship_id: ShipId::new(2),
+        },
+        Command::ProcessContainers { player_id },
         Command::EndTurn { player_id },
         Command::AITakeTurn {
             player_id,
@@ -71,6 +78,71 @@ fn test_dock_ship_command_validation() {
     assert!(result.unwrap_err().contains("not found"));
 }
 
+#[test]
+fn test_dock_ship_rejects_already_docked_ship() {
+    let player_id = PlayerId::new();
+    let mut port = Port::new(player_id, 2, 2);
+    let aggregate_id = Uuid::new_v4();
+
+    port.ships
+        .insert(ShipId::new(1), Ship::new(ShipId::new(1), 30, 0.0));
+
+    let events = handle_dock_ship_command(
+        &port,
+        aggregate_id,
+        ShipId::new(1),
+        BerthId::new(0),
+        player_id,
+    )
+    .unwrap();
+    for event in &events {
+        port.apply_event(event);
+    }
+
+    // Berth 1 is free, but the ship is already at berth 0
+    let result = handle_dock_ship_command(
+        &port,
+        aggregate_id,
+        ShipId::new(1),
+        BerthId::new(1),
+        player_id,
+    );
+    assert!(result.is_err());
+    assert!(result.unwrap_err().contains("already docked"));
+    assert!(port.berths.get(&BerthId::new(1)).unwrap().is_free());
+}
+
+#[test]
+fn test_large_ship_docks_only_at_high_tide() {
+    let player_id = PlayerId::new();
+    let mut port = Port::new(player_id, 2, 2);
+    let aggregate_id = Uuid::new_v4();
+
+    port.ships
+        .insert(ShipId::new(1), Ship::new(ShipId::new(1), 50, 0.0));
+
+    port.tide = Tide::Low;
+    let result = handle_dock_ship_command(
+        &port,
+        aggregate_id,
+        ShipId::new(1),
+        BerthId::new(0),
+        player_id,
+    );
+    assert!(result.is_err());
+    assert!(result.unwrap_err().contains("Tide too low"));
+
+    port.tide = Tide::High;
+    let result = handle_dock_ship_command(
+        &port,
+        aggregate_id,
+        ShipId::new(1),
+        BerthId::new(0),
+        player_id,
+    );
+    assert!(result.is_ok());
+}
+
 #[test]
 fn test_assign_crane_command_validation() {
     let player_id = PlayerId::new();
@@ -121,6 +193,96 @@ fn test_assign_crane_command_validation() {
     assert!(result.unwrap_err().contains("already assigned"));
 }
 
+#[test]
+fn test_assign_crane_rejects_undocked_ship() {
+    let player_id = PlayerId::new();
+    let mut port = Port::new(player_id, 2, 2);
+    let aggregate_id = Uuid::new_v4();
+
+    // Ship is still waiting in the harbor
+    port.ships
+        .insert(ShipId::new(1), Ship::new(ShipId::new(1), 30, 0.0));
+
+    let result = handle_assign_crane_command(
+        &port,
+        aggregate_id,
+        CraneId::new(0),
+        ShipId::new(1),
+        player_id,
+    );
+    assert!(result.is_err());
+    assert!(result.unwrap_err().contains("not docked"));
+}
+
+#[test]
+fn test_process_containers_command_emits_events() {
+    let player_id = PlayerId::new();
+    let mut port = Port::new(player_id, 2, 2);
+    let aggregate_id = Uuid::new_v4();
+
+    port.ships
+        .insert(ShipId::new(1), Ship::new(ShipId::new(1), 25, 0.0));
+    port.ships
+        .insert(ShipId::new(2), Ship::new(ShipId::new(2), 30, 0.0));
+
+    for event in handle_dock_ship_command(
+        &port,
+        aggregate_id,
+        ShipId::new(1),
+        BerthId::new(0),
+        player_id,
+    )
+    .unwrap()
+    {
+        port.apply_event(&event);
+    }
+    for event in handle_assign_crane_command(
+        &port,
+        aggregate_id,
+        CraneId::new(0),
+        ShipId::new(1),
+        player_id,
+    )
+    .unwrap()
+    {
+        port.apply_event(&event);
+    }
+
+    let events = handle_process_containers_command(&port, aggregate_id, 1.0);
+
+    // Only the docked ship with a crane is processed, and the port is untouched
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+        DomainEvent::ContainerProcessed {
+            metadata,
+            crane_id,
+            ship_id,
+            player,
+            containers_remaining,
+            ..
+        } => {
+            assert_eq!(metadata.aggregate_id, aggregate_id);
+            assert_eq!(metadata.version, port.version() + 1);
+            assert_eq!(*crane_id, CraneId::new(0));
+            assert_eq!(*ship_id, ShipId::new(1));
+            assert_eq!(*player, player_id);
+            assert_eq!(*containers_remaining, 15);
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+    assert_eq!(port.ships[&ShipId::new(1)].containers_remaining, 25);
+
+    // Efficiency scales the amount processed
+    let events = handle_process_containers_command(&port, aggregate_id, 0.5);
+    match &events[0] {
+        DomainEvent::ContainerProcessed {
+            containers_remaining,
+            ..
+        } => assert_eq!(*containers_remaining, 20),
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
 #[test]
 fn test_query_port_state() {
     let player_id = PlayerId::new();
@@ -297,3 +459,275 @@ fn test_complex_workflow() {
     let ship2 = port.ships.get(&ShipId::new(2)).unwrap();
     assert_eq!(ship2.assigned_cranes.len(), 1);
 }
+
+#[test]
+fn test_assign_crane_respects_crane_cap() {
+    let player_id = PlayerId::new();
+    let mut port = Port::new(player_id, 2, 3);
+    port.max_cranes_per_ship = 2;
+    let aggregate_id = Uuid::new_v4();
+
+    port.ships
+        .insert(ShipId::new(1), Ship::new(ShipId::new(1), 50, 0.0));
+
+    let events = handle_dock_ship_command(
+        &port,
+        aggregate_id,
+        ShipId::new(1),
+        BerthId::new(0),
+        player_id,
+    )
+    .unwrap();
+    for event in &events {
+        port.apply_event(event);
+    }
+
+    // Assigning up to the cap succeeds
+    for crane in 0..2 {
+        let events = handle_assign_crane_command(
+            &port,
+            aggregate_id,
+            CraneId::new(crane),
+            ShipId::new(1),
+            player_id,
+        )
+        .unwrap();
+        for event in &events {
+            port.apply_event(event);
+        }
+    }
+
+    // One more crane is rejected
+    let result = handle_assign_crane_command(
+        &port,
+        aggregate_id,
+        CraneId::new(2),
+        ShipId::new(1),
+        player_id,
+    );
+    assert!(result.is_err());
+    assert!(result.unwrap_err().contains("Crane limit reached"));
+}
+
+#[test]
+fn test_query_comparison_stats() {
+    let mut player_port = Port::new(PlayerId::new(), 2, 2);
+    let mut ai_port = Port::new(PlayerId::new(), 2, 2);
+    player_port.current_time = 6.0;
+    ai_port.current_time = 6.0;
+
+    player_port
+        .ships
+        .insert(ShipId::new(1), Ship::new(ShipId::new(1), 20, 2.0));
+    player_port
+        .ships
+        .insert(ShipId::new(2), Ship::new(ShipId::new(2), 20, 4.0));
+
+    // One AI ship fully handled and undocked
+    let mut done = Ship::new(ShipId::new(3), 20, 0.0);
+    done.dock(BerthId::new(0));
+    ai_port.ships.insert(done.id, done);
+    ai_port.undock_ship(ShipId::new(3), BerthId::new(0));
+
+    let stats = query_comparison_stats(&player_port, &ai_port, ai_port.uncommitted_events());
+
+    assert_eq!(stats.player_avg_wait_time, 3.0);
+    assert_eq!(stats.ai_avg_wait_time, 0.0);
+    assert_eq!(stats.player_ships_processed, 0);
+    assert_eq!(stats.ai_ships_processed, 1);
+    assert_eq!(stats.player_score, player_port.calculate_score());
+}
+
+#[test]
+fn test_reassign_crane_emits_unassign_then_assign() {
+    let player_id = PlayerId::new();
+    let mut port = Port::new(player_id, 2, 1);
+    for (id, berth) in [(1, 0), (2, 1)] {
+        let mut ship = Ship::new(ShipId::new(id), 20, 0.0);
+        ship.dock(BerthId::new(berth));
+        port.ships.insert(ship.id, ship);
+    }
+    port.ships
+        .get_mut(&ShipId::new(1))
+        .unwrap()
+        .assign_crane(CraneId::new(0));
+    port.cranes
+        .get_mut(&CraneId::new(0))
+        .unwrap()
+        .assign(ShipId::new(1));
+
+    let events = handle_reassign_crane_command(
+        &port,
+        Uuid::new_v4(),
+        CraneId::new(0),
+        ShipId::new(2),
+        player_id,
+    )
+    .unwrap();
+
+    let types: Vec<_> = events.iter().map(|event| event.event_type()).collect();
+    assert_eq!(types, vec!["CraneUnassigned", "CraneAssigned"]);
+    assert_eq!(events[1].metadata().version, port.version() + 2);
+
+    // A free crane cannot be "reassigned"
+    let idle = Port::new(player_id, 1, 1);
+    assert!(handle_reassign_crane_command(
+        &idle,
+        Uuid::new_v4(),
+        CraneId::new(0),
+        ShipId::new(2),
+        player_id
+    )
+    .is_err());
+}
+
+/// Port with a 60-container ship and a 30-container one that arrived
+/// before it (despite its higher id), both docked with one crane each
+fn two_docked_ships(player_id: PlayerId, aggregate_id: Uuid) -> Port {
+    let mut port = Port::new(player_id, 2, 2);
+    port.ships
+        .insert(ShipId::new(1), Ship::new(ShipId::new(1), 60, 1.0));
+    port.ships
+        .insert(ShipId::new(2), Ship::new(ShipId::new(2), 30, 0.0));
+
+    for index in [1, 2] {
+        let mut events = handle_dock_ship_command(
+            &port,
+            aggregate_id,
+            ShipId::new(index),
+            BerthId::new(index - 1),
+            player_id,
+        )
+        .unwrap();
+        for event in &events {
+            port.apply_event(event);
+        }
+        events = handle_assign_crane_command(
+            &port,
+            aggregate_id,
+            CraneId::new(index - 1),
+            ShipId::new(index),
+            player_id,
+        )
+        .unwrap();
+        for event in &events {
+            port.apply_event(event);
+        }
+    }
+    port
+}
+
+/// (ship, containers left) of each `ContainerProcessed`, in event order
+fn processing_order(events: &[DomainEvent]) -> Vec<(usize, u32)> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            DomainEvent::ContainerProcessed {
+                ship_id,
+                containers_remaining,
+                ..
+            } => Some((ship_id.0, *containers_remaining)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_processing_policies_order_ships() {
+    let player_id = PlayerId::new();
+    let aggregate_id = Uuid::new_v4();
+    let port = two_docked_ships(player_id, aggregate_id);
+    let order = |batch_size, policy| {
+        processing_order(&handle_process_containers_with_policy(
+            &port,
+            aggregate_id,
+            1.0,
+            batch_size,
+            policy,
+        ))
+    };
+
+    // Earliest arrival first, whatever its size
+    let fifo = order(None, ProcessingPolicy::Fifo);
+    assert_eq!(
+        fifo.iter().map(|&(ship, _)| ship).collect::<Vec<_>>(),
+        [2, 1]
+    );
+
+    // The fullest ship gets its share unloaded first
+    let most_full = order(None, ProcessingPolicy::MostFull);
+    assert_eq!(
+        most_full.iter().map(|&(ship, _)| ship).collect::<Vec<_>>(),
+        [1, 2]
+    );
+
+    // Same amounts whatever the order, and the same events on every call
+    let mut sorted_fifo = fifo.clone();
+    sorted_fifo.sort();
+    assert_eq!(sorted_fifo, most_full);
+    assert_eq!(order(None, ProcessingPolicy::Fifo), fifo);
+    assert_eq!(order(None, ProcessingPolicy::MostFull), most_full);
+
+    // Round-robin alternates the ships batch by batch
+    let fair = order(Some(2), ProcessingPolicy::Fair);
+    let ships: Vec<_> = fair.iter().map(|&(ship, _)| ship).collect();
+    assert!(ships.len() >= 4);
+    assert_eq!(ships[..4], [1, 2, 1, 2]);
+}
+
+#[test]
+fn test_processing_policies_keep_each_ship_on_its_own_cranes() {
+    let player_id = PlayerId::new();
+    let aggregate_id = Uuid::new_v4();
+    // Three ships with one default-speed crane each (10 containers a turn):
+    // ship 1 needs only 4, its crane's spare capacity stays at its berth
+    let mut port = Port::new_with_crane_speeds(player_id, 3, &[DEFAULT_CRANE_SPEED; 3]);
+    for (index, containers) in [(1, 4), (2, 30), (3, 50)] {
+        port.ships.insert(
+            ShipId::new(index),
+            Ship::new(ShipId::new(index), containers, index as f64),
+        );
+        let mut events = handle_dock_ship_command(
+            &port,
+            aggregate_id,
+            ShipId::new(index),
+            BerthId::new(index - 1),
+            player_id,
+        )
+        .unwrap();
+        for event in &events {
+            port.apply_event(event);
+        }
+        events = handle_assign_crane_command(
+            &port,
+            aggregate_id,
+            CraneId::new(index - 1),
+            ShipId::new(index),
+            player_id,
+        )
+        .unwrap();
+        for event in &events {
+            port.apply_event(event);
+        }
+    }
+
+    let left = |policy| {
+        let mut left: Vec<_> = processing_order(&handle_process_containers_with_policy(
+            &port,
+            aggregate_id,
+            1.0,
+            None,
+            policy,
+        ));
+        left.sort();
+        left
+    };
+
+    for policy in [
+        ProcessingPolicy::Fifo,
+        ProcessingPolicy::MostFull,
+        ProcessingPolicy::Fair,
+    ] {
+        assert_eq!(left(policy), [(1, 0), (2, 20), (3, 40)], "{:?}", policy);
+    }
+}
@@ -221,7 +221,7 @@ fn test_query_port_state() {
 
 mod tests {
     use port_game::domain::value_objects::{BerthId, CraneId, PlayerId, ShipId};
-    use port_game::game::{EventGenerator, GameMode, GameSession};
+    use port_game::game::{EventGenerator, GameMode, GameSession, SpawnConfig};
 
     #[test]
     fn test_free_completed_ships() {
@@ -285,14 +285,23 @@ mod tests {
     fn test_score_persists_after_ship_completion() {
         let player_id = PlayerId::new();
         let ai_id = PlayerId::new();
-        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+        // 19 standard containers: two rounds with one default-speed crane (10, then 9 once worn)
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 19,
+                max_containers: 19,
+                reefer_percent: 0,
+                hazmat_percent: 0,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
         session.event_generator = EventGenerator::new(0.0);
 
         session.spawn_ships(1);
 
         let ship_id = ShipId::new(0);
         let berth_id = BerthId::new(0);
-        let crane_id = CraneId::new(0);
+        let crane_id = CraneId::new(1);
 
         session.player_dock_ship(ship_id, berth_id).unwrap();
         session.player_assign_crane(crane_id, ship_id).unwrap();
@@ -1,6 +1,9 @@
 //! Tests d'intégration exhaustifs du gameplay du Port Game
+use port_game::domain::entities::LARGE_SHIP_CONTAINERS;
+use port_game::domain::events::DomainEvent;
 use port_game::domain::value_objects::{BerthId, CraneId, PlayerId};
-use port_game::game::{EventGenerator, GameMode, GameSession};
+use port_game::game::{EventGenerator, GameMode, GameSession, SpawnConfig};
+use port_game::infrastructure::{EventStore, ReplayEnvelope};
 
 #[cfg(test)]
 mod test {
@@ -17,12 +20,7 @@ mod test {
 
         // Récupérer tous les IDs nécessaires d'abord
         let berth_id = *session.player_port.berths.iter().next().unwrap().0;
-        let ship_ids: Vec<_> = session
-            .player_port
-            .ships
-            .iter()
-            .map(|(id, _)| *id)
-            .collect();
+        let ship_ids: Vec<_> = session.player_port.ships.keys().copied().collect();
         let crane_id = *session.player_port.cranes.iter().next().unwrap().0;
 
         // Tests d'amarrage
@@ -157,18 +155,8 @@ mod test {
         println!("Test démarré");
 
         // Collecter les IDs des installations portuaires
-        let berth_ids: Vec<_> = session
-            .player_port
-            .berths
-            .iter()
-            .map(|(id, _)| *id)
-            .collect();
-        let crane_ids: Vec<_> = session
-            .player_port
-            .cranes
-            .iter()
-            .map(|(id, _)| *id)
-            .collect();
+        let berth_ids: Vec<_> = session.player_port.berths.keys().copied().collect();
+        let crane_ids: Vec<_> = session.player_port.cranes.keys().copied().collect();
 
         // Ajouter les premiers navires
         session.spawn_ships(5);
@@ -304,12 +292,7 @@ mod test {
         // Préparation du scénario
         session.spawn_ships(2);
         let berth_id = *session.player_port.berths.iter().next().unwrap().0;
-        let ship_ids: Vec<_> = session
-            .player_port
-            .ships
-            .iter()
-            .map(|(id, _)| *id)
-            .collect();
+        let ship_ids: Vec<_> = session.player_port.ships.keys().copied().collect();
         let crane_id = *session.player_port.cranes.iter().next().unwrap().0;
 
         // Amarrer un navire et assigner une grue
@@ -372,17 +355,19 @@ mod test {
     fn test_crane_freed_and_reassignable_after_end_turn() {
         let player_id = PlayerId::new();
         let ai_id = PlayerId::new();
-        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+        // Petits navires : la marée basse ne bloque pas l'accostage
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+            .with_spawn_config(SpawnConfig {
+                min_containers: 20,
+                max_containers: LARGE_SHIP_CONTAINERS,
+                ..SpawnConfig::default()
+            })
+            .unwrap();
 
         // Ajouter deux navires
         session.spawn_ships(2);
         let berth_id = *session.player_port.berths.iter().next().unwrap().0;
-        let ship_ids: Vec<_> = session
-            .player_port
-            .ships
-            .iter()
-            .map(|(id, _)| *id)
-            .collect();
+        let ship_ids: Vec<_> = session.player_port.ships.keys().copied().collect();
         let crane_id = *session.player_port.cranes.iter().next().unwrap().0;
 
         // Premier tour : dock et assigne la grue au premier navire
@@ -505,4 +490,48 @@ mod test {
             "Berth should be free after auto undock of empty ship"
         );
     }
+
+    #[test]
+    fn test_freed_crane_and_undock_are_in_replay() {
+        let player_id = PlayerId::new();
+        let ai_id = PlayerId::new();
+        let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+
+        session.spawn_ships(1);
+        let berth_id = *session.player_port.berths.iter().next().unwrap().0;
+        let ship_id = *session.player_port.ships.iter().next().unwrap().0;
+        let crane_id = *session.player_port.cranes.iter().next().unwrap().0;
+        session.player_dock_ship(ship_id, berth_id).unwrap();
+        session.player_assign_crane(crane_id, ship_id).unwrap();
+
+        // Libération manuelle, hors de la boucle de fin de tour
+        session.player_port.free_crane(crane_id);
+        session.player_port.undock_ship(ship_id, berth_id);
+
+        let json = session.export_replay().unwrap();
+        let replay = ReplayEnvelope::from_json(&json).unwrap();
+        let unassigned = replay
+            .events
+            .iter()
+            .find(|e| matches!(e, DomainEvent::CraneUnassigned { .. }))
+            .expect("CraneUnassigned missing from the replay");
+        let undocked = replay
+            .events
+            .iter()
+            .find(|e| matches!(e, DomainEvent::ShipUndocked { .. }))
+            .expect("ShipUndocked missing from the replay");
+
+        assert_eq!(unassigned.metadata().aggregate_id, session.session_id);
+        assert_eq!(undocked.metadata().aggregate_id, session.session_id);
+
+        // Une fois persistés, ils restent dans le flux de la session
+        session.commit_events();
+        let stored = session.event_store.load(session.session_id).unwrap();
+        assert!(stored
+            .iter()
+            .any(|e| matches!(e, DomainEvent::CraneUnassigned { .. })));
+        assert!(stored
+            .iter()
+            .any(|e| matches!(e, DomainEvent::ShipUndocked { .. })));
+    }
 }
@@ -1,6 +1,7 @@
 //! Tests d'intégration pour port_game
 use port_game::domain::value_objects::PlayerId;
 use port_game::game::{GameMode, GameSession};
+use port_game::infrastructure::{ReplayEnvelope, REPLAY_FORMAT_VERSION};
 
 #[test]
 fn test_game_session_initialization() {
@@ -53,8 +54,10 @@ fn test_event_sourcing_replay() {
     assert!(replay_json.contains("ShipArrived"));
 
     // Parse JSON to verify it's valid
-    let events: Vec<port_game::domain::events::DomainEvent> =
-        serde_json::from_str(&replay_json).expect("Failed to parse replay JSON");
+    let replay = ReplayEnvelope::from_json(&replay_json).expect("Failed to parse replay JSON");
+    assert_eq!(replay.format_version, REPLAY_FORMAT_VERSION);
+    assert_eq!(replay.session_id, session.session_id);
+    let events = replay.events;
 
     // Should have: 1 GameStarted + 1 TurnStarted + 3 ShipArrived = 5 events
     assert!(events.len() >= 5);
@@ -119,3 +122,41 @@ fn test_event_store_isolation() {
     assert!(replay1.contains("GameStarted"));
     assert!(replay2.contains("GameStarted"));
 }
+
+#[test]
+fn test_turn_ended_events_delimit_turns() {
+    use port_game::domain::events::DomainEvent;
+    use port_game::game::EventGenerator;
+
+    let player_id = PlayerId::new();
+    let ai_id = PlayerId::new();
+
+    let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+    session.event_generator = EventGenerator::new(0.0);
+    session.start_turn();
+    session.end_turn();
+    session.end_turn();
+
+    let replay_json = session.export_replay().unwrap();
+    let events = ReplayEnvelope::from_json(&replay_json).unwrap().events;
+
+    let markers: Vec<(&str, u32)> = events
+        .iter()
+        .filter_map(|event| match event {
+            DomainEvent::TurnStarted { turn_number, .. } => Some(("start", *turn_number)),
+            DomainEvent::TurnEnded { turn_number, .. } => Some(("end", *turn_number)),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        markers,
+        vec![
+            ("start", 1),
+            ("end", 1),
+            ("start", 2),
+            ("end", 2),
+            ("start", 3)
+        ]
+    );
+}
@@ -160,7 +160,7 @@ fn test_event_descriptions() {
 #[test]
 fn test_events_expire_after_duration() {
     let player_id = PlayerId::new();
-  	let ai_id = PlayerId::new();
+    let ai_id = PlayerId::new();
     let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
 
     // Désactiver la génération aléatoire pour un test déterministe
@@ -194,7 +194,7 @@ fn test_container_processing_with_modified_efficiency() {
     session.spawn_ships(1);
     let ship_id = *session.player_port.ships.iter().next().unwrap().0;
     let berth_id = *session.player_port.berths.iter().next().unwrap().0;
-    let crane_id = *session.player_port.cranes.iter().next().unwrap().0;
+    let crane_id = CraneId::new(1); // Grue à vitesse par défaut
 
     // Docker le navire et assigner une grue
     session.player_dock_ship(ship_id, berth_id).unwrap();
@@ -237,3 +237,105 @@ fn test_container_processing_with_modified_efficiency() {
         "Devrait traiter 5 conteneurs avec l'efficacité réduite de 50%"
     );
 }
+
+#[test]
+fn test_storm_on_player_port_does_not_slow_ai_port() {
+    use port_game::domain::value_objects::{BerthId, ShipId};
+    use port_game::game::SpawnConfig;
+
+    let player_id = PlayerId::new();
+    let ai_id = PlayerId::new();
+    let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id)
+        .with_spawn_config(SpawnConfig {
+            min_containers: 30,
+            max_containers: 30,
+            ..SpawnConfig::default()
+        })
+        .unwrap();
+    session.event_generator = EventGenerator::new(0.0);
+
+    session.spawn_ships(1);
+    let ship_id = ShipId::new(0);
+    session.player_dock_ship(ship_id, BerthId::new(0)).unwrap();
+    session
+        .player_assign_crane(CraneId::new(1), ship_id)
+        .unwrap();
+    session
+        .ai_port
+        .ships
+        .get_mut(&ship_id)
+        .unwrap()
+        .dock(BerthId::new(0));
+    session
+        .ai_port
+        .ships
+        .get_mut(&ship_id)
+        .unwrap()
+        .assign_crane(CraneId::new(1));
+    session
+        .ai_port
+        .cranes
+        .get_mut(&CraneId::new(1))
+        .unwrap()
+        .assign(ship_id);
+
+    // The storm only hits the player's harbor
+    session
+        .active_events
+        .push(ActiveEvent::new(RandomEvent::Storm {
+            duration_turns: 2,
+            efficiency_penalty: 0.5,
+        }));
+    session.process_random_events();
+    assert_eq!(session.crane_efficiency_modifier, 0.5);
+    assert_eq!(session.ai_crane_efficiency_modifier, 1.0);
+
+    session.process_containers();
+
+    assert_eq!(session.player_port.ships[&ship_id].containers_remaining, 25);
+    assert_eq!(session.ai_port.ships[&ship_id].containers_remaining, 20);
+}
+
+#[test]
+fn test_disabled_events_only_let_active_effects_expire() {
+    let player_id = PlayerId::new();
+    let ai_id = PlayerId::new();
+    let mut session = GameSession::new(GameMode::VersusAI, player_id, ai_id);
+    assert!(session.events_enabled());
+
+    // Would fire every turn if generation were still on
+    session.event_generator = EventGenerator::new(1.0);
+    session.set_events_enabled(false);
+    session
+        .active_events
+        .push(ActiveEvent::new(RandomEvent::Storm {
+            duration_turns: 2,
+            efficiency_penalty: 0.5,
+        }));
+
+    let mut triggered = Vec::new();
+    for _ in 0..10 {
+        triggered.extend(session.process_random_events());
+    }
+
+    assert!(triggered.is_empty());
+    assert!(
+        session.active_events.is_empty(),
+        "storm should have expired"
+    );
+    assert!(session.ai_active_events.is_empty());
+    assert_eq!(session.crane_efficiency_modifier, 1.0);
+    assert!(!session
+        .export_replay()
+        .unwrap()
+        .contains("RandomEventTriggered"));
+}
+
+#[test]
+fn test_tutorial_starts_without_random_events() {
+    let tutorial = GameSession::new(GameMode::Tutorial, PlayerId::new(), PlayerId::new());
+    let versus = GameSession::new(GameMode::VersusAI, PlayerId::new(), PlayerId::new());
+
+    assert!(!tutorial.events_enabled());
+    assert!(versus.events_enabled());
+}
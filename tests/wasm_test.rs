@@ -208,7 +208,7 @@ mod native_tests {
         // Process all containers
         for _ in 0..10 {
             session.process_containers();
-            if session.player_port.ships.get(&ship_id).is_none() {
+            if !session.player_port.ships.contains_key(&ship_id) {
                 // Ship was removed after completion
                 break;
             }
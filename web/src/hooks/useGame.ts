@@ -1,5 +1,5 @@
 import { useState, useEffect, useCallback } from 'react';
-import type { GameState, PortState } from '../types/game';
+import type { GameSnapshot, GameState } from '../types/game';
 import { WasmGame } from 'port_game';
 
 export function useGame() {
@@ -11,22 +11,17 @@ export function useGame() {
   // Update game state from WASM
   const updateGameState = useCallback((g: any) => {
     try {
-      const playerPort = g.getPlayerPort() as PortState;
-      const aiPort = g.getAiPort() as PortState;
-      const currentTurn = g.getCurrentTurn();
-      const isGameOver = g.isGameOver();
-      const winner = g.getWinner();
+      const snapshot = g.snapshot() as GameSnapshot;
       const activeEvents = g.processRandomEvents() as any[];
-      const activeEffects = g.getActiveEffects() as any[];
 
       setGameState({
-        playerPort,
-        aiPort,
-        currentTurn,
-        isGameOver,
-        winner: winner || undefined,
+        playerPort: snapshot.player_port,
+        aiPort: snapshot.ai_port,
+        currentTurn: snapshot.current_turn,
+        isGameOver: snapshot.is_game_over,
+        winner: snapshot.winner || undefined,
         activeEvents,
-        activeEffects
+        activeEffects: snapshot.active_effects as any[]
       });
     } catch (err) {
       setError(`Failed to update game state: ${err}`);
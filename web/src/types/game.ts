@@ -8,12 +8,23 @@ export interface Ship {
   is_docked: boolean;
   docked_at?: number;
   assigned_cranes: number[];
+  priority: boolean;
+  cargo: CargoMix;
+  eta_turns?: number;
+  progress: number; // Share of the cargo unloaded, 0 to 1
+}
+
+export interface CargoMix {
+  standard: number;
+  reefer: number;
+  hazmat: number;
 }
 
 export interface Berth {
   id: number;
   is_free: boolean;
   occupied_by?: number;
+  docking_cost: number;
 }
 
 export interface Crane {
@@ -21,6 +32,7 @@ export interface Crane {
   is_free: boolean;
   assigned_to?: number;
   processing_speed: number;
+  wear: number;
 }
 
 export interface PortState {
@@ -30,8 +42,27 @@ export interface PortState {
   cranes: Crane[];
   score: number;
   current_time: number;
+  tide: 'High' | 'Low';
+}
+
+// Returned by WasmGame.snapshot()
+export interface GameSnapshot {
+  player_port: PortState;
+  ai_port: PortState;
+  current_turn: number;
+  is_game_over: boolean;
+  winner?: string;
+  active_effects: string[];
+  crane_efficiency: number;
+  action_points: number;
 }
 
+// Serialized MCTSAction (Pass is never listed as legal)
+export type LegalAction =
+  | { DockShip: { ship_id: number; berth_id: number } }
+  | { AssignCrane: { crane_id: number; ship_id: number } }
+  | { UnassignCrane: { crane_id: number } };
+
 export interface RandomEvent {
   type: string;
   description: string;
@@ -5,15 +5,22 @@ declare module '@pkg/port_game' {
         spawnShips(count: number): void;
         dockShip(shipId: number, berthId: number): Promise<void>;
         assignCrane(craneId: number, shipId: number): Promise<void>;
+        unassignCrane(craneId: number): Promise<void>;
+        undoLastAction(): void;
         processContainers(): void;
         aiTakeTurn(): void;
         processRandomEvents(): string[];
         getPlayerPort(): any;
         getAiPort(): any;
         getCurrentTurn(): number;
+        getStateFingerprint(): string;
         isGameOver(): boolean;
         getWinner(): string | null;
         exportReplay(): Promise<string>;
+        getTurnLog(): any[];
+        getEventLog(): string[];
+        legalActions(): any[];
+        snapshot(): any;
         getActiveEffects(): any[];
         getCraneEfficiency(): number;
     }